@@ -6,6 +6,7 @@ use bevy::prelude::*;
 
 use crate::state::GameState;
 use crate::util::{Facing, FacingX, FacingY};
+use crate::common::UpdateStage;
 
 
 pub struct AnimationPlugin;
@@ -13,9 +14,9 @@ pub struct AnimationPlugin;
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
        app.add_event::<AnimationChangeEvent>().add_system_set(
-           SystemSet::on_update(GameState::Gameplay)
-               .with_system(animation_tick)
+           UpdateStage::Animation.on_update(GameState::Gameplay)
                .with_system(handle_animation_change_events)
+               .with_system(animation_tick.after(handle_animation_change_events))
                .with_system(flip_sprite_on_direction)
        );
     }
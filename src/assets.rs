@@ -1,10 +1,13 @@
 use crate::state::GameState;
 
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 
 use std::collections::HashMap;
 use crate::anim::Animation;
 use crate::anim::map::AnimationMap;
+use crate::enemies::registry::EnemyAssets;
+use crate::enemies::stats::EnemyStatsHandle;
 use crate::ui::hud::{DASH_CD_CHUNKS, PLAYER_HUD_DISPLAY_CHUNKS, SHOOT_CD_CHUNKS, SLASH_CD_CHUNKS};
 
 #[derive(Resource, Default, Debug)]
@@ -117,8 +120,8 @@ pub struct FlowerEnemyAssets {
     pub map: AnimationMap,
 }
 
-impl FlowerEnemyAssets {
-    pub fn load(
+impl EnemyAssets for FlowerEnemyAssets {
+    fn load(
         asset_server: Res<AssetServer>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
         mut assets: ResMut<FlowerEnemyAssets>,
@@ -163,8 +166,8 @@ pub struct PumpkinEnemyAssets {
     pub bullet: Animation
 }
 
-impl PumpkinEnemyAssets {
-    pub fn load(
+impl EnemyAssets for PumpkinEnemyAssets {
+    fn load(
         asset_server: Res<AssetServer>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
         mut assets: ResMut<PumpkinEnemyAssets>,
@@ -219,8 +222,8 @@ pub struct DandelionEnemyAssets {
     pub map: AnimationMap,
 }
 
-impl DandelionEnemyAssets {
-    pub fn load(
+impl EnemyAssets for DandelionEnemyAssets {
+    fn load(
         asset_server: Res<AssetServer>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
         mut assets: ResMut<DandelionEnemyAssets>,
@@ -259,8 +262,8 @@ pub struct TumbleweedEnemyAssets {
     pub map: AnimationMap,
 }
 
-impl TumbleweedEnemyAssets {
-    pub fn load(
+impl EnemyAssets for TumbleweedEnemyAssets {
+    fn load(
         asset_server: Res<AssetServer>,
         mut texture_atlases: ResMut<Assets<TextureAtlas>>,
         mut assets: ResMut<TumbleweedEnemyAssets>,
@@ -292,6 +295,62 @@ impl TumbleweedEnemyAssets {
     }
 }
 
+#[derive(Resource, Default, Debug)]
+pub struct MushroomEnemyAssets {
+    pub map: AnimationMap,
+}
+
+impl EnemyAssets for MushroomEnemyAssets {
+    fn load(
+        asset_server: Res<AssetServer>,
+        mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+        mut assets: ResMut<MushroomEnemyAssets>,
+    ) {
+        const SIZE: Vec2 = Vec2::new(32., 32.);
+        let sheet = asset_server.load("art/enemies/Mushroom-Sheet.png");
+
+        let mut anims = HashMap::new();
+
+        // IDLE
+        let idle_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 2, 1, None, None);
+        let idle_handle = texture_atlases.add(idle_atlas);
+        let idle_anim = Animation::new("IDLE".to_string(), idle_handle.clone(), 0.75);
+        anims.insert(idle_anim.name.clone(), idle_anim);
+
+        // MOVE
+        let move_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 4, 1, None, Some(Vec2::new(2.0, 0.0) * SIZE));
+        let move_handle = texture_atlases.add(move_atlas);
+        let move_anim = Animation::new("MOVE".to_string(), move_handle.clone(), 0.1);
+        anims.insert(move_anim.name.clone(), move_anim);
+
+        // BURROW
+        let burrow_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 5, 1, None, Some(Vec2::new(6.0, 0.0) * SIZE));
+        let burrow_handle = texture_atlases.add(burrow_atlas);
+        let burrow_anim = Animation::new("BURROW".to_string(), burrow_handle.clone(), 0.08);
+        anims.insert(burrow_anim.name.clone(), burrow_anim);
+
+        // BURROWED, the mound of dirt that follows the player around
+        let burrowed_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 2, 1, None, Some(Vec2::new(11.0, 0.0) * SIZE));
+        let burrowed_handle = texture_atlases.add(burrowed_atlas);
+        let burrowed_anim = Animation::new("BURROWED".to_string(), burrowed_handle.clone(), 0.15);
+        anims.insert(burrowed_anim.name.clone(), burrowed_anim);
+
+        // EMERGE
+        let emerge_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 5, 1, None, Some(Vec2::new(13.0, 0.0) * SIZE));
+        let emerge_handle = texture_atlases.add(emerge_atlas);
+        let emerge_anim = Animation::new("EMERGE".to_string(), emerge_handle.clone(), 0.08);
+        anims.insert(emerge_anim.name.clone(), emerge_anim);
+
+        // DEATH
+        let death_atlas = TextureAtlas::from_grid(sheet.clone(), SIZE, 7, 1, None, Some(Vec2::new(18.0, 0.0) * SIZE));
+        let death_handle = texture_atlases.add(death_atlas);
+        let death_anim = Animation::new("DEATH".to_string(), death_handle.clone(), 0.1);
+        anims.insert(death_anim.name.clone(), death_anim);
+
+        assets.map = AnimationMap::new(anims);
+    }
+}
+
 #[derive(Resource, Default, Debug)]
 pub struct ExplosionAssets {
     pub anims: HashMap<String, Animation>
@@ -510,6 +569,7 @@ impl IndicatorAssets {
 #[derive(Resource, Default, Debug)]
 pub struct LevelAssets {
     pub anims: AnimationMap,
+    pub tileset: Handle<Image>,
 }
 
 impl LevelAssets {
@@ -534,6 +594,9 @@ impl LevelAssets {
             (vines_anim.name.clone(), vines_anim),
             (vines_disintegrate_anim.name.clone(), vines_disintegrate_anim)
         ]));
+
+        // the LDtk tileset, for platforms that can't be part of a tile layer
+        assets.tileset = asset_server.load("tileset/cavernas/cavernas.png");
     }
 }
 
@@ -677,6 +740,31 @@ impl ShopAssets {
     }
 }
 
+// Loaded by `GameAudioPlugin`, the headless app has no audio
+#[derive(Resource, Default, Debug)]
+pub struct AudioAssets {
+    pub hit: Handle<AudioSource>,
+    pub explosion: Handle<AudioSource>,
+    pub smoke: Handle<AudioSource>,
+    pub coin: Handle<AudioSource>,
+    pub purchase: Handle<AudioSource>,
+    pub boss_stage: Handle<AudioSource>,
+}
+
+impl AudioAssets {
+    pub fn load(
+        asset_server: Res<AssetServer>,
+        mut assets: ResMut<AudioAssets>,
+    ) {
+        assets.hit = asset_server.load("audio/sfx/Hit.wav");
+        assets.explosion = asset_server.load("audio/sfx/Explosion.wav");
+        assets.smoke = asset_server.load("audio/sfx/Smoke.wav");
+        assets.coin = asset_server.load("audio/sfx/Coin.wav");
+        assets.purchase = asset_server.load("audio/sfx/Purchase.wav");
+        assets.boss_stage = asset_server.load("audio/sfx/BossStage.wav");
+    }
+}
+
 
 pub struct AssetLoaderPlugin;
 
@@ -684,10 +772,6 @@ impl Plugin for AssetLoaderPlugin {
     fn build(&self, app: &mut App) {
         app
             .init_resource::<PlayerAssets>()
-            .init_resource::<FlowerEnemyAssets>()
-            .init_resource::<PumpkinEnemyAssets>()
-            .init_resource::<DandelionEnemyAssets>()
-            .init_resource::<TumbleweedEnemyAssets>()
             .init_resource::<ExplosionAssets>()
             .init_resource::<SporeAssets>()
             .init_resource::<CoinAssets>()
@@ -696,16 +780,13 @@ impl Plugin for AssetLoaderPlugin {
             .init_resource::<UiAssets>()
             .init_resource::<ShopAssets>()
             .init_resource::<LevelAssets>()
+            // enemy assets are loaded by `register_enemy`, see enemies/registry.rs
 
             .add_state(GameState::AssetLoading)
             .add_startup_system_set(
                 SystemSet::new()
                     .label("assets")
                     .with_system(PlayerAssets::load)
-                    .with_system(FlowerEnemyAssets::load)
-                    .with_system(PumpkinEnemyAssets::load)
-                    .with_system(DandelionEnemyAssets::load)
-                    .with_system(TumbleweedEnemyAssets::load)
                     .with_system(ExplosionAssets::load)
                     .with_system(SporeAssets::load)
                     .with_system(CoinAssets::load)
@@ -716,10 +797,27 @@ impl Plugin for AssetLoaderPlugin {
                     .with_system(LevelAssets::load)
             )
 
-            .add_startup_system(enter_main_menu.after("assets"));
+            .add_system_set(
+                SystemSet::on_update(GameState::AssetLoading)
+                    .with_system(enter_main_menu)
+            );
     }
 }
 
-fn enter_main_menu(mut state: ResMut<State<GameState>>) {
-    state.overwrite_set(GameState::MainMenu).unwrap();
+// Enemies can't spawn without their stats, so the game waits for those. The
+// rest of the assets pop in as they load.
+fn enter_main_menu(
+    mut state: ResMut<State<GameState>>,
+    asset_server: Res<AssetServer>,
+    stats: Res<EnemyStatsHandle>
+) {
+    match asset_server.get_load_state(&stats.0) {
+        LoadState::Loaded => {}
+        LoadState::Failed => {
+            error!("Failed to load enemies.stats.toml, no enemies will spawn");
+        }
+        _ => return
+    }
+
+    state.set(GameState::MainMenu).unwrap();
 }
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use crate::assets::AudioAssets;
+
+pub mod sfx;
+pub mod music;
+
+#[derive(Resource, serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct VolumeSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for VolumeSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.7,
+            sfx: 1.0,
+        }
+    }
+}
+
+impl VolumeSettings {
+    pub fn music_volume(&self) -> f32 {
+        self.master * self.music
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.master * self.sfx
+    }
+}
+
+pub struct GameAudioPlugin;
+
+impl Plugin for GameAudioPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<VolumeSettings>()
+            .init_resource::<AudioAssets>()
+            .add_startup_system(AudioAssets::load);
+
+        sfx::register_sfx(app);
+        music::register_music(app);
+    }
+}
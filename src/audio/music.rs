@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy::audio::AudioSink;
+use bevy_ecs_ldtk::prelude::*;
+use crate::audio::VolumeSettings;
+use crate::level::transition::TransitionSetupEvent;
+use crate::level::util::LdtkFields;
+use crate::state::GameState;
+
+// Each LDtk level picks its background track through the "Music" level
+// field, a path relative to the assets folder. Levels that share a track
+// keep it playing across the transition instead of restarting it.
+pub const MUSIC_FIELD: &str = "Music";
+
+#[derive(Resource, Default)]
+pub struct MusicPlayer {
+    pub track: Option<String>,
+    sink: Option<Handle<AudioSink>>
+}
+
+pub fn register_music(app: &mut App) {
+    app
+        .init_resource::<MusicPlayer>()
+        .add_system_set(
+            SystemSet::on_update(GameState::LevelTransition)
+                .with_system(select_level_music)
+        )
+        .add_system(update_music_volume);
+}
+
+pub fn level_music(ldtk: &LdtkAsset, level: &str) -> Option<String> {
+    let lvl = ldtk.get_level(&LevelSelection::Identifier(level.to_string()))?;
+
+    lvl.field_string(MUSIC_FIELD).ok()
+}
+
+fn select_level_music(
+    mut setup: EventReader<TransitionSetupEvent>,
+    levels: Query<&Handle<LdtkAsset>>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    sinks: Res<Assets<AudioSink>>,
+    volume: Res<VolumeSettings>,
+    mut music: ResMut<MusicPlayer>
+) {
+    if setup.is_empty() || levels.is_empty() {
+        return;
+    }
+
+    let ldtk = match ldtk_assets.get(levels.single()) {
+        Some(ldtk) => ldtk,
+        None => return
+    };
+
+    for ev in setup.iter() {
+        let track = level_music(ldtk, &ev.new_level);
+        if track == music.track {
+            continue;
+        }
+
+        if let Some(sink) = music.sink.take() {
+            if let Some(sink) = sinks.get(&sink) {
+                sink.stop();
+            }
+        }
+
+        music.track = track.clone();
+
+        if let Some(path) = track {
+            let weak = audio.play_with_settings(
+                asset_server.load(&path),
+                PlaybackSettings::LOOP.with_volume(volume.music_volume())
+            );
+
+            music.sink = Some(sinks.get_handle(weak));
+        }
+    }
+}
+
+fn update_music_volume(
+    volume: Res<VolumeSettings>,
+    music: Res<MusicPlayer>,
+    sinks: Res<Assets<AudioSink>>
+) {
+    if !volume.is_changed() {
+        return;
+    }
+
+    if let Some(sink) = music.sink.as_ref().and_then(|sink| sinks.get(sink)) {
+        sink.set_volume(volume.music_volume());
+    }
+}
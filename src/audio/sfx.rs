@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+use crate::assets::AudioAssets;
+use crate::audio::VolumeSettings;
+use crate::bossfight::stage::BossStage;
+use crate::coin::pickup::CoinPickupEvent;
+use crate::combat::{CombatEvent, ExplosionEvent};
+use crate::fx::smoke::SmokeEvent;
+use crate::shop::ShopPurchaseEvent;
+
+// These run outside of any GameState, the shop sends its purchases while
+// the game is in the shop menu
+pub fn register_sfx(app: &mut App) {
+    app
+        .add_system(play_hit_sfx)
+        .add_system(play_explosion_sfx)
+        .add_system(play_smoke_sfx)
+        .add_system(play_coin_sfx)
+        .add_system(play_purchase_sfx)
+        .add_system(play_boss_stage_sfx);
+}
+
+pub fn play_sfx(audio: &Audio, sound: &Handle<AudioSource>, volume: &VolumeSettings) {
+    let volume = volume.sfx_volume();
+    if volume <= 0.0 {
+        return;
+    }
+
+    audio.play_with_settings(sound.clone(), PlaybackSettings::ONCE.with_volume(volume));
+}
+
+// A slash can hit several enemies on the same frame, only play one sound
+// for all of them so they don't stack up into something deafening
+fn play_hit_sfx(
+    events: EventReader<CombatEvent>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    events.clear();
+    play_sfx(&audio, &assets.hit, &volume);
+}
+
+fn play_explosion_sfx(
+    events: EventReader<ExplosionEvent>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    events.clear();
+    play_sfx(&audio, &assets.explosion, &volume);
+}
+
+fn play_smoke_sfx(
+    events: EventReader<SmokeEvent>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    events.clear();
+    play_sfx(&audio, &assets.smoke, &volume);
+}
+
+fn play_coin_sfx(
+    events: EventReader<CoinPickupEvent>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    if events.is_empty() {
+        return;
+    }
+
+    events.clear();
+    play_sfx(&audio, &assets.coin, &volume);
+}
+
+fn play_purchase_sfx(
+    mut events: EventReader<ShopPurchaseEvent>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    for _ in events.iter() {
+        play_sfx(&audio, &assets.purchase, &volume);
+    }
+}
+
+fn play_boss_stage_sfx(
+    stages: Query<&BossStage, Changed<BossStage>>,
+    audio: Res<Audio>,
+    assets: Res<AudioAssets>,
+    volume: Res<VolumeSettings>
+) {
+    for stage in stages.iter() {
+        // the boss is spawned in the waiting stage, that's not a change
+        if *stage == BossStage::Waiting {
+            continue;
+        }
+
+        play_sfx(&audio, &assets.boss_stage, &volume);
+    }
+}
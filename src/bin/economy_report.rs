@@ -0,0 +1,243 @@
+// Walks the levels in the order they're played and prints how many coins
+// each one pays out against the shop's upgrade prices, then how long every
+// enemy takes to kill at each upgrade level:
+//
+//     cargo run --bin economy_report [path/to/levels.ldtk] [path/to/enemies.stats.toml]
+
+use std::collections::HashSet;
+use std::fs;
+use std::process::exit;
+use bevy_ecs_ldtk::ldtk::{Level, LdtkJson};
+use shroom_boom::bossfight::consts::{BOSS_COINS, BOSS_HEALTH};
+use shroom_boom::enemies::spawner::EnemyDifficulty;
+use shroom_boom::enemies::stats::EnemyStatTable;
+use shroom_boom::level::consts::FIRST_LEVEL;
+use shroom_boom::level::enemies::{coins_for_difficulty, parse_difficulty};
+use shroom_boom::level::util::LdtkFields;
+use shroom_boom::player::consts::{DASH_LEVELS, MAX_UPGRADE_LEVEL, SHOOT_LEVELS, SLASH_LEVELS};
+use shroom_boom::shop::info::cost_for_upgrading;
+use shroom_boom::shop::stock::SHOP_CATALOG_UPGRADES;
+
+const DEFAULT_LEVELS_PATH: &str = "assets/levels/levels.ldtk";
+const DEFAULT_STATS_PATH: &str = "assets/enemies.stats.toml";
+
+const DIFFICULTIES: [EnemyDifficulty; 4] = [
+    EnemyDifficulty::Mellow,
+    EnemyDifficulty::Easy,
+    EnemyDifficulty::Medium,
+    EnemyDifficulty::Hard
+];
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let levels_path = args.get(1).map(String::as_str).unwrap_or(DEFAULT_LEVELS_PATH);
+    let stats_path = args.get(2).map(String::as_str).unwrap_or(DEFAULT_STATS_PATH);
+
+    let project: LdtkJson = serde_json::from_str(&read(levels_path)).unwrap_or_else(|e| {
+        eprintln!("Failed to parse {}: {}", levels_path, e);
+        exit(2);
+    });
+
+    let stats: EnemyStatTable = toml::from_str(&read(stats_path)).unwrap_or_else(|e| {
+        eprintln!("Failed to parse {}: {}", stats_path, e);
+        exit(2);
+    });
+
+    print_income(&project);
+    println!();
+    print_time_to_kill(&stats);
+}
+
+fn read(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("Failed to read {}: {}", path, e);
+        exit(2);
+    })
+}
+
+// Follows the exits from the first level until one leads to "None"
+fn level_order(project: &LdtkJson) -> Vec<&Level> {
+    let mut order = vec![];
+    let mut visited = HashSet::new();
+    let mut next = FIRST_LEVEL.to_string();
+
+    while visited.insert(next.clone()) {
+        let lvl = match project.levels.iter().find(|lvl| lvl.identifier == next) {
+            Some(lvl) => lvl,
+            None => {
+                eprintln!("Exit links to unknown level {:?}, stopping there", next);
+                break;
+            }
+        };
+
+        order.push(lvl);
+
+        // levels can link back to the ones before them, so the way forward
+        // is the first exit into a level that hasn't been seen yet
+        let exit = entities(lvl)
+            .filter(|inst| inst.identifier == "Exit")
+            .filter_map(|inst| inst.field_string("NextLevel").ok())
+            .find(|link| !visited.contains(link));
+
+        match exit {
+            Some(link) if link != "None" => next = link,
+            _ => break
+        }
+    }
+
+    order
+}
+
+fn entities(lvl: &Level) -> impl Iterator<Item = &bevy_ecs_ldtk::EntityInstance> {
+    lvl.layer_instances
+        .iter()
+        .flatten()
+        .flat_map(|layer| &layer.entity_instances)
+}
+
+// Every upgrade purchase in the game, cheapest first
+fn upgrade_costs() -> Vec<i32> {
+    let mut costs: Vec<i32> = SHOP_CATALOG_UPGRADES
+        .iter()
+        .flat_map(|_| (0..MAX_UPGRADE_LEVEL).map(cost_for_upgrading))
+        .collect();
+
+    costs.sort();
+    costs
+}
+
+// What it costs to bring every skill up to `tier`
+fn tier_cost(tier: u8) -> i32 {
+    let per_skill: i32 = (0..tier).map(cost_for_upgrading).sum();
+    per_skill * SHOP_CATALOG_UPGRADES.len() as i32
+}
+
+fn print_income(project: &LdtkJson) {
+    let costs = upgrade_costs();
+
+    println!("Coin income, assuming every enemy is killed");
+    println!(
+        "{:<14} {:>6} {:>6} {:>6} {:>6} {:>5} {:>6} {:>6} {:>5} {:>9} {:>5}",
+        "Level", "Mellow", "Easy", "Medium", "Hard", "Boss", "Coins", "Total", "Shop", "Upgrades", "Tier"
+    );
+
+    let mut total = 0;
+
+    for lvl in level_order(project) {
+        let mut counts = [0; 4];
+        let mut coins = 0;
+        let mut boss = false;
+        let mut shop = false;
+
+        for inst in entities(lvl) {
+            match inst.identifier.as_str() {
+                "EnemySpawnpoint" => {
+                    let difficulty = match inst.field_enum("Difficulty").and_then(|d| parse_difficulty(&d)) {
+                        Ok(d) => d,
+                        Err(e) => {
+                            eprintln!("{}: {}", lvl.describe(), e);
+                            continue;
+                        }
+                    };
+
+                    counts[difficulty as usize] += 1;
+                    coins += coins_for_difficulty(difficulty);
+                }
+
+                "BossSpawnpoint" => {
+                    boss = true;
+                    coins += BOSS_COINS;
+                }
+
+                "ShopSpawnpoint" => shop = true,
+
+                _ => {}
+            }
+        }
+
+        total += coins;
+
+        // buying the cheapest upgrade available every time
+        let mut spent = 0;
+        let upgrades = costs
+            .iter()
+            .take_while(|cost| {
+                spent += *cost;
+                spent <= total
+            })
+            .count();
+
+        let tier = (0..=MAX_UPGRADE_LEVEL)
+            .take_while(|tier| tier_cost(*tier) <= total)
+            .last()
+            .unwrap_or(0);
+
+        println!(
+            "{:<14} {:>6} {:>6} {:>6} {:>6} {:>5} {:>6} {:>6} {:>5} {:>9} {:>5}",
+            lvl.identifier,
+            counts[0], counts[1], counts[2], counts[3],
+            if boss { "yes" } else { "" },
+            coins,
+            total,
+            if shop { "yes" } else { "" },
+            format!("{}/{}", upgrades, costs.len()),
+            tier
+        );
+    }
+
+    println!();
+    println!("Upgrade prices per skill: {:?}", (0..MAX_UPGRADE_LEVEL)
+        .map(cost_for_upgrading)
+        .collect::<Vec<_>>());
+
+    for tier in 1..=MAX_UPGRADE_LEVEL {
+        println!("Every skill at level {}: {} coins", tier, tier_cost(tier));
+    }
+}
+
+fn print_time_to_kill(stats: &EnemyStatTable) {
+    // (cooldown, damage) at each upgrade level
+    let weapons: [(&str, Vec<(f32, i32)>); 3] = [
+        ("Slash", SLASH_LEVELS.iter().map(|(cd, dmg)| (*cd, *dmg)).collect()),
+        ("Shoot", SHOOT_LEVELS.iter().map(|(cd, _, dmg)| (*cd, *dmg)).collect()),
+        ("Dash", DASH_LEVELS.iter().map(|(cd, _, dmg)| (*cd, *dmg)).collect())
+    ];
+
+    let mut enemies: Vec<(String, i32)> = vec![];
+
+    let mut names: Vec<&String> = stats.enemies.keys().collect();
+    names.sort();
+
+    for name in names {
+        for difficulty in DIFFICULTIES {
+            let hp = stats.enemies[name].get(difficulty).health;
+            enemies.push((format!("{} {:?}", name, difficulty), hp));
+        }
+    }
+
+    enemies.push(("Boss".to_string(), BOSS_HEALTH));
+
+    for (weapon, levels) in weapons.iter() {
+        println!("{} time to kill, hits/seconds at each upgrade level", weapon);
+
+        print!("{:<20} {:>4}", "Enemy", "HP");
+        for lvl in 0..levels.len() {
+            print!(" {:>10}", format!("L{}", lvl));
+        }
+        println!();
+
+        for (name, hp) in &enemies {
+            print!("{:<20} {:>4}", name, hp);
+
+            for (cd, dmg) in levels {
+                let hits = (hp + dmg - 1) / dmg;
+                let secs = (hits - 1) as f32 * cd;
+                print!(" {:>10}", format!("{}/{:.1}s", hits, secs));
+            }
+
+            println!();
+        }
+
+        println!();
+    }
+}
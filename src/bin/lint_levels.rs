@@ -0,0 +1,47 @@
+// Checks levels.ldtk against what the `level` module expects without
+// starting the game, exits with 1 if anything is wrong:
+//
+//     cargo run --bin lint_levels [path/to/levels.ldtk]
+
+use std::fs;
+use std::process::exit;
+use bevy_ecs_ldtk::ldtk::LdtkJson;
+use shroom_boom::level::validate::validate_project;
+
+const DEFAULT_PATH: &str = "assets/levels/levels.ldtk";
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| DEFAULT_PATH.to_string());
+
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path, e);
+            exit(2);
+        }
+    };
+
+    let project: LdtkJson = match serde_json::from_str(&text) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed to parse {}: {}", path, e);
+            exit(2);
+        }
+    };
+
+    let problems = validate_project(&project);
+
+    if problems.is_empty() {
+        println!("{}: {} levels, no problems", path, project.levels.len());
+        return;
+    }
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+
+    println!();
+    println!("{}: {} problems in {} levels", path, problems.len(), project.levels.len());
+
+    exit(1);
+}
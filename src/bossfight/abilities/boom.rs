@@ -2,17 +2,20 @@ use bevy::prelude::*;
 use rand::prelude::*;
 use seldom_state::prelude::*;
 use crate::bossfight::{Boss, BossConfig, util};
-use crate::bossfight::consts::{BOSS_BOOM_EXPLOSION_COUNT, BOSS_BOOM_PARTITION_SIZE, BOSS_BOOM_SELECTION_TIME, BOSS_BOOM_SUMMON_WAIT_TIME, BOSS_BOOM_WAIT_TIME};
+use crate::bossfight::consts::{BOSS_BOOM_EXPLOSION_COUNT, BOSS_BOOM_PARTITION_SIZE, BOSS_BOOM_SELECTION_TIME, BOSS_BOOM_SUMMON_EXTRA, BOSS_BOOM_SUMMON_TYPES, BOSS_BOOM_SUMMON_WAIT_TIME, BOSS_BOOM_WAIT_TIME};
 use crate::bossfight::enraged::EnragedAttackMove;
 use crate::bossfight::stage::BossStage;
 use crate::bossfight::state_machine::{AbilityStartup, Boom};
 use crate::bossfight::summon::SummonAbility;
 use crate::bossfight::util::pick_point_in_region;
 use crate::combat::{CombatLayerMask, ExplosionEvent, Immunity};
+use crate::enemies::registry::EnemyRegistry;
 use crate::enemies::spawner::{EnemyDifficulty, EnemyLocation, EnemySpawnEvent, EnemyType};
 use crate::fx::indicator::Indicator;
 use crate::pathfind::Region;
+use crate::rng::{GameRng, RngStream};
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct BoomAbility {
@@ -37,20 +40,22 @@ impl Default for BoomAbility {
 
 pub fn register_boom_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_booming)
-            .with_system(boom_update)
+            .with_system(boom_update.after(start_booming))
     );
 }
 
-fn start_booming(
+pub fn start_booming(
     mut q: Query<(
         &mut Immunity,
         &mut BoomAbility,
         &Boss,
         &BossConfig
     ), Added<AbilityStartup>>,
-    mut indicators: EventWriter<Indicator>
+    mut indicators: EventWriter<Indicator>,
+    registry: Res<EnemyRegistry>,
+    mut rng: ResMut<GameRng>
 ) {
     if q.is_empty() {
         return;
@@ -70,18 +75,25 @@ fn start_booming(
     boom.enemy_points.clear();
 
     // Summon 2 enemies & a dandelion
-    let ty_list = [EnemyType::Tumbleweed, EnemyType::Pumpkin, EnemyType::Flower];
-    let mut rng = thread_rng();
+    let rng = rng.stream(RngStream::Boss);
 
     let chosen = [
-        ty_list[rng.gen_range(0..(ty_list.len() - 1))],
-        EnemyType::Dandelion
+        BOSS_BOOM_SUMMON_TYPES[rng.gen_range(0..(BOSS_BOOM_SUMMON_TYPES.len() - 1))],
+        BOSS_BOOM_SUMMON_EXTRA
     ];
 
-    for i in chosen {
+    for name in chosen {
+        let i = match registry.lookup(name) {
+            Ok(ty) => ty,
+            Err(e) => {
+                error!("Can't summon: {}", e);
+                continue;
+            }
+        };
+
         let mut p;
         loop {
-            p = pick_point_in_region(cfg.summon_region, BOSS_BOOM_PARTITION_SIZE);
+            p = pick_point_in_region(rng, cfg.summon_region, BOSS_BOOM_PARTITION_SIZE);
             if boom.enemy_points.iter().find(|i| i.1 == p).is_none() {
                 break;
             }
@@ -101,7 +113,7 @@ fn start_booming(
 }
 
 
-fn boom_update(
+pub fn boom_update(
     time: Res<Time>,
     mut commands: Commands,
     booming: Query<&Boom>,
@@ -118,7 +130,8 @@ fn boom_update(
 
     mut events: EventWriter<ExplosionEvent>,
     mut spawn_events: EventWriter<EnemySpawnEvent>,
-    mut indicators: EventWriter<Indicator>
+    mut indicators: EventWriter<Indicator>,
+    mut rng: ResMut<GameRng>
 ) {
     if q.is_empty() {
         return;
@@ -136,7 +149,7 @@ fn boom_update(
             let mut point;
 
             loop {
-                point = pick_point_in_region(cfg.boom_region, BOSS_BOOM_PARTITION_SIZE);
+                point = pick_point_in_region(rng.stream(RngStream::Boss), cfg.boom_region, BOSS_BOOM_PARTITION_SIZE);
 
                 if !boom.explosion_points.contains(&point) {
                     break;
@@ -211,6 +224,7 @@ fn boom_spawn_enemies(
                 patrol_region: *reg
             },
             rand_range: 0.9..1.1,
+            spawnpoint: None,
             extra_components: None
         });
     }
@@ -11,6 +11,7 @@ use crate::enemies::Enemy;
 use crate::fx::shake::ScreenShakeEvent;
 use crate::state::GameState;
 use crate::util::{Facing, FacingX, FacingY};
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct ChargeAbility {
@@ -27,9 +28,9 @@ impl Default for ChargeAbility {
 
 pub fn register_boom_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_charging)
-            .with_system(charge_update)
+            .with_system(charge_update.after(start_charging))
     );
 }
 
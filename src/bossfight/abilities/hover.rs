@@ -9,6 +9,7 @@ use crate::enemies::Enemy;
 use crate::player::Player;
 use crate::state::GameState;
 use crate::util::{Facing, FacingX};
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct HoverAbility;
@@ -21,9 +22,9 @@ impl Default for HoverAbility {
 
 pub fn register_hover_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_hover)
-            .with_system(hover_update)
+            .with_system(hover_update.after(start_hover))
     );
 }
 
@@ -14,6 +14,7 @@ use crate::common::{PHYSICS_STEP_DELTA, PHYSICS_STEPS_PER_SEC};
 use crate::enemies::Enemy;
 use crate::state::GameState;
 use crate::util::{deg_to_rad, Facing, FacingX, FacingY, quat_rot2d_deg, rad_to_deg};
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct LeapAbility {
@@ -31,12 +32,12 @@ impl Default for LeapAbility {
 pub fn register_leap_ability(app: &mut App) {
     app
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_system(start_leaping)
-                .with_system(leap_update)
+                .with_system(leap_update.after(start_leaping))
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_run_criteria(FixedTimestep::steps_per_second(PHYSICS_STEPS_PER_SEC))
                 .with_system(leap_rotate)
         );
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 
 mod rest;
-mod boom;
+pub mod boom;
 mod relocate;
 mod charge;
 mod leap;
@@ -7,6 +7,7 @@ use crate::bossfight::state_machine::{AbilityStartup, Relocate};
 use crate::combat::Immunity;
 use crate::state::GameState;
 use crate::util::quat_rot2d_deg;
+use crate::common::UpdateStage;
 
 
 #[derive(Component, Clone, Debug)]
@@ -26,9 +27,9 @@ impl Default for RelocateAbility {
 
 pub fn register_relocate_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_relocation)
-            .with_system(relocate_update)
+            .with_system(relocate_update.after(start_relocation))
     );
 }
 
@@ -11,6 +11,7 @@ use crate::enemies::Enemy;
 use crate::fx::indicator::Indicator;
 use crate::pathfind::Region;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Debug, Component, Clone)]
 pub struct RestAbility {
@@ -29,9 +30,9 @@ impl Default for RestAbility {
 
 pub fn register_rest_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_resting)
-            .with_system(rest_update)
+            .with_system(rest_update.after(start_resting))
     );
 }
 
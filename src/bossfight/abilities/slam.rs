@@ -8,6 +8,7 @@ use crate::combat::{ColliderAttack, Immunity};
 use crate::enemies::Enemy;
 use crate::fx::shake::ScreenShakeEvent;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct SlamAbility;
@@ -20,9 +21,9 @@ impl Default for SlamAbility {
 
 pub fn register_slam_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_slam)
-            .with_system(slam_update)
+            .with_system(slam_update.after(start_slam))
     );
 }
 
@@ -7,6 +7,7 @@ use crate::bossfight::state_machine::{AbilityStartup, Takeoff};
 use crate::combat::Immunity;
 use crate::enemies::Enemy;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct TakeoffAbility;
@@ -19,9 +20,9 @@ impl Default for TakeoffAbility  {
 
 pub fn register_takeoff_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(start_takeoff)
-            .with_system(takeoff_update)
+            .with_system(takeoff_update.after(start_takeoff))
     );
 }
 
@@ -9,10 +9,11 @@ use crate::bossfight::stage::BossStage;
 use crate::bossfight::state_machine::{BeginVulnerable, Boom, Hover, Relocate, Rest, Slam, Summon};
 use crate::state::GameState;
 use crate::entity_states::*;
+use crate::common::UpdateStage;
 
 pub fn register_boss_animations(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(boss_anim_boom)
             .with_system(boss_anim_retract)
             .with_system(boss_anim_extend)
@@ -25,8 +25,13 @@ pub const BOSS_BOOM_SELECTION_TIME: f32 = 0.1;
 pub const BOSS_BOOM_WAIT_TIME: f32 = 0.8;
 pub const BOSS_BOOM_SUMMON_WAIT_TIME: f32 = 0.6;
 pub const BOSS_BOOM_PARTITION_SIZE: f32 = 128.0;
+// Looked up in the `EnemyRegistry`, one of these is summoned along with
+// `BOSS_BOOM_SUMMON_EXTRA`
+pub const BOSS_BOOM_SUMMON_TYPES: [&str; 3] = ["Tumbleweed", "Pumpkin", "Flower"];
+pub const BOSS_BOOM_SUMMON_EXTRA: &str = "Dandelion";
 
 pub const BOSS_HEALTH: i32 = 500;
+pub const BOSS_COINS: i32 = 800;
 pub const BOSS_EASY_HEALTH_THRESHOLD: i32 = 400;
 pub const BOSS_MEDIUM_HEALTH_THRESHOLD: i32 = 300;
 pub const BOSS_HARD_HEALTH_THRESHOLD: i32 = 200;
@@ -34,3 +39,5 @@ pub const BOSS_HARD_HEALTH_THRESHOLD: i32 = 200;
 pub const BOSS_SUMMON_COUNT_EASY: usize = 6;
 pub const BOSS_SUMMON_COUNT_MEDIUM: usize = 8;
 pub const BOSS_SUMMON_COUNT_HARD: usize = 10;
+// Looked up in the `EnemyRegistry`
+pub const BOSS_SUMMON_TYPES: [&str; 4] = ["Flower", "Dandelion", "Pumpkin", "Tumbleweed"];
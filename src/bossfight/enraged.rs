@@ -2,6 +2,7 @@ use bevy::prelude::*;
 use crate::bossfight::{Boss, BossStage};
 use crate::bossfight::state_machine::{BeginEnraged, PickNextMove};
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Copy, Clone, Component, Debug, PartialEq)]
 pub enum EnragedAttackMove {
@@ -56,9 +57,9 @@ pub const ATTACK_SEQUENCE: &[EnragedAttackMove] = &[
 
 pub fn register_boss_enraged(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(boss_enter_enraged)
-            .with_system(boss_enraged_update)
+            .with_system(boss_enraged_update.after(boss_enter_enraged))
     );
 }
 
@@ -29,11 +29,12 @@ use enraged::ATTACK_SEQUENCE;
 use crate::bossfight::abilities::{BoomAbility, RelocateAbility, register_boss_abilities, RestAbility, ChargeAbility, LeapAbility, HoverAbility, SlamAbility, TakeoffAbility};
 
 pub use crate::bossfight::config::BossConfig;
-use crate::bossfight::consts::{BOSS_FULL_SIZE, BOSS_HALF_SIZE, BOSS_HEALTH};
+use crate::bossfight::consts::{BOSS_COINS, BOSS_FULL_SIZE, BOSS_HALF_SIZE, BOSS_HEALTH};
 use crate::util::Facing;
 use crate::anim::Animator;
 use crate::anim::map::AnimationMap;
 use crate::bossfight::anim::register_boss_animations;
+use crate::common::UpdateStage;
 
 
 #[derive(Component, Clone, Reflect)]
@@ -171,7 +172,7 @@ impl BossBundle {
             combat_layer: CombatLayerMask::ENEMY,
 
             coins: CoinHolder {
-                total_value: 800
+                total_value: BOSS_COINS
             },
 
             sprite_sheet: SpriteSheetBundle {
@@ -209,10 +210,10 @@ impl Plugin for BossPlugin {
             .register_type::<BossStage>()
             .register_type::<BossConfig>()
             .add_system_set(
-                SystemSet::on_update(GameState::Gameplay)
+                UpdateStage::GameLogic.on_update(GameState::Gameplay)
                     .with_system(boss_got_hurt)
-                    .with_system(boss_start_idling)
-                    .with_system(print_stage)
+                    .with_system(boss_start_idling.after(boss_got_hurt))
+                    .with_system(print_stage.after(boss_start_idling))
             );
     }
 }
@@ -2,18 +2,22 @@ use bevy::prelude::*;
 
 use rand::prelude::*;
 use crate::bossfight::{Boss, BossConfig};
-use crate::bossfight::consts::{BOSS_BOOM_PARTITION_SIZE, BOSS_SUMMON_COUNT_EASY, BOSS_SUMMON_COUNT_HARD, BOSS_SUMMON_COUNT_MEDIUM};
+use crate::bossfight::abilities::boom::boom_update;
+use crate::bossfight::consts::{BOSS_BOOM_PARTITION_SIZE, BOSS_SUMMON_COUNT_EASY, BOSS_SUMMON_COUNT_HARD, BOSS_SUMMON_COUNT_MEDIUM, BOSS_SUMMON_TYPES};
 use crate::bossfight::stage::BossStage;
 use crate::bossfight::state_machine::Summon;
 use crate::bossfight::util::pick_point_in_region;
 use crate::combat::{ColliderAttack, HurtAbility, Immunity};
-use crate::enemies::spawner::{EnemyDifficulty, EnemyLocation, EnemySpawnEvent, EnemyType};
+use crate::enemies::registry::EnemyRegistry;
+use crate::enemies::spawner::{EnemyDifficulty, EnemyLocation, EnemySpawnEvent};
 use crate::fx::indicator::Indicator;
 use crate::level::consts::RENDERED_TILE_SIZE;
 use crate::level::LevelInfo;
 use crate::pathfind::Region;
+use crate::rng::{GameRng, RngStream};
 use crate::player::abilities::autotarget::Untargetable;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 
 
@@ -52,13 +56,14 @@ pub struct FinishedSummoning;
 
 pub fn register_boss_summon(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(enter_summon)
-            .with_system(summon_update)
-            .with_system(summon_enemies)
+            // shares the boss's random numbers with booming
+            .with_system(summon_update.after(enter_summon).after(boom_update))
+            .with_system(summon_enemies.after(summon_update))
 
-            .with_system(reset_escaped_enemies)
-            .with_system(fix_summoned_enemy_colliders)
+            .with_system(reset_escaped_enemies.after(summon_enemies))
+            .with_system(fix_summoned_enemy_colliders.after(summon_enemies))
     );
 }
 
@@ -124,7 +129,9 @@ fn summon_update(
         &mut SummonAbility
     ), (With<Boss>, With<Summon>)>,
 
-    mut indicators: EventWriter<Indicator>
+    mut indicators: EventWriter<Indicator>,
+    registry: Res<EnemyRegistry>,
+    mut rng: ResMut<GameRng>
 ) {
     if q.is_empty() {
         return;
@@ -134,16 +141,24 @@ fn summon_update(
     summon.summon_lag.tick(time.delta());
 
     if summon.enemies.len() < summon.target_count && summon.summon_lag.just_finished() {
+        let rng = rng.stream(RngStream::Boss);
+
         let mut p;
         loop {
-            p = pick_point_in_region(cfg.summon_region, BOSS_BOOM_PARTITION_SIZE);
+            p = pick_point_in_region(rng, cfg.summon_region, BOSS_BOOM_PARTITION_SIZE);
             if summon.enemies.iter().find(|i| i.location.pos == p).is_none() {
                 break;
             }
         }
 
-        let types = [EnemyType::Flower, EnemyType::Dandelion, EnemyType::Pumpkin, EnemyType::Tumbleweed];
-        let rand_type = types[thread_rng().gen_range(0..types.len())];
+        let name = BOSS_SUMMON_TYPES[rng.gen_range(0..BOSS_SUMMON_TYPES.len())];
+        let rand_type = match registry.lookup(name) {
+            Ok(ty) => ty,
+            Err(e) => {
+                error!("Can't summon: {}", e);
+                return;
+            }
+        };
 
         let len = summon.enemies.len();
         let wait = (0.1 * (summon.target_count - len) as f32) - 0.1;
@@ -170,6 +185,7 @@ fn summon_update(
                 patrol_region: region
             },
             rand_range: 0.9..1.1,
+            spawnpoint: None,
             extra_components: Some(|cmd, x| {
                 cmd.entity(x).insert(SummonedEnemy);
             })
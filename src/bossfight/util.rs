@@ -2,9 +2,7 @@ use rand::prelude::*;
 use bevy::prelude::*;
 use crate::pathfind::Region;
 
-pub fn pick_point_in_region(reg: Region, partition_size: f32) -> Vec2 {
-    let mut rng = thread_rng();
-
+pub fn pick_point_in_region<R: Rng + ?Sized>(rng: &mut R, reg: Region, partition_size: f32) -> Vec2 {
     let x_min = reg.tl.x;
     let x_max = reg.br.x;
     let y_min = reg.br.y;
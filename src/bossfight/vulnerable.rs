@@ -5,13 +5,14 @@ use crate::bossfight::summon::{FinishedSummoning, SummonedEnemy};
 use crate::combat::{Health, Immunity};
 use crate::player::abilities::autotarget::Untargetable;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 pub fn register_boss_vulnerable(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(boss_enter_vulnerable)
-            .with_system(boss_vulnerable_update)
-            .with_system(boss_tick_vulnerable_timer)
+            .with_system(boss_vulnerable_update.after(boss_enter_vulnerable))
+            .with_system(boss_tick_vulnerable_timer.after(boss_vulnerable_update))
     );
 }
 
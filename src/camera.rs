@@ -1,20 +1,44 @@
 use bevy::prelude::*;
+use bevy::render::camera::Viewport;
 use bevy_ecs_ldtk::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     state::GameState,
     player::Player,
-    level::consts::SCALE_FACTOR
+    player::consts::PLAYER_RUN_SPEED,
+    level::FinishedTransitioning,
+    level::camera_zone::{CameraZone, CameraZoneMode},
+    level::consts::SCALE_FACTOR,
+    util::{Facing, FacingX}
 };
 
+// The game is drawn at this size in the art's own pixels, scaled up by a
+// whole number to fit the window with black bars around it
+pub const VIRTUAL_RESOLUTION: UVec2 = UVec2::new(320, 180);
+
+// How much of the level is in view, in world units
+pub const VIEW_SIZE: Vec2 = Vec2::new(
+    VIRTUAL_RESOLUTION.x as f32 * SCALE_FACTOR,
+    VIRTUAL_RESOLUTION.y as f32 * SCALE_FACTOR
+);
+
+// Share of the look-ahead kept while standing still, so turning around
+// still shows more of where the player is facing
+const IDLE_LOOK_AHEAD: f32 = 0.5;
+
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(spawn_camera);
+        app
+            .init_resource::<CameraSettings>()
+            .init_resource::<GameViewport>()
+            .add_startup_system(spawn_camera);
 
         app.add_system_set(
             SystemSet::new()
+                .with_system(fit_camera_to_window.before(camera_track_player))
                 .with_system(camera_track_player)
         );
 
@@ -25,10 +49,52 @@ impl Plugin for CameraPlugin {
     }
 }
 
+// Read from the `[camera]` section of config.toml. Distances are in world
+// units, speeds are how quickly the gap closes, per second.
+#[derive(Resource, Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct CameraSettings {
+    pub follow_speed: f32,
+    // how far the player can move from the camera's focus before it follows
+    pub dead_zone_x: f32,
+    pub dead_zone_y: f32,
+    pub look_ahead: f32,
+    pub look_ahead_speed: f32,
+    pub zoom_speed: f32
+}
+
+impl Default for CameraSettings {
+    fn default() -> Self {
+        Self {
+            follow_speed: 5.0,
+            dead_zone_x: 32.0,
+            dead_zone_y: 96.0,
+            look_ahead: 128.0,
+            look_ahead_speed: 2.0,
+            zoom_speed: 3.0
+        }
+    }
+}
+
+// Where the game is drawn in the window, updated as it's resized
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq)]
+pub struct GameViewport {
+    // window pixels per art pixel
+    pub scale: u32,
+    // in logical pixels, from the window's top left corner
+    pub pos: Vec2,
+    pub size: Vec2
+}
+
 #[derive(Component)]
 pub struct GameCamera {
-    pub border_min: Vec2,
-    pub border_max: Vec2
+    pub level_size: Vec2,
+    // where the camera is without screen shake
+    pub pos: Vec2,
+    // the point the dead zone is around, follows the player
+    pub focus: Vec2,
+    pub look_ahead: f32,
+    pub zoom: f32
 }
 
 pub fn spawn_camera(mut commands: Commands) {
@@ -36,34 +102,165 @@ pub fn spawn_camera(mut commands: Commands) {
         Camera2dBundle::default(),
 
         GameCamera {
-            border_min: Vec2::new(0.0, 0.0),
-            border_max: Vec2::new(0.0, 0.0)
+            level_size: Vec2::ZERO,
+            pos: Vec2::ZERO,
+            focus: Vec2::ZERO,
+            look_ahead: 0.0,
+            zoom: 1.0
         }
     ));
 }
 
+// Exponential smoothing, the same follow speed whatever the frame rate
+fn smoothing(speed: f32, dt: f32) -> f32 {
+    1.0 - (-speed * dt).exp()
+}
+
+// Keeps a view of `half` either side of `pos` inside `min..max`, or centers it
+// if it doesn't fit
+fn clamp_view(pos: f32, half: f32, min: f32, max: f32) -> f32 {
+    if max - min <= half * 2.0 {
+        (min + max) / 2.0
+    } else {
+        pos.clamp(min + half, max - half)
+    }
+}
+
+// The biggest whole multiple of `VIRTUAL_RESOLUTION` that fits, centered.
+// Done in physical pixels so every art pixel covers the same number of them.
+fn fit_camera_to_window(
+    windows: Option<Res<Windows>>,
+    mut viewport: ResMut<GameViewport>,
+    mut q: Query<(&mut Camera, &mut OrthographicProjection, &GameCamera)>
+) {
+    let window = match windows.as_ref().and_then(|w| w.get_primary()) {
+        Some(w) => w,
+        None => return
+    };
+
+    let physical = UVec2::new(window.physical_width(), window.physical_height());
+
+    // minimized
+    if physical.min_element() == 0 {
+        return;
+    }
+
+    let scale = (physical / VIRTUAL_RESOLUTION).min_element().max(1);
+    let size = VIRTUAL_RESOLUTION * scale;
+    let pos = (physical.max(size) - size) / 2;
+
+    let new = GameViewport {
+        scale,
+        pos: pos.as_vec2() / window.scale_factor() as f32,
+        size: size.as_vec2() / window.scale_factor() as f32
+    };
+
+    if *viewport == new {
+        return;
+    }
+
+    *viewport = new;
+
+    for (mut cam, mut proj, game_cam) in q.iter_mut() {
+        cam.viewport = Some(Viewport {
+            physical_position: pos,
+            physical_size: size,
+            ..default()
+        });
+
+        proj.scale = game_cam.zoom * VIEW_SIZE.x / new.size.x;
+    }
+}
+
 pub fn camera_track_player(
-    p: Query<&GlobalTransform, With<Player>>,
-    mut q: Query<(&mut Transform, &GameCamera)>
+    time: Res<Time>,
+    settings: Res<CameraSettings>,
+    viewport: Res<GameViewport>,
+    p: Query<(&GlobalTransform, &Player, &Facing)>,
+    placed: Query<(), (With<Player>, Added<FinishedTransitioning>)>,
+    zones: Query<&CameraZone>,
+    mut q: Query<(&mut Transform, &mut OrthographicProjection, &mut GameCamera)>
 ) {
-    if p.is_empty() || q.is_empty() {
+    if p.is_empty() || q.is_empty() || viewport.scale == 0 {
         return;
     }
 
-    let pos = p.single().translation();
-    let (mut cam_pos, cam) = q.single_mut();
+    let (tf, player, facing) = p.single();
+    let (mut cam_tf, mut proj, mut cam) = q.single_mut();
+    let cam = &mut *cam;
+
+    let pos = tf.translation().truncate();
+    let dt = time.delta_seconds();
+
+    // just placed in a new level, jump straight there instead of panning
+    // across it while the transition reveals it
+    let snap = !placed.is_empty();
+
+    if snap {
+        cam.focus = pos;
+    }
+
+    cam.focus.x = cam.focus.x.clamp(pos.x - settings.dead_zone_x, pos.x + settings.dead_zone_x);
+
+    // Only re-centered vertically on landing, jumps stay inside the dead zone
+    if player.grounded {
+        cam.focus.y = pos.y;
+    } else {
+        cam.focus.y = cam.focus.y.clamp(pos.y - settings.dead_zone_y, pos.y + settings.dead_zone_y);
+    }
+
+    let dir = match facing.x {
+        FacingX::Left => -1.0,
+        FacingX::Right => 1.0
+    };
+
+    let moving = (player.vel.x.abs() / PLAYER_RUN_SPEED).min(1.0);
+    let look_ahead = dir * settings.look_ahead * (IDLE_LOOK_AHEAD + (1.0 - IDLE_LOOK_AHEAD) * moving);
+
+    cam.look_ahead += (look_ahead - cam.look_ahead) * smoothing(settings.look_ahead_speed, dt);
+
+    let mut target = cam.focus + Vec2::new(cam.look_ahead, 0.0);
+    let mut zoom = 1.0;
+
+    // the smallest zone wins where they overlap
+    let zone = zones
+        .iter()
+        .filter(|zone| zone.contains(pos))
+        .min_by(|a, b| a.area().total_cmp(&b.area()));
+
+    match zone.map(|zone| (zone, zone.mode)) {
+        Some((zone, CameraZoneMode::Frame)) => {
+            target = zone.center();
+            zoom = (zone.size() / VIEW_SIZE).max_element().max(1.0);
+        }
+        Some((zone, CameraZoneMode::Bias(bias))) => {
+            target = target.lerp(zone.center(), bias);
+        }
+        _ => {}
+    }
+
+    if snap {
+        cam.look_ahead = look_ahead;
+        cam.zoom = zoom;
+        cam.pos = target;
+    } else {
+        cam.zoom += (zoom - cam.zoom) * smoothing(settings.zoom_speed, dt);
+        cam.pos += (target - cam.pos) * smoothing(settings.follow_speed, dt);
+    }
+
+    let half = VIEW_SIZE * cam.zoom / 2.0;
 
-    let _ = cam;
-    let fac_x = 20.0;
-    let fac_y = 20.0;
+    if let Some(zone) = zone.filter(|zone| zone.mode == CameraZoneMode::Lock) {
+        cam.pos.x = clamp_view(cam.pos.x, half.x, zone.min.x, zone.max.x);
+        cam.pos.y = clamp_view(cam.pos.y, half.y, zone.min.y, zone.max.y);
+    }
 
-    cam_pos.translation.x += (pos.x - cam_pos.translation.x) / fac_x;
-    cam_pos.translation.y += (pos.y - cam_pos.translation.y) / fac_y;
+    cam.pos.x = clamp_view(cam.pos.x, half.x, 0.0, cam.level_size.x);
+    cam.pos.y = clamp_view(cam.pos.y, half.y, 0.0, cam.level_size.y);
 
-    cam_pos.translation.x = cam_pos.translation.x.clamp(cam.border_min.x, cam.border_max.x);
-    cam_pos.translation.y = cam_pos.translation.y.clamp(cam.border_min.y, cam.border_max.y);
+    proj.scale = cam.zoom * VIEW_SIZE.x / viewport.size.x;
 
-    cam_pos.translation.z = 999.0;
+    cam_tf.translation = cam.pos.extend(999.0);
 }
 
 fn camera_reconfigure_to_fit_level(
@@ -72,7 +269,6 @@ fn camera_reconfigure_to_fit_level(
     levels: Query<&Handle<LdtkAsset>>,
     level_sel: Res<LevelSelection>,
 
-    windows: Res<Windows>,
     assets: Res<Assets<LdtkAsset>>,
 ) {
     if levels.is_empty() || assets.is_empty() {
@@ -80,25 +276,13 @@ fn camera_reconfigure_to_fit_level(
     }
 
     for mut camera in camera.iter_mut() {
-        let win = windows.get_primary().unwrap();
         let level = assets.get(levels.single()).unwrap();
 
         if let Some(lvl) = level.get_level(&level_sel) {
-            let half_extents = Vec2::new(
-                win.width() / 2.0,
-                win.height() / 2.0
-            );
-
-            camera.border_min = half_extents;
-            camera.border_max = Vec2::new(
+            camera.level_size = Vec2::new(
                 lvl.px_wid as f32 * SCALE_FACTOR,
                 lvl.px_hei as f32 * SCALE_FACTOR
-            ) - half_extents;
-
-            if camera.border_min.x > camera.border_max.x
-                || camera.border_min.y > camera.border_max.y {
-                camera.border_max = camera.border_max + half_extents;
-            }
+            );
         }
     }
-}
\ No newline at end of file
+}
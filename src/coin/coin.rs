@@ -9,27 +9,28 @@ use crate::coin::state_machine::{coin_state_machine, Follow, register_coin_state
 use crate::common::{PHYSICS_STEP_DELTA, PHYSICS_STEPS_PER_SEC};
 use crate::state::GameState;
 use crate::anim::Animator;
+use crate::common::UpdateStage;
 
 pub fn register_coin(app: &mut App) {
     register_coin_state_machine(app);
 
     app
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
-                .with_system(coin_move)
-                .with_system(coin_slide)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_system(coin_set_grounded)
-                .with_system(set_coin_target)
-                .with_system(coin_track_target)
+                .with_system(set_coin_target.after(coin_set_grounded))
+                .with_system(coin_track_target.after(set_coin_target))
+                .with_system(coin_slide.after(coin_track_target))
+                .with_system(coin_wall_slide.after(coin_slide))
+                .with_system(coin_added_die.after(coin_wall_slide))
+                .with_system(coin_move.after(coin_added_die))
                 .with_system(coin_disable_collisions)
-                .with_system(coin_wall_slide)
-                .with_system(coin_added_die)
                 .with_system(coin_dead_update)
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_run_criteria(FixedTimestep::steps_per_second(PHYSICS_STEPS_PER_SEC))
-                .with_system(coin_fall)
+                .with_system(coin_fall.after(coin_set_grounded).before(coin_slide))
                 .with_system(coin_shrink)
         )
     ;
@@ -3,8 +3,11 @@ use rand::prelude::*;
 use crate::assets::CoinAssets;
 use crate::coin::coin::{Coin, CoinBundle, CoinMovement};
 use crate::state::GameState;
+use crate::enemies::spawner::spawn_enemies;
 use crate::entity_states::Die;
 use crate::player::Player;
+use crate::rng::{GameRng, RngStream};
+use crate::common::UpdateStage;
 
 
 #[derive(Copy, Clone, Debug, Component, Default)]
@@ -14,8 +17,8 @@ pub struct CoinHolder {
 
 pub fn register_drops(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(drop_coins_on_death)
+        UpdateStage::Spawn.on_update(GameState::Gameplay)
+            .with_system(drop_coins_on_death.after(spawn_enemies))
     );
 }
 
@@ -38,10 +41,11 @@ fn spawn_random_coin<R: Rng + ?Sized>(
     });
 }
 
-fn drop_coins_on_death(
+pub fn drop_coins_on_death(
     mut commands: Commands,
     dead: Query<(&CoinHolder, &GlobalTransform), (Added<Die>, Without<Player>)>,
-    assets: Res<CoinAssets>
+    assets: Res<CoinAssets>,
+    mut rng: ResMut<GameRng>
 ) {
     for (drop, transform) in dead.iter() {
         let pos = Vec2::new(
@@ -49,7 +53,7 @@ fn drop_coins_on_death(
             transform.translation().y
         );
 
-        let mut rng = thread_rng();
+        let rng = rng.stream(RngStream::Coins);
 
         const COIN_SPLIT: usize = 5;
 
@@ -57,11 +61,11 @@ fn drop_coins_on_death(
         let remaining = drop.total_value % (COIN_SPLIT as i32);
 
         for _ in 0..value_split {
-            spawn_random_coin(&mut rng, &mut commands, COIN_SPLIT as i32, pos, &assets);
+            spawn_random_coin(rng, &mut commands, COIN_SPLIT as i32, pos, &assets);
         }
 
         if remaining != 0 {
-            spawn_random_coin(&mut rng, &mut commands, remaining, pos, &assets);
+            spawn_random_coin(rng, &mut commands, remaining, pos, &assets);
         }
 
         println!("dropping {:?}", drop);
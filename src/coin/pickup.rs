@@ -3,11 +3,12 @@ use bevy_rapier2d::prelude::*;
 use crate::coin::coin::{Coin, CoinMovement};
 use crate::coin::drops::CoinHolder;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 
 pub fn register_pickup(app: &mut App) {
-    app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+    app.add_event::<CoinPickupEvent>().add_system_set(
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
             .with_system(collect_coins)
     );
 }
@@ -16,10 +17,17 @@ pub fn register_pickup(app: &mut App) {
 #[derive(Component, Copy, Clone, Debug, Default)]
 pub struct CoinCollector;
 
+#[derive(Copy, Clone, Debug)]
+pub struct CoinPickupEvent {
+    pub collector: Entity,
+    pub value: i32
+}
+
 
 fn collect_coins(
     mut coins: Query<(&GlobalTransform, &Collider, &mut Coin, &mut CoinMovement)>,
     mut collectors: Query<&mut CoinHolder, With<CoinCollector>>,
+    mut pickups: EventWriter<CoinPickupEvent>,
     rapier: Res<RapierContext>
 ) {
 
@@ -46,6 +54,11 @@ fn collect_coins(
                     coin_holder.total_value += coin.value;
                     coin_mov.picked_up = true;
                     coin.collected = true;
+
+                    pickups.send(CoinPickupEvent {
+                        collector: collision,
+                        value: coin.value
+                    });
                 }
 
                 true
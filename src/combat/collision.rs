@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
-use crate::combat::{AttackStrength, CombatEvent, CombatLayerMask, KnockbackModifier};
+use crate::combat::{AttackStrength, AttackSystems, CombatEvent, CombatLayerMask, handle_hits, KnockbackModifier};
 use crate::combat::knockbacks::collider_attack_knockback;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Copy, Clone, Debug, Component)]
 pub struct ColliderAttack {
@@ -46,8 +47,13 @@ impl ColliderAttackBundle {
 
 pub fn register_collider_attacks(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(collider_attack_update)
+        UpdateStage::Combat.on_update(GameState::Gameplay)
+            .with_system(
+                collider_attack_update
+                    .label(AttackSystems::Colliders)
+                    .after(AttackSystems::Melee)
+                    .before(handle_hits)
+            )
     );
 }
 
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use seldom_state::prelude::*;
-use crate::combat::Health;
+use crate::combat::{handle_hits, Health};
 use crate::entity_states::Die;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Reflect, FromReflect, Clone)]
 pub struct DeathTrigger;
@@ -23,8 +24,8 @@ impl Trigger for DeathTrigger {
 pub fn register_death(app: &mut App) {
     app.add_plugin(TriggerPlugin::<DeathTrigger>::default());
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(despawn_dead_entities)
+        UpdateStage::Combat.on_update(GameState::Gameplay)
+            .with_system(despawn_dead_entities.after(handle_hits))
     );
 }
 
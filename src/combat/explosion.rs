@@ -4,12 +4,13 @@ use bevy_rapier2d::prelude::*;
 use seldom_state::prelude::*;
 use crate::anim::Animator;
 use crate::assets::ExplosionAssets;
-use crate::combat::{AttackStrength, CombatEvent, CombatLayerMask, KnockbackModifier};
+use crate::combat::{AttackStrength, AttackSystems, CombatEvent, CombatLayerMask, despawn_dead_entities, handle_hits, KnockbackModifier};
 use crate::combat::consts::{EXPLOSION_DIAMETER, EXPLOSION_DURATION, EXPLOSION_EFFECTIVE_DURATION, EXPLOSION_RADIUS};
 use crate::combat::knockbacks::explosion_knockback;
 use crate::entity_states::*;
 use crate::fx::shake::ScreenShakeEvent;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Clone)]
 pub struct ExplosionAttack {
@@ -81,12 +82,18 @@ pub fn register_explosion_attacks(app: &mut App) {
     app
         .add_event::<ExplosionEvent>()
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
-                .with_system(tick_explosion_timers)
-                .with_system(explosion_expand)
-                .with_system(explosion_death)
-                .with_system(explosion_damage)
+            UpdateStage::Combat.on_update(GameState::Gameplay)
                 .with_system(explosion_events)
+                .with_system(tick_explosion_timers.after(explosion_events))
+                .with_system(explosion_expand.after(tick_explosion_timers))
+                .with_system(
+                    explosion_damage
+                        .label(AttackSystems::Explosions)
+                        .after(explosion_expand)
+                        .after(AttackSystems::Projectiles)
+                        .before(handle_hits)
+                )
+                .with_system(explosion_death.after(explosion_damage).before(despawn_dead_entities))
         );
 }
 
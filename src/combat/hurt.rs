@@ -1,11 +1,12 @@
 use bevy::prelude::*;
 use seldom_state::prelude::*;
 use crate::bossfight::Boss;
-use crate::combat::{ColliderAttack, CombatEvent, Immunity};
+use crate::combat::{ColliderAttack, CombatEvent, handle_hits, Immunity};
 use crate::enemies::Enemy;
 use crate::entity_states::*;
 use crate::state::GameState;
 use crate::util;
+use crate::common::UpdateStage;
 
 
 
@@ -32,11 +33,12 @@ pub fn register_hurt_ability(app: &mut App) {
     app.add_plugin(TriggerPlugin::<HurtTrigger>::default());
 
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::Combat.on_update(GameState::Gameplay)
+            .after(handle_hits)
             .with_system(hurt_ability_trigger)
-            .with_system(hurt_ability_update)
-            .with_system(hurt_ability_flash)
-            .with_system(hurt_ability_enemy_disable_collider)
+            .with_system(hurt_ability_update.after(hurt_ability_trigger))
+            .with_system(hurt_ability_flash.after(hurt_ability_update))
+            .with_system(hurt_ability_enemy_disable_collider.after(hurt_ability_trigger))
             .with_system(hurt_ability_enemy_enable_collider.after(hurt_ability_update))
     );
 }
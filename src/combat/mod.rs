@@ -26,23 +26,35 @@ pub use spore_cloud::*;
 use crate::camera::GameCamera;
 use crate::combat::collision::register_collider_attacks;
 use crate::combat::consts::EXPLOSION_RADIUS;
-use crate::combat::spore_cloud::SporeCloudAttackBundle;
 use crate::entity_states::*;
 use crate::fx::shake::ScreenShakeEvent;
 use crate::fx::smoke::SmokeEvent;
 use crate::player::Player;
 
 use crate::state::GameState;
+use crate::common::UpdateStage;
 pub struct AttackPlugin;
 
+// The attacks in `UpdateStage::Combat` send their hits in this order, and
+// `handle_hits` only applies the first one on each target
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AttackSystems {
+    Melee,
+    Colliders,
+    Projectiles,
+    Explosions,
+    SporeClouds,
+    Hazards
+}
+
 
 impl Plugin for AttackPlugin {
     fn build(&self, app: &mut App) {
 
         app
             .add_system_set(
-                SystemSet::on_update(GameState::Gameplay)
-                    .with_system(resolve_melee_attacks)
+                UpdateStage::Combat.on_update(GameState::Gameplay)
+                    .with_system(resolve_melee_attacks.label(AttackSystems::Melee).before(handle_hits))
                     .with_system(handle_hits)
                     // .with_system(temp_explosion)
             )
@@ -62,7 +74,7 @@ impl Plugin for AttackPlugin {
 fn temp_explosion(
     events: Res<Input<MouseButton>>,
     windows: Res<Windows>,
-    camera: Query<&GlobalTransform, With<GameCamera>>,
+    camera: Query<(&GlobalTransform, &OrthographicProjection), With<GameCamera>>,
     mut player_pos: Query<&mut Transform, With<Player>>,
     mut explosions: EventWriter<ExplosionEvent>,
     mut shakes: EventWriter<ScreenShakeEvent>
@@ -71,9 +83,10 @@ fn temp_explosion(
         return;
     }
 
+    let (cam_tf, proj) = camera.single();
     let cam = Vec2::new(
-        camera.single().translation().x,
-        camera.single().translation().y
+        cam_tf.translation().x,
+        cam_tf.translation().y
     );
 
     let win = windows.primary();
@@ -83,7 +96,7 @@ fn temp_explosion(
     }
 
     let cpos = win.cursor_position().unwrap();
-    let world_pos = cpos + (cam - 0.5 * Vec2::new(win.width(), win.height()));
+    let world_pos = cam + (cpos - 0.5 * Vec2::new(win.width(), win.height())) * proj.scale;
 
     if events.just_pressed(MouseButton::Left) {
         shakes.send(ScreenShakeEvent::LARGE);
@@ -111,7 +124,7 @@ fn temp_explosion(
     }
 }
 
-fn handle_hits(
+pub fn handle_hits(
     immune: Query<&Immunity>,
     mut q: Query<(Entity, &mut HurtAbility, &mut Health), (Without<Hurt>, Without<Die>)>,
     mut hit_events: EventReader<CombatEvent>
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use seldom_state::prelude::*;
-use crate::combat::{AttackStrength, CombatLayerMask, CombatEvent, Immunity, KnockbackModifier};
+use crate::combat::{AttackStrength, AttackSystems, CombatLayerMask, CombatEvent, handle_hits, Immunity, KnockbackModifier};
 use crate::combat::knockbacks::projectile_knockback;
 use crate::entity_states::*;
 use crate::level::consts::SOLIDS_INTERACTION_GROUP;
@@ -9,6 +9,7 @@ use crate::state::GameState;
 use crate::anim::Animator;
 use crate::level::door::DoorTile;
 use crate::level::solid::SolidTile;
+use crate::common::UpdateStage;
 
 #[derive(Copy, Clone, Reflect, FromReflect)]
 pub struct CollidedTrigger;
@@ -66,10 +67,16 @@ pub fn register_projectile_attacks(app: &mut App) {
     projectile_register_triggers(app);
 
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::Combat.on_update(GameState::Gameplay)
             .with_system(move_projectile_attacks)
-            .with_system(projectile_hit_targets)
-            .with_system(tick_proj_expirations)
+            .with_system(
+                projectile_hit_targets
+                    .label(AttackSystems::Projectiles)
+                    .after(move_projectile_attacks)
+                    .after(AttackSystems::Colliders)
+                    .before(handle_hits)
+            )
+            .with_system(tick_proj_expirations.after(projectile_hit_targets))
     );
 }
 
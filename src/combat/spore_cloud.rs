@@ -3,12 +3,14 @@ use rand::prelude::*;
 use bevy_rapier2d::prelude::*;
 use seldom_state::prelude::{AlwaysTrigger, Done, DoneTrigger, NotTrigger, StateMachine};
 use crate::assets::SporeAssets;
-use crate::combat::{AttackStrength, CombatEvent, CombatLayerMask, KnockbackModifier};
+use crate::combat::{AttackStrength, AttackSystems, CombatEvent, CombatLayerMask, handle_hits, KnockbackModifier};
 use crate::combat::consts::{SPORE_CLOUD_COLOR, SPORE_CLOUD_DAMAGE_RATE, SPORE_CLOUD_DURATION, SPORE_CLOUD_PARTICLE_SPAWN_RATE, SPORE_CLOUD_SIZE, SPORE_CLOUD_SPORE_ROTATION_RANGE, SPORE_CLOUD_SPORE_SCALE_RANGE};
 use crate::combat::knockbacks::spore_cloud_knockback;
 use crate::entity_states::*;
 use crate::fx::spore::{SporeParticle, SporeParticleBundle};
 use crate::state::GameState;
+use crate::rng::{GameRng, RngStream};
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug, Clone)]
 pub struct SporeCloudAttack {
@@ -78,9 +80,15 @@ impl SporeCloudAttackBundle {
 
 pub fn register_spore_cloud_attacks(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::Combat.on_update(GameState::Gameplay)
             .with_system(spore_cloud_update)
-            .with_system(spore_cloud_damage)
+            .with_system(
+                spore_cloud_damage
+                    .label(AttackSystems::SporeClouds)
+                    .after(spore_cloud_update)
+                    .after(AttackSystems::Explosions)
+                    .before(handle_hits)
+            )
     );
 }
 
@@ -88,7 +96,8 @@ fn spore_cloud_update(
     time: Res<Time>,
     mut commands: Commands,
     assets: Res<SporeAssets>,
-    mut q: Query<(Entity, &GlobalTransform, &mut SporeCloudAttack), Without<Die>>
+    mut q: Query<(Entity, &GlobalTransform, &mut SporeCloudAttack), Without<Die>>,
+    mut rng: ResMut<GameRng>
 ) {
     for (entity, transform, mut spore_cloud) in q.iter_mut() {
         spore_cloud.dur.tick(time.delta());
@@ -103,7 +112,7 @@ fn spore_cloud_update(
 
         if spore_cloud.particle_timer.just_finished() {
             let pos = transform.translation();
-            let mut rng = thread_rng();
+            let rng = rng.stream(RngStream::Effects);
 
             let (x, y, rot, scale) = {
                 let half_x = spore_cloud.size.x / 2.0;
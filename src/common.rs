@@ -1,10 +1,63 @@
 use bevy::prelude::*;
+use crate::state::GameState;
 
-#[derive(SystemLabel)]
+// The phases gameplay runs in every frame, in this order. Bevy sorts systems
+// that don't depend on each other differently in every app, so without these
+// the same seed and inputs, or a replay, only play out the same way in the
+// app they were made in.
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum UpdateStage {
+    // level transitions, and the new level's tiles and entities
+    Level,
+    // input and AI, the player, enemies and the boss deciding what to do
+    GameLogic,
+    // attacks landing, damage, knockback and deaths
+    Combat,
+    // enemies summoned and coins dropped
+    Spawn,
+    // velocities handed to the character controllers and platforms
     Physics,
-    GameLogic
+    // animations switched by the events sent above, and advanced
+    Animation
+}
+
+impl UpdateStage {
+    const ORDER: [UpdateStage; 6] = [
+        UpdateStage::Level,
+        UpdateStage::GameLogic,
+        UpdateStage::Combat,
+        UpdateStage::Spawn,
+        UpdateStage::Physics,
+        UpdateStage::Animation
+    ];
+
+    fn previous(self) -> Option<UpdateStage> {
+        let i = Self::ORDER.iter().position(|s| *s == self).unwrap();
+        i.checked_sub(1).map(|i| Self::ORDER[i])
+    }
+
+    // A set in this phase, after the one before it
+    pub fn set(self) -> SystemSet {
+        self.with_criteria(SystemSet::new())
+    }
+
+    pub fn on_update(self, state: GameState) -> SystemSet {
+        self.with_criteria(SystemSet::on_update(state))
+    }
+
+    pub fn on_enter(self, state: GameState) -> SystemSet {
+        self.with_criteria(SystemSet::on_enter(state))
+    }
+
+    fn with_criteria(self, set: SystemSet) -> SystemSet {
+        let set = set.label(self);
+
+        match self.previous() {
+            Some(previous) => set.after(previous),
+            None => set
+        }
+    }
 }
 
 pub const PHYSICS_STEPS_PER_SEC: f64 = 60.0;
-pub const PHYSICS_STEP_DELTA: f32 = 1.0 / 60.0;
\ No newline at end of file
+pub const PHYSICS_STEP_DELTA: f32 = 1.0 / 60.0;
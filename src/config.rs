@@ -0,0 +1,426 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use bevy::asset::FileAssetIo;
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use bevy_common_assets::toml::TomlAssetPlugin;
+use serde::{Serialize, Serializer};
+use crate::audio::VolumeSettings;
+use crate::camera::CameraSettings;
+use crate::input::bindings::{Binding, GamepadBinding};
+use crate::input::PlayerControls;
+use crate::level::checkpoint::RespawnSettings;
+use crate::level::transition::TransitionSettings;
+
+#[derive(serde::Deserialize, bevy::reflect::TypeUuid)]
+#[uuid = "413be529-bfeb-41b3-9db0-4b8b380a2c46"]
+struct Config {
+    #[serde(default)]
+    pub controls: HashMap<String, BindingList>,
+    #[serde(default)]
+    pub gamepad: HashMap<String, BindingList>,
+    #[serde(default)]
+    pub display: DisplayConfig,
+    #[serde(default)]
+    pub fx: FxConfig,
+    #[serde(default)]
+    pub audio: VolumeSettings,
+    #[serde(default)]
+    pub respawn: RespawnSettings,
+    #[serde(default)]
+    pub transition: TransitionSettings,
+    #[serde(default)]
+    pub camera: CameraSettings,
+}
+
+// Each action in `[controls]` can be bound to a single key or a list of them
+#[derive(serde::Deserialize, Serialize, Clone, Debug)]
+#[serde(untagged)]
+enum BindingList {
+    One(String),
+    Many(Vec<String>)
+}
+
+impl BindingList {
+    fn new(mut names: Vec<String>) -> Self {
+        if names.len() == 1 {
+            BindingList::One(names.remove(0))
+        } else {
+            BindingList::Many(names)
+        }
+    }
+
+    fn entries(&self) -> Vec<String> {
+        match self {
+            BindingList::One(s) => vec![s.clone()],
+            BindingList::Many(v) => v.clone()
+        }
+    }
+}
+
+// A `[controls]` or `[gamepad]` table, written in the order the actions are
+// listed in instead of alphabetically
+struct BindingTable(Vec<(&'static str, BindingList)>);
+
+impl Serialize for BindingTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(name, list)| (name, list)))
+    }
+}
+
+#[derive(serde::Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+struct DisplayConfig {
+    pub fullscreen: bool,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        let settings = GameSettings::default();
+
+        Self {
+            fullscreen: settings.fullscreen,
+            width: settings.resolution.x,
+            height: settings.resolution.y,
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+struct FxConfig {
+    pub screen_shake: f32,
+}
+
+impl Default for FxConfig {
+    fn default() -> Self {
+        Self {
+            screen_shake: GameSettings::default().screen_shake
+        }
+    }
+}
+
+#[derive(Resource, Clone)]
+struct ConfigHandle(Handle<Config>);
+
+pub const RESOLUTIONS: &[Vec2] = &[
+    Vec2::new(960.0, 540.0),
+    Vec2::new(1280.0, 720.0),
+    Vec2::new(1600.0, 900.0),
+    Vec2::new(1920.0, 1080.0),
+];
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct GameSettings {
+    pub fullscreen: bool,
+    pub resolution: Vec2,
+    // multiplier on every ScreenShakeEvent, 0 turns shaking off
+    pub screen_shake: f32,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            fullscreen: false,
+            resolution: Vec2::new(1280.0, 720.0),
+            screen_shake: 1.0,
+        }
+    }
+}
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(TomlAssetPlugin::<Config>::new(&["toml"]))
+            .init_resource::<GameSettings>()
+            .add_startup_system(load_config)
+            .add_system(update_from_config)
+            .add_system(apply_display_settings);
+    }
+}
+
+fn load_config(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let config = ConfigHandle(asset_server.load("config.toml"));
+    commands.insert_resource(config);
+}
+
+fn update_from_config(
+    mut ctrl: ResMut<PlayerControls>,
+    mut settings: ResMut<GameSettings>,
+    mut volume: ResMut<VolumeSettings>,
+    mut respawn: ResMut<RespawnSettings>,
+    mut transition: ResMut<TransitionSettings>,
+    mut camera: ResMut<CameraSettings>,
+    mut events: EventReader<AssetEvent<Config>>,
+    cfg: Res<ConfigHandle>,
+    configs: Res<Assets<Config>>
+) {
+    // only re-parse when the file (re)loads, so bad entries don't warn every frame
+    let changed = events.iter().any(|ev| match ev {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => *handle == cfg.0,
+        _ => false
+    });
+
+    if !changed {
+        return;
+    }
+
+    if let Some(cfg) = configs.get(&cfg.0) {
+        let new = controls_from_config(cfg);
+
+        if *ctrl != new {
+            *ctrl = new;
+        }
+
+        let new = GameSettings {
+            fullscreen: cfg.display.fullscreen,
+            resolution: Vec2::new(cfg.display.width, cfg.display.height),
+            screen_shake: cfg.fx.screen_shake.max(0.0),
+        };
+
+        if *settings != new {
+            *settings = new;
+        }
+
+        let new = VolumeSettings {
+            master: cfg.audio.master.clamp(0.0, 1.0),
+            music: cfg.audio.music.clamp(0.0, 1.0),
+            sfx: cfg.audio.sfx.clamp(0.0, 1.0),
+        };
+
+        if *volume != new {
+            *volume = new;
+        }
+
+        let new = RespawnSettings {
+            coin_loss: cfg.respawn.coin_loss.clamp(0.0, 1.0),
+            ..cfg.respawn.clone()
+        };
+
+        if *respawn != new {
+            *respawn = new;
+        }
+
+        let new = TransitionSettings {
+            cover: cfg.transition.cover.max(0.0),
+            pause: cfg.transition.pause.max(0.0),
+            reveal: cfg.transition.reveal.max(0.0),
+            ..cfg.transition.clone()
+        };
+
+        if *transition != new {
+            *transition = new;
+        }
+
+        let new = CameraSettings {
+            follow_speed: cfg.camera.follow_speed.max(0.0),
+            dead_zone_x: cfg.camera.dead_zone_x.max(0.0),
+            dead_zone_y: cfg.camera.dead_zone_y.max(0.0),
+            look_ahead: cfg.camera.look_ahead.max(0.0),
+            look_ahead_speed: cfg.camera.look_ahead_speed.max(0.0),
+            zoom_speed: cfg.camera.zoom_speed.max(0.0),
+        };
+
+        if *camera != new {
+            *camera = new;
+        }
+    }
+}
+
+fn controls_from_config(cfg: &Config) -> PlayerControls {
+    let defaults = PlayerControls::default();
+    let mut controls = PlayerControls::default();
+
+    for (name, default_bindings, _) in defaults.actions() {
+        let parsed = parse_bindings("controls", name, &cfg.controls, default_bindings, Binding::parse);
+        *controls.bindings_mut(name).unwrap() = parsed;
+    }
+
+    for (name, default_bindings, _) in defaults.gamepad.actions() {
+        let parsed = parse_bindings("gamepad", name, &cfg.gamepad, default_bindings, GamepadBinding::parse);
+        *controls.gamepad.bindings_mut(name).unwrap() = parsed;
+    }
+
+    for name in cfg.controls.keys() {
+        if controls.bindings_mut(name).is_none() {
+            warn!("Unknown action {:?} in [controls], ignoring it", name);
+        }
+    }
+
+    for name in cfg.gamepad.keys() {
+        if controls.gamepad.bindings_mut(name).is_none() {
+            warn!("Unknown action {:?} in [gamepad], ignoring it", name);
+        }
+    }
+
+    controls
+}
+
+fn parse_bindings<B: Clone>(
+    section: &str,
+    name: &str,
+    entries: &HashMap<String, BindingList>,
+    defaults: &Vec<B>,
+    parse: fn(&str) -> Result<B, String>
+) -> Vec<B> {
+    let entries = match entries.get(name) {
+        Some(list) => list.entries(),
+        None => return defaults.clone()
+    };
+
+    let mut parsed = vec![];
+    for entry in entries.iter() {
+        match parse(entry) {
+            Ok(binding) => parsed.push(binding),
+            Err(e) => warn!("Invalid binding for {} in [{}]: {}", name, section, e)
+        }
+    }
+
+    if parsed.is_empty() {
+        warn!("No valid bindings for {} in [{}], falling back to the default", name, section);
+        parsed = defaults.clone();
+    }
+
+    parsed
+}
+
+fn apply_display_settings(
+    settings: Res<GameSettings>,
+    mut windows: ResMut<Windows>
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let window = match windows.get_primary_mut() {
+        Some(w) => w,
+        None => return
+    };
+
+    if settings.fullscreen {
+        window.set_mode(WindowMode::BorderlessFullscreen);
+    } else {
+        window.set_mode(WindowMode::Windowed);
+        window.set_resolution(settings.resolution.x, settings.resolution.y);
+    }
+}
+
+pub fn config_file_path() -> PathBuf {
+    FileAssetIo::get_base_path().join("assets").join("config.toml")
+}
+
+const CONFIG_HEADER: &str = "\
+# Keys use their names, e.g. \"A\", \"Space\", \"Left\", \"F1\", \"Key1\", \"Comma\", \"LShift\".
+# Mouse buttons are \"MouseLeft\", \"MouseRight\", \"MouseMiddle\" or \"MouseOther4\" and up.
+# Modifiers are joined with \"+\", e.g. \"Ctrl+J\".
+# An action can have several bindings: jump = [\"Space\", \"W\"]
+";
+
+const GAMEPAD_HEADER: &str = "\
+# Buttons: South, East, North, West, LeftBumper, RightBumper, LeftTrigger,
+# RightTrigger, Select, Start, LeftThumb, RightThumb, DPadUp, DPadDown,
+# DPadLeft, DPadRight. Sticks: LeftStickLeft, LeftStickRight, LeftStickUp,
+# LeftStickDown and the same for RightStick.
+";
+
+const RESPAWN_HEADER: &str = "\
+# penalty is \"coins\" to lose coin_loss (0.0 to 1.0) of your coins on every
+# respawn, or \"lives\" to get a game over after that many respawns.
+";
+
+const TRANSITION_HEADER: &str = "\
+# effect is \"fade\", \"wipe\", \"iris\", \"pixelate\" or \"none\", for exits that
+# don't pick their own in the level editor. cover, pause and reveal are in seconds.
+";
+
+const CAMERA_HEADER: &str = "\
+# Distances are in pixels. The camera only follows once the player is further
+# than dead_zone_x/dead_zone_y from it, and looks look_ahead pixels ahead of
+# where the player is facing. The speeds are how fast it catches up.
+";
+
+// Each section goes through serde on its own, so the comments above them and
+// the order of the actions survive a round trip
+fn push_section<T: Serialize>(out: &mut String, comment: &str, name: &str, section: &T) -> Result<(), toml::ser::Error> {
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    out.push_str(comment);
+    out.push_str(&format!("[{}]\n", name));
+    out.push_str(&toml::to_string(section)?);
+
+    Ok(())
+}
+
+pub fn config_to_toml(
+    controls: &PlayerControls,
+    settings: &GameSettings,
+    volume: &VolumeSettings,
+    respawn: &RespawnSettings,
+    transition: &TransitionSettings,
+    camera: &CameraSettings
+) -> Result<String, toml::ser::Error> {
+    let keys = BindingTable(controls
+        .actions()
+        .into_iter()
+        .map(|(name, bindings, _)| (name, BindingList::new(bindings.iter().map(|b| b.config_name()).collect())))
+        .collect());
+
+    let gamepad = BindingTable(controls.gamepad
+        .actions()
+        .into_iter()
+        .map(|(name, bindings, _)| (name, BindingList::new(bindings.iter().map(|b| b.config_name().to_string()).collect())))
+        .collect());
+
+    let display = DisplayConfig {
+        fullscreen: settings.fullscreen,
+        width: settings.resolution.x,
+        height: settings.resolution.y
+    };
+
+    let fx = FxConfig {
+        screen_shake: settings.screen_shake
+    };
+
+    let mut out = String::new();
+
+    push_section(&mut out, CONFIG_HEADER, "controls", &keys)?;
+    push_section(&mut out, GAMEPAD_HEADER, "gamepad", &gamepad)?;
+    push_section(&mut out, "", "display", &display)?;
+    push_section(&mut out, "", "fx", &fx)?;
+    push_section(&mut out, "", "audio", volume)?;
+    push_section(&mut out, RESPAWN_HEADER, "respawn", respawn)?;
+    push_section(&mut out, TRANSITION_HEADER, "transition", transition)?;
+    push_section(&mut out, CAMERA_HEADER, "camera", camera)?;
+
+    Ok(out)
+}
+
+pub fn write_config(
+    controls: &PlayerControls,
+    settings: &GameSettings,
+    volume: &VolumeSettings,
+    respawn: &RespawnSettings,
+    transition: &TransitionSettings,
+    camera: &CameraSettings
+) {
+    let path = config_file_path();
+
+    let text = match config_to_toml(controls, settings, volume, respawn, transition, camera) {
+        Ok(text) => text,
+        Err(e) => {
+            error!("Failed to serialize the config: {}", e);
+            return;
+        }
+    };
+
+    if let Err(e) = fs::write(&path, text) {
+        error!("Failed to write config file {:?}: {}", path, e);
+    }
+}
@@ -4,18 +4,21 @@ use std::time::Duration;
 
 use crate::anim::{AnimationChangeEvent, Animator};
 use crate::anim::map::AnimationMap;
-use crate::enemies::Enemy;
+use crate::enemies::{Enemy, EnemySystems};
 use crate::state::GameState;
 use crate::entity_states::*;
 use crate::pathfind::{Pathfinder, Patrol};
+use crate::common::UpdateStage;
 
 pub fn register_enemy_animations(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(EnemySystems::Animation)
+            .after(EnemySystems::Behaviour)
             .with_system(enemy_idle_on_patrol_pause)
-            .with_system(enemy_move_on_patrol_resume)
-            .with_system(enemy_idle)
-            .with_system(enemy_move)
+            .with_system(enemy_move_on_patrol_resume.after(enemy_idle_on_patrol_pause))
+            .with_system(enemy_idle.after(enemy_move_on_patrol_resume))
+            .with_system(enemy_move.after(enemy_idle))
     );
 }
 
@@ -1,5 +1,4 @@
 pub mod state_machine;
-pub mod stats;
 
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
@@ -12,6 +11,8 @@ use crate::assets::DandelionEnemyAssets;
 use crate::coin::drops::CoinHolder;
 use crate::combat::{AttackStrength, ColliderAttackBundle, CombatLayerMask, Health, HurtAbility, Immunity};
 use crate::enemies::Enemy;
+use crate::enemies::registry::EnemyKind;
+use crate::enemies::spawner::{configure_enemy, EnemySpawnEvent, EnemyType};
 use crate::enemies::stats::EnemyStats;
 use crate::pathfind::{util::BoundingBox, Pathfinder, PathfinderBundle};
 
@@ -21,6 +22,22 @@ use crate::util::Facing;
 #[derive(Component, Copy, Clone)]
 pub struct DandelionEnemy;
 
+impl EnemyKind for DandelionEnemy {
+    const TYPE: EnemyType = EnemyType("Dandelion");
+    type Assets = DandelionEnemyAssets;
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &DandelionEnemyAssets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity {
+        let mut bundle = DandelionEnemyBundle::from_assets(assets);
+        configure_enemy(&mut bundle.enemy, ev);
+        DandelionEnemyBundle::spawn_with_stats(commands, bundle, stats)
+    }
+}
+
 #[derive(Bundle)]
 pub struct DandelionEnemyBundle {
     #[bundle]
@@ -4,15 +4,18 @@ use crate::anim::{AnimationChangeEvent};
 use crate::anim::map::AnimationMap;
 
 
+use crate::enemies::EnemySystems;
 use crate::enemies::flower::FlowerEnemy;
 use crate::enemies::flower::state_machine::Detonate;
 use crate::state::GameState;
 use crate::entity_states::*;
+use crate::common::UpdateStage;
 
 
 pub fn register_flower_enemy_animations(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .after(EnemySystems::Animation)
             .with_system(flower_enemy_detonate)
     );
 }
@@ -1,4 +1,3 @@
-pub mod stats;
 pub mod state_machine;
 mod anim;
 
@@ -14,6 +13,8 @@ use crate::{
 use crate::coin::drops::CoinHolder;
 use crate::combat::{AttackStrength, ColliderAttackBundle, Immunity};
 use crate::enemies::flower::state_machine::register_flower_enemy_state_machine;
+use crate::enemies::registry::EnemyKind;
+use crate::enemies::spawner::{configure_enemy, EnemySpawnEvent, EnemyType};
 use crate::enemies::stats::EnemyStats;
 use crate::anim::Animator;
 
@@ -22,9 +23,25 @@ use crate::util::Facing;
 
 
 
-pub fn register_flower_enemy(app: &mut App) {
-    register_flower_enemy_state_machine(app);
-    register_flower_enemy_animations(app);
+impl EnemyKind for FlowerEnemy {
+    const TYPE: EnemyType = EnemyType("Flower");
+    type Assets = FlowerEnemyAssets;
+
+    fn register(app: &mut App) {
+        register_flower_enemy_state_machine(app);
+        register_flower_enemy_animations(app);
+    }
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &FlowerEnemyAssets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity {
+        let mut bundle = FlowerEnemyBundle::from_assets(assets);
+        configure_enemy(&mut bundle.enemy, ev);
+        FlowerEnemyBundle::spawn_with_stats(commands, bundle, stats)
+    }
 }
 
 
@@ -2,13 +2,14 @@ use bevy::prelude::*;
 use seldom_state::prelude::*;
 use crate::coin::drops::CoinHolder;
 use crate::combat::{ColliderAttack, CombatLayerMask, ExplosionEvent, Immunity};
-use crate::enemies::Enemy;
+use crate::enemies::{Enemy, EnemySystems};
 use crate::enemies::flower::FlowerEnemy;
 use crate::entity_states::*;
 use crate::fx::indicator::Indicator;
-use crate::pathfind::{Pathfinder, Region};
+use crate::pathfind::{Pathfinder, PathfinderSystems, Region};
 pub use crate::pathfind::state_machine::*;
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 
 const BOOM_SIZE: f32 = 72.0;
@@ -46,10 +47,12 @@ pub fn register_flower_enemy_state_machine(app: &mut App) {
     app
         .add_plugin(TriggerPlugin::<DetonateTrigger>::default())
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .label(EnemySystems::Behaviour)
+                .after(PathfinderSystems::Fly)
                 .with_system(flower_enemy_detonate)
-                .with_system(flower_enemy_tick)
-                .with_system(flower_enemy_disable_collider_on_detonate)
+                .with_system(flower_enemy_tick.after(flower_enemy_detonate))
+                .with_system(flower_enemy_disable_collider_on_detonate.after(flower_enemy_detonate))
         );
 }
 
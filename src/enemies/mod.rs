@@ -4,12 +4,13 @@ use bevy_rapier2d::prelude::*;
 use crate::anim::{AnimationChangeEvent, Animator};
 use crate::anim::map::AnimationMap;
 use crate::coin::drops::CoinHolder;
-use crate::combat::{ColliderAttack, CombatLayerMask, Health, HurtAbility, Immunity};
+use crate::common::UpdateStage;
+use crate::combat::{ColliderAttack, CombatLayerMask, despawn_dead_entities, Health, HurtAbility, Immunity};
 use crate::enemies::anim::register_enemy_animations;
-use crate::enemies::flower::register_flower_enemy;
-use crate::enemies::pumpkin::register_pumpkin_enemy;
+use crate::enemies::registry::register_enemy_types;
 
 use crate::enemies::spawner::register_enemy_spawner;
+use crate::enemies::stats::register_enemy_stats;
 use crate::entity_states::Die;
 use crate::fx::smoke::SmokeEvent;
 use crate::pathfind::PathfinderBundle;
@@ -20,11 +21,21 @@ pub mod flower;
 pub mod pumpkin;
 pub mod dandelion;
 pub mod tumbleweed;
+pub mod mushroom;
 
 pub mod stats;
 pub mod spawner;
+pub mod registry;
 mod anim;
 
+// Enemy systems run after the pathfinders in `UpdateStage::GameLogic`: what
+// each kind does on top of pathfinding, then the animations that follow from it
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum EnemySystems {
+    Behaviour,
+    Animation
+}
+
 #[derive(Default, Component, Clone, Copy)]
 pub struct Enemy {
     pub vel: Vec2,
@@ -63,17 +74,19 @@ impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_system_set(
-                SystemSet::on_update(GameState::Gameplay)
-                    .with_system(move_enemies)
+                UpdateStage::Combat.on_update(GameState::Gameplay)
                     .with_system(enemies_died)
-                    .with_system(enemies_despawn)
+                    .with_system(enemies_despawn.before(despawn_dead_entities))
+            )
+            .add_system_set(
+                UpdateStage::Physics.on_update(GameState::Gameplay)
+                    .with_system(move_enemies)
             );
 
+        register_enemy_stats(app);
         register_enemy_spawner(app);
         register_enemy_animations(app);
-
-        register_flower_enemy(app);
-        register_pumpkin_enemy(app);
+        register_enemy_types(app);
     }
 }
 
@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use crate::anim::{AnimationChangeEvent, Animator};
+use crate::anim::map::AnimationMap;
+use crate::enemies::EnemySystems;
+use crate::enemies::mushroom::MushroomEnemy;
+use crate::enemies::mushroom::state_machine::{Burrow, Emerge};
+use crate::state::GameState;
+use crate::entity_states::*;
+use crate::common::UpdateStage;
+
+pub fn register_mushroom_enemy_animations(app: &mut App) {
+    app.add_system_set(
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .after(EnemySystems::Animation)
+            .with_system(mushroom_enemy_burrow)
+            .with_system(mushroom_enemy_emerge.after(mushroom_enemy_burrow))
+            .with_system(mushroom_enemy_finish_anims.after(mushroom_enemy_emerge))
+    );
+}
+
+fn mushroom_enemy_burrow(
+    q: Query<(&AnimationMap, Entity), (Added<Burrow>, With<MushroomEnemy>, Without<Die>)>,
+    mut ev: EventWriter<AnimationChangeEvent>
+) {
+    for (anims, mushroom) in q.iter() {
+        ev.send(AnimationChangeEvent {
+            e: mushroom,
+            new_anim: anims["BURROW"].clone()
+        });
+    }
+}
+
+fn mushroom_enemy_emerge(
+    q: Query<(&AnimationMap, Entity), (Added<Emerge>, With<MushroomEnemy>, Without<Die>)>,
+    mut ev: EventWriter<AnimationChangeEvent>
+) {
+    for (anims, mushroom) in q.iter() {
+        ev.send(AnimationChangeEvent {
+            e: mushroom,
+            new_anim: anims["EMERGE"].clone()
+        });
+    }
+}
+
+// BURROW -> BURROWED and EMERGE -> IDLE once they've played through
+fn mushroom_enemy_finish_anims(
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    q: Query<(&AnimationMap, &Animator, Entity), (With<MushroomEnemy>, Without<Die>)>,
+    mut ev: EventWriter<AnimationChangeEvent>
+) {
+    for (anims, animator, mushroom) in q.iter() {
+        let next = match animator.anim.name.as_str() {
+            "BURROW" => "BURROWED",
+            "EMERGE" => "IDLE",
+            _ => continue
+        };
+
+        let frame_count = texture_atlases.get(&animator.anim.tex).unwrap().textures.len();
+        if animator.total_frames >= frame_count as u32 - 1 {
+            ev.send(AnimationChangeEvent {
+                e: mushroom,
+                new_anim: anims[next].clone()
+            });
+        }
+    }
+}
@@ -0,0 +1,177 @@
+pub mod state_machine;
+mod anim;
+
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    enemies::{EnemyBundle, Enemy},
+    assets::MushroomEnemyAssets,
+    combat::{CombatLayerMask, Health, HurtAbility},
+    pathfind::{Pathfinder, PathfinderBundle, util::BoundingBox, walk::WalkPathfinder, MeleePathfinder}
+};
+use crate::coin::drops::CoinHolder;
+use crate::combat::{AttackStrength, ColliderAttackBundle, Immunity};
+use crate::enemies::registry::EnemyKind;
+use crate::enemies::spawner::{configure_enemy, EnemySpawnEvent, EnemyType};
+use crate::enemies::stats::{CustomEnemyStats, EnemyStats};
+use crate::anim::Animator;
+use crate::enemies::mushroom::anim::register_mushroom_enemy_animations;
+use crate::enemies::mushroom::state_machine::register_mushroom_enemy_state_machine;
+use crate::util::Facing;
+
+
+impl EnemyKind for MushroomEnemy {
+    const TYPE: EnemyType = EnemyType("Mushroom");
+    type Assets = MushroomEnemyAssets;
+
+    fn register(app: &mut App) {
+        register_mushroom_enemy_state_machine(app);
+        register_mushroom_enemy_animations(app);
+    }
+
+    fn check_stats(stats: &EnemyStats) -> Result<(), String> {
+        match stats.custom {
+            CustomEnemyStats::Burrow(_) => Ok(()),
+            other => Err(format!("expected Burrow stats, found {}", other.name()))
+        }
+    }
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &MushroomEnemyAssets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity {
+        let mut bundle = MushroomEnemyBundle::from_assets(assets);
+        configure_enemy(&mut bundle.enemy, ev);
+        MushroomEnemyBundle::spawn_with_stats(commands, bundle, stats)
+    }
+}
+
+
+// Walks around like any other melee enemy, but once the player gets close it
+// burrows, tunnels over to them and pops back up inside a spore cloud
+#[derive(Component, Debug, Clone)]
+pub struct MushroomEnemy {
+    pub burrow_timer: Timer,
+    pub surface_timer: Timer,
+    pub burrow_cooldown: Timer,
+
+    pub burrow_dist: f32,
+    pub burrow_speed: f32,
+
+    pub spore_power: i32,
+    pub spore_size: Vec2
+}
+
+impl Default for MushroomEnemy {
+    fn default() -> Self {
+        Self {
+            burrow_timer: Timer::from_seconds(1.4, TimerMode::Once),
+            surface_timer: Timer::from_seconds(1.0, TimerMode::Once),
+            burrow_cooldown: Timer::from_seconds(3.0, TimerMode::Once),
+
+            burrow_dist: 0.0,
+            burrow_speed: 0.0,
+
+            spore_power: 0,
+            spore_size: Vec2::new(96.0, 64.0)
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct MushroomEnemyBundle {
+    #[bundle]
+    pub enemy: EnemyBundle,
+    pub mushroom: MushroomEnemy,
+    pub walk: WalkPathfinder,
+    pub melee_pathfinder: MeleePathfinder
+}
+
+impl MushroomEnemyBundle {
+    pub fn collider_attack(collision_dmg: i32) -> ColliderAttackBundle {
+        ColliderAttackBundle {
+            combat_layer: CombatLayerMask::ENEMY,
+            strength: AttackStrength::new(collision_dmg),
+            ..ColliderAttackBundle::from_size(Vec2::new(32.0, 32.0))
+        }
+    }
+
+    pub fn spawn_with_stats(commands: &mut Commands, mut item: Self, stats: EnemyStats) -> Entity {
+        item.enemy.health.hp = stats.health;
+        item.enemy.path.pathfinder.speed = stats.speed;
+        item.enemy.path.pathfinder.patrol_speed = stats.patrol_speed;
+        item.walk.jump_speed = stats.jump_speed;
+        item.mushroom.spore_power = stats.attack_damage;
+
+        // always there, `spawn_enemies` checks first
+        if let CustomEnemyStats::Burrow(extra) = stats.custom {
+            item.mushroom.burrow_dist = extra.burrow_dist;
+            item.mushroom.burrow_speed = extra.burrow_speed;
+            item.mushroom.burrow_timer.set_duration(Duration::from_secs_f32(extra.burrow_time));
+            item.mushroom.surface_timer.set_duration(Duration::from_secs_f32(extra.surface_time));
+            item.mushroom.burrow_cooldown.set_duration(Duration::from_secs_f32(extra.burrow_cd));
+        }
+
+        commands.spawn(item).with_children(|p| {
+            p.spawn(Self::collider_attack(stats.collision_damage));
+        }).id()
+    }
+
+    pub fn from_assets(assets: &MushroomEnemyAssets) -> Self {
+        MushroomEnemyBundle {
+            enemy: EnemyBundle {
+                anim_map: assets.map.clone(),
+                anim: Animator::new(assets.map["IDLE"].clone()),
+
+                facing: Facing::default(),
+                immunity: Immunity::default(),
+                coins: CoinHolder::default(),
+                collider: Collider::cuboid(32.0, 32.0),
+                rigid_body: RigidBody::KinematicPositionBased,
+
+                character_controller: KinematicCharacterController {
+                    slide: true,
+                    snap_to_ground: Some(CharacterLength::Relative(0.2)),
+                    offset: CharacterLength::Relative(0.02),
+                    filter_flags: QueryFilterFlags::EXCLUDE_SENSORS,
+                    ..default()
+                },
+
+                state_machine: state_machine::mushroom_enemy_state_machine(),
+
+                sprite_sheet: SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        custom_size: Some(Vec2::new(112.0, 112.0)),
+                        ..default()
+                    },
+                    texture_atlas: assets.map["IDLE"].clone().tex,
+                    transform: Transform::from_translation(Vec3::new(0.0, 0.0, 1.0)),
+                    ..default()
+                },
+
+                enemy: Enemy::default(),
+                sensor: Sensor,
+
+                path: PathfinderBundle {
+                    pathfinder: Pathfinder {
+                        bb: BoundingBox::new(24.0, 24.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+
+                combat_layer: CombatLayerMask::ENEMY,
+                hurt_ability: HurtAbility::new(0.5, None),
+                health: Health::default(),
+            },
+
+            mushroom: MushroomEnemy::default(),
+            walk: WalkPathfinder::default(),
+            melee_pathfinder: MeleePathfinder,
+        }
+    }
+}
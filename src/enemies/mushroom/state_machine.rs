@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use seldom_state::prelude::*;
+use crate::combat::{AttackStrength, ColliderAttack, CombatLayerMask, DeathTrigger, HurtTrigger, Immunity, SporeCloudAttackBundle};
+use crate::enemies::{Enemy, EnemySystems};
+use crate::enemies::mushroom::MushroomEnemy;
+use crate::entity_states::*;
+use crate::pathfind::{Pathfinder, PathfinderSystems};
+pub use crate::pathfind::state_machine::*;
+use crate::state::GameState;
+use crate::util::{Facing, FacingX};
+use crate::common::UpdateStage;
+
+
+#[derive(Copy, Clone, Debug, Reflect, Component)]
+pub struct Burrow;
+
+#[derive(Copy, Clone, Debug, Reflect, Component)]
+pub struct Emerge;
+
+#[derive(Copy, Clone, Debug, Reflect, FromReflect, Component)]
+pub struct BurrowTrigger;
+
+impl Trigger for BurrowTrigger {
+    type Param<'w, 's> = Query<'w, 's,
+        (&'static Pathfinder, &'static GlobalTransform, &'static MushroomEnemy)
+    >;
+
+    fn trigger(&self, entity: Entity, q: &Self::Param<'_, '_>) -> bool {
+        if !q.contains(entity) {
+            return false;
+        }
+
+        let (pathfinder, tf, mushroom) = q.get(entity).unwrap();
+        let pos = tf.translation();
+
+        if !mushroom.burrow_cooldown.finished() {
+            return false;
+        }
+
+        if let Some(target) = pathfinder.target {
+            return target.distance(Vec2::new(pos.x, pos.y)) <= mushroom.burrow_dist;
+        }
+
+        false
+    }
+}
+
+pub fn register_mushroom_enemy_state_machine(app: &mut App) {
+    app
+        .add_plugin(TriggerPlugin::<BurrowTrigger>::default())
+        .add_system_set(
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .label(EnemySystems::Behaviour)
+                .after(PathfinderSystems::Fly)
+                .with_system(mushroom_enemy_cooldown)
+                .with_system(mushroom_enemy_burrow.after(mushroom_enemy_cooldown))
+                .with_system(mushroom_enemy_tunnel.after(mushroom_enemy_burrow))
+                .with_system(mushroom_enemy_emerge.after(mushroom_enemy_tunnel))
+                .with_system(mushroom_enemy_surface.after(mushroom_enemy_emerge))
+                .with_system(mushroom_enemy_resume_pathfinding.after(mushroom_enemy_surface))
+        );
+}
+
+fn set_collider_attacks(
+    children: &Children,
+    attacks: &mut Query<&mut ColliderAttack>,
+    enabled: bool
+) {
+    for child in children.iter() {
+        if let Ok(mut atk) = attacks.get_mut(*child) {
+            atk.enabled = enabled;
+        }
+    }
+}
+
+fn mushroom_enemy_cooldown(
+    time: Res<Time>,
+    mut q: Query<&mut MushroomEnemy, (Without<Burrow>, Without<Emerge>, Without<Die>)>
+) {
+    for mut mushroom in q.iter_mut() {
+        mushroom.burrow_cooldown.tick(time.delta());
+    }
+}
+
+// Can't be hit or hurt anyone while underground
+fn mushroom_enemy_burrow(
+    mut q: Query<(
+        &Children,
+        &mut Pathfinder,
+        &mut Enemy,
+        &mut MushroomEnemy,
+        &mut Immunity
+    ), Added<Burrow>>,
+    mut attacks: Query<&mut ColliderAttack>
+) {
+    for (children, mut pathfinder, mut enemy, mut mushroom, mut immunity) in q.iter_mut() {
+        pathfinder.active = false;
+        enemy.vel.x = 0.0;
+        immunity.is_immune = true;
+        mushroom.burrow_timer.reset();
+
+        set_collider_attacks(children, &mut attacks, false);
+    }
+}
+
+// Heads for the player, but never leaves its patrol region
+fn mushroom_enemy_tunnel(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut q: Query<(
+        Entity,
+        &GlobalTransform,
+        &Pathfinder,
+        &mut Enemy,
+        &mut MushroomEnemy,
+        &mut Facing
+    ), (With<Burrow>, Without<Die>)>
+) {
+    for (entity, tf, pathfinder, mut enemy, mut mushroom, mut facing) in q.iter_mut() {
+        mushroom.burrow_timer.tick(time.delta());
+
+        if mushroom.burrow_timer.just_finished() {
+            enemy.vel.x = 0.0;
+            commands.entity(entity).insert(Done::Success);
+            continue;
+        }
+
+        let pos = tf.translation();
+        let target_x = match pathfinder.target {
+            Some(target) => target.x.clamp(pathfinder.region.tl.x, pathfinder.region.br.x),
+            None => pos.x
+        };
+
+        let dx = target_x - pos.x;
+        if dx.abs() <= 2.0 {
+            enemy.vel.x = 0.0;
+            continue;
+        }
+
+        enemy.vel.x = dx.signum() * mushroom.burrow_speed;
+        facing.x = if dx < 0.0 { FacingX::Left } else { FacingX::Right };
+    }
+}
+
+fn mushroom_enemy_emerge(
+    mut commands: Commands,
+    mut q: Query<(
+        &GlobalTransform,
+        &Children,
+        &mut MushroomEnemy,
+        &mut Immunity
+    ), Added<Emerge>>,
+    mut attacks: Query<&mut ColliderAttack>
+) {
+    for (tf, children, mut mushroom, mut immunity) in q.iter_mut() {
+        let pos = tf.translation();
+
+        immunity.is_immune = false;
+        mushroom.surface_timer.reset();
+        mushroom.burrow_cooldown.reset();
+
+        set_collider_attacks(children, &mut attacks, true);
+
+        commands.spawn(SporeCloudAttackBundle {
+            strength: AttackStrength::new(mushroom.spore_power),
+            combat_layer: CombatLayerMask::ENEMY,
+            ..SporeCloudAttackBundle::new(
+                Vec2::new(pos.x, pos.y + mushroom.spore_size.y / 4.0),
+                mushroom.spore_size
+            )
+        });
+    }
+}
+
+fn mushroom_enemy_surface(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut q: Query<(Entity, &mut MushroomEnemy), (With<Emerge>, Without<Die>)>
+) {
+    for (entity, mut mushroom) in q.iter_mut() {
+        mushroom.surface_timer.tick(time.delta());
+
+        if mushroom.surface_timer.just_finished() {
+            commands.entity(entity).insert(Done::Success);
+        }
+    }
+}
+
+// Whatever comes after emerging (or getting hit while emerging), the
+// pathfinder takes over again
+fn mushroom_enemy_resume_pathfinding(
+    mut q: Query<&mut Pathfinder, (With<MushroomEnemy>, Or<(Added<Fall>, Added<Hurt>)>)>
+) {
+    for mut pathfinder in q.iter_mut() {
+        pathfinder.active = true;
+    }
+}
+
+pub fn mushroom_enemy_state_machine() -> StateMachine {
+    melee_pathfinder_state_machine()
+        .trans::<Move>(BurrowTrigger, Burrow)
+        .trans::<Burrow>(DoneTrigger::Success, Emerge)
+        .trans::<Burrow>(DeathTrigger, Die::default())
+
+        .trans::<Emerge>(DoneTrigger::Success, Fall)
+        .trans::<Emerge>(HurtTrigger, Hurt)
+        .trans::<Emerge>(DeathTrigger, Die::default())
+}
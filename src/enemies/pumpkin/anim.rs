@@ -1,17 +1,19 @@
 use bevy::prelude::*;
 use crate::anim::{AnimationChangeEvent, Animator};
 use crate::anim::map::AnimationMap;
-use crate::enemies::Enemy;
+use crate::enemies::{Enemy, EnemySystems};
 use crate::enemies::pumpkin::PumpkinEnemy;
 use crate::state::GameState;
 use crate::entity_states::*;
+use crate::common::UpdateStage;
 
 pub fn register_pumpkin_enemy_animations(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .after(EnemySystems::Animation)
             .with_system(pumpkin_enemy_shoot)
-            .with_system(pumpkin_enemy_idle_after_shoot)
-            .with_system(pumpkin_enemy_move_after_shoot_wait)
+            .with_system(pumpkin_enemy_idle_after_shoot.after(pumpkin_enemy_shoot))
+            .with_system(pumpkin_enemy_move_after_shoot_wait.after(pumpkin_enemy_idle_after_shoot))
     );
 }
 
@@ -1,5 +1,4 @@
 pub mod state_machine;
-pub mod stats;
 mod anim;
 
 use std::time::Duration;
@@ -14,14 +13,39 @@ use crate::{
 };
 use crate::coin::drops::CoinHolder;
 use crate::combat::{AttackStrength, ColliderAttackBundle, Immunity, ProjectileAttack, ProjectileAttackBundle};
+use crate::enemies::registry::EnemyKind;
+use crate::enemies::spawner::{configure_enemy, EnemySpawnEvent, EnemyType};
 use crate::enemies::stats::{CustomEnemyStats, EnemyStats};
 use crate::util::{deg_to_rad, Facing};
 use crate::anim::Animator;
 use crate::enemies::pumpkin::anim::register_pumpkin_enemy_animations;
 
 
-pub fn register_pumpkin_enemy(app: &mut App) {
-    register_pumpkin_enemy_animations(app);
+impl EnemyKind for PumpkinEnemy {
+    const TYPE: EnemyType = EnemyType("Pumpkin");
+    type Assets = PumpkinEnemyAssets;
+
+    fn register(app: &mut App) {
+        register_pumpkin_enemy_animations(app);
+    }
+
+    fn check_stats(stats: &EnemyStats) -> Result<(), String> {
+        match stats.custom {
+            CustomEnemyStats::Ranged(_) => Ok(()),
+            other => Err(format!("expected Ranged stats, found {}", other.name()))
+        }
+    }
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &PumpkinEnemyAssets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity {
+        let mut bundle = PumpkinEnemyBundle::from_assets(assets);
+        configure_enemy(&mut bundle.enemy, ev);
+        PumpkinEnemyBundle::spawn_with_stats(commands, bundle, stats)
+    }
 }
 
 
@@ -65,15 +89,13 @@ impl PumpkinEnemyBundle {
         item.walk.jump_speed = stats.jump_speed;
         item.ranged_pathfinder.projectile.strength.power = stats.attack_damage;
 
-        let extra = match stats.custom {
-            CustomEnemyStats::Ranged(ranged) => ranged,
-            _ => panic!("Ranged pathfinder not configured with ranged stats!")
-        };
-
-        item.ranged_pathfinder.max_shoot_distance = extra.max_shoot_dist;
-        item.ranged_pathfinder.shoot_pause.set_duration(Duration::from_secs_f32(extra.atk_pause));
-        item.ranged_pathfinder.shoot_cooldown.set_duration(Duration::from_secs_f32(extra.atk_cd));
-        item.ranged_pathfinder.projectile.attack.speed = extra.proj_speed;
+        // always there, `spawn_enemies` checks first
+        if let CustomEnemyStats::Ranged(extra) = stats.custom {
+            item.ranged_pathfinder.max_shoot_distance = extra.max_shoot_dist;
+            item.ranged_pathfinder.shoot_pause.set_duration(Duration::from_secs_f32(extra.atk_pause));
+            item.ranged_pathfinder.shoot_cooldown.set_duration(Duration::from_secs_f32(extra.atk_cd));
+            item.ranged_pathfinder.projectile.attack.speed = extra.proj_speed;
+        }
 
         commands.spawn(item).with_children(|p| {
             p.spawn(Self::collider_attack(stats.collision_damage));
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use bevy::ecs::system::CommandQueue;
+use bevy::prelude::*;
+use crate::enemies::dandelion::DandelionEnemy;
+use crate::enemies::flower::FlowerEnemy;
+use crate::enemies::mushroom::MushroomEnemy;
+use crate::enemies::pumpkin::PumpkinEnemy;
+use crate::enemies::spawner::{EnemySpawnEvent, EnemyType};
+use crate::enemies::tumbleweed::TumbleweedEnemy;
+use crate::enemies::stats::EnemyStats;
+use crate::level::memory::SpawnedFrom;
+
+// Everything the game needs to know about an enemy type lives behind this
+// trait, implemented on the enemy's marker component. Adding a new enemy
+// means writing its module and adding its marker to `enemy_kinds!`; LDtk
+// spawnpoints, the boss summons and the stat table all find it by `TYPE`'s
+// name from then on.
+pub trait EnemyKind: Component {
+    // Also the enemy's value in the LDtk `EnemyType` enum and its key in
+    // `enemies.stats.toml`
+    const TYPE: EnemyType;
+
+    type Assets: EnemyAssets;
+
+    // State machines, triggers and animation systems
+    fn register(app: &mut App) {
+        let _ = app;
+    }
+
+    // Whether `spawn` can use these, e.g. that they have the custom stats
+    // it reads. Checked when the stat table loads and before every spawn.
+    fn check_stats(stats: &EnemyStats) -> Result<(), String> {
+        let _ = stats;
+        Ok(())
+    }
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &Self::Assets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity;
+}
+
+// The one list of enemy kinds, passed to `$then` as a comma separated list of
+// types. Both `ENEMY_TYPES` and `register_enemy_types` are built from it so
+// they can't drift apart.
+macro_rules! enemy_kinds {
+    ($then:ident) => {
+        $then!(
+            FlowerEnemy,
+            PumpkinEnemy,
+            DandelionEnemy,
+            TumbleweedEnemy,
+            MushroomEnemy
+        )
+    }
+}
+
+macro_rules! enemy_type_list {
+    ($($kind:ty),*) => {
+        &[$(<$kind as EnemyKind>::TYPE),*]
+    }
+}
+
+// Every enemy type there is, for code that only needs their names and has no
+// `App` to fill a registry with, like the level linter
+pub const ENEMY_TYPES: &[EnemyType] = enemy_kinds!(enemy_type_list);
+
+pub fn register_enemy_types(app: &mut App) {
+    macro_rules! register_each {
+        ($($kind:ty),*) => {
+            $(app.register_enemy::<$kind>();)*
+        }
+    }
+
+    enemy_kinds!(register_each);
+}
+
+pub fn enemy_type(name: &str) -> Result<EnemyType, String> {
+    ENEMY_TYPES
+        .iter()
+        .find(|ty| ty.name() == name)
+        .copied()
+        .ok_or_else(|| unknown_type(name, ENEMY_TYPES.iter().map(EnemyType::name).collect()))
+}
+
+fn unknown_type(name: &str, mut known: Vec<&str>) -> String {
+    known.sort();
+    format!("Unknown enemy type {:?} (known types: {})", name, known.join(", "))
+}
+
+pub trait EnemyAssets: Resource + Default {
+    fn load(
+        asset_server: Res<AssetServer>,
+        texture_atlases: ResMut<Assets<TextureAtlas>>,
+        assets: ResMut<Self>,
+    );
+}
+
+#[derive(Copy, Clone)]
+pub struct EnemyDescriptor {
+    pub ty: EnemyType,
+    check_stats: fn(&EnemyStats) -> Result<(), String>,
+    spawn: fn(&mut World, &EnemySpawnEvent, EnemyStats)
+}
+
+impl EnemyDescriptor {
+    pub fn check_stats(&self, stats: &EnemyStats) -> Result<(), String> {
+        (self.check_stats)(stats)
+    }
+
+    // Spawns straight into the world, so this runs from a command
+    pub fn spawn(&self, world: &mut World, ev: &EnemySpawnEvent, stats: EnemyStats) {
+        (self.spawn)(world, ev, stats);
+    }
+}
+
+#[derive(Resource, Default, Clone)]
+pub struct EnemyRegistry {
+    enemies: HashMap<&'static str, EnemyDescriptor>
+}
+
+impl EnemyRegistry {
+    pub fn get(&self, name: &str) -> Result<&EnemyDescriptor, String> {
+        self.enemies.get(name).ok_or_else(|| unknown_type(name, self.names()))
+    }
+
+    pub fn lookup(&self, name: &str) -> Result<EnemyType, String> {
+        self.get(name).map(|desc| desc.ty)
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.enemies.keys().copied().collect()
+    }
+}
+
+pub trait RegisterEnemy {
+    fn register_enemy<T: EnemyKind>(&mut self) -> &mut Self;
+}
+
+impl RegisterEnemy for App {
+    fn register_enemy<T: EnemyKind>(&mut self) -> &mut Self {
+        debug_assert!(ENEMY_TYPES.contains(&T::TYPE), "{:?} is missing from enemy_kinds!", T::TYPE);
+
+        self
+            .init_resource::<EnemyRegistry>()
+            .init_resource::<T::Assets>()
+            .add_startup_system(<T::Assets as EnemyAssets>::load.label("assets"));
+
+        T::register(self);
+
+        self.world
+            .resource_mut::<EnemyRegistry>()
+            .enemies
+            .insert(T::TYPE.name(), EnemyDescriptor {
+                ty: T::TYPE,
+                check_stats: T::check_stats,
+                spawn: spawn_enemy::<T>
+            });
+
+        self
+    }
+}
+
+fn spawn_enemy<T: EnemyKind>(world: &mut World, ev: &EnemySpawnEvent, stats: EnemyStats) {
+    let mut queue = CommandQueue::default();
+    let mut commands = Commands::new(&mut queue, world);
+
+    let assets = world.resource::<T::Assets>();
+    let id = T::spawn(&mut commands, assets, ev, stats);
+
+    commands.entity(id).insert(ev.ty);
+
+    if let Some(iid) = &ev.spawnpoint {
+        commands.entity(id).insert(SpawnedFrom(iid.clone()));
+    }
+
+    if let Some(func) = ev.extra_components {
+        func(&mut commands, id);
+    }
+
+    queue.apply(world);
+}
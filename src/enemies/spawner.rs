@@ -1,16 +1,11 @@
 use std::ops::Range;
 use bevy::prelude::*;
-use crate::assets::{DandelionEnemyAssets, FlowerEnemyAssets, PumpkinEnemyAssets, TumbleweedEnemyAssets};
-use crate::enemies::dandelion::DandelionEnemyBundle;
-use crate::enemies::dandelion::stats::{DANDELION_EASY, DANDELION_HARD, DANDELION_MEDIUM, DANDELION_MELLOW};
+use crate::common::UpdateStage;
 use crate::enemies::EnemyBundle;
-use crate::enemies::flower::FlowerEnemyBundle;
-use crate::enemies::flower::stats::{FLOWER_EASY, FLOWER_HARD, FLOWER_MEDIUM, FLOWER_MELLOW};
-use crate::enemies::pumpkin::PumpkinEnemyBundle;
-use crate::enemies::pumpkin::stats::{PUMPKIN_EASY, PUMPKIN_HARD, PUMPKIN_MEDIUM, PUMPKIN_MELLOW};
-use crate::enemies::tumbleweed::stats::{TUMBLEWEED_EASY, TUMBLEWEED_HARD, TUMBLEWEED_MEDIUM, TUMBLEWEED_MELLOW};
-use crate::enemies::tumbleweed::TumbleweedEnemyBundle;
+use crate::enemies::registry::EnemyRegistry;
+use crate::enemies::stats::{EnemyStatsHandle, EnemyStatTable};
 use crate::pathfind::Region;
+use crate::rng::{GameRng, RngStream};
 
 #[derive(Copy, Clone, Debug, Component)]
 pub enum EnemyDifficulty {
@@ -20,13 +15,14 @@ pub enum EnemyDifficulty {
     Hard
 }
 
-#[derive(Copy, Clone, Debug, Component)]
-pub enum EnemyType {
-    Flower,
-    Dandelion,
-    Pumpkin,
-    Tumbleweed,
-    Mushroom
+// The name an enemy is registered under, see `EnemyKind::TYPE`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Component)]
+pub struct EnemyType(pub &'static str);
+
+impl EnemyType {
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
 }
 
 #[derive(Clone, Component)]
@@ -36,6 +32,8 @@ pub struct EnemySpawnEvent {
     pub difficulty: EnemyDifficulty,
     pub location: EnemyLocation,
     pub rand_range: Range<f32>,
+    // iid of the LDtk spawnpoint, for enemies placed in the level
+    pub spawnpoint: Option<String>,
     pub extra_components: Option<fn(&mut Commands, Entity)>
 }
 
@@ -48,94 +46,58 @@ pub struct EnemyLocation {
 pub fn register_enemy_spawner(app: &mut App) {
     app
         .add_event::<EnemySpawnEvent>()
-        .add_system(spawn_enemies);
+        .init_resource::<EnemyRegistry>()
+        .add_system_set(UpdateStage::Spawn.set().with_system(spawn_enemies));
 }
 
 
 
 
-fn configure_enemy(enemy: &mut EnemyBundle, ev: &EnemySpawnEvent) {
+pub fn configure_enemy(enemy: &mut EnemyBundle, ev: &EnemySpawnEvent) {
     enemy.sprite_sheet.transform.translation.x = ev.location.pos.x;
     enemy.sprite_sheet.transform.translation.y = ev.location.pos.y;
     enemy.path.pathfinder.region = ev.location.patrol_region;
     enemy.coins.total_value = ev.coins;
 }
 
-fn spawn_enemies(
+pub fn spawn_enemies(
     mut commands: Commands,
     mut events: EventReader<EnemySpawnEvent>,
 
-    flower_assets: Res<FlowerEnemyAssets>,
-    pumpkin_assets: Res<PumpkinEnemyAssets>,
-    dandelion_assets: Res<DandelionEnemyAssets>,
-    tumbleweed_assets: Res<TumbleweedEnemyAssets>
+    registry: Res<EnemyRegistry>,
+    stats_handle: Res<EnemyStatsHandle>,
+    stat_tables: Res<Assets<EnemyStatTable>>,
+    mut rng: ResMut<GameRng>
 ) {
     for enemy in events.iter() {
-        let id = match enemy.ty {
-            EnemyType::Flower => {
-                let mut bundle = FlowerEnemyBundle::from_assets(&flower_assets);
-                configure_enemy(&mut bundle.enemy, &enemy);
-
-                let stats = match enemy.difficulty {
-                    EnemyDifficulty::Mellow => FLOWER_MELLOW,
-                    EnemyDifficulty::Easy => FLOWER_EASY,
-                    EnemyDifficulty::Medium => FLOWER_MEDIUM,
-                    EnemyDifficulty::Hard => FLOWER_HARD,
-                }.randomized(enemy.rand_range.clone());
-
-                FlowerEnemyBundle::spawn_with_stats(&mut commands, bundle, stats)
-            },
-
-            EnemyType::Dandelion => {
-                let mut bundle = DandelionEnemyBundle::from_assets(&dandelion_assets);
-                configure_enemy(&mut bundle.enemy, &enemy);
-
-                let stats = match enemy.difficulty {
-                    EnemyDifficulty::Mellow => DANDELION_MELLOW,
-                    EnemyDifficulty::Easy => DANDELION_EASY,
-                    EnemyDifficulty::Medium => DANDELION_MEDIUM,
-                    EnemyDifficulty::Hard => DANDELION_HARD
-                }.randomized(enemy.rand_range.clone());
-
-            DandelionEnemyBundle::spawn_with_stats(&mut commands, bundle, stats)
-            },
-
-            EnemyType::Pumpkin => {
-                let mut bundle = PumpkinEnemyBundle::from_assets(&pumpkin_assets);
-                configure_enemy(&mut bundle.enemy, &enemy);
-
-                let stats = match enemy.difficulty {
-                    EnemyDifficulty::Mellow => PUMPKIN_MELLOW,
-                    EnemyDifficulty::Easy => PUMPKIN_EASY,
-                    EnemyDifficulty::Medium => PUMPKIN_MEDIUM,
-                    EnemyDifficulty::Hard => PUMPKIN_HARD
-                }.randomized(enemy.rand_range.clone());
-
-                PumpkinEnemyBundle::spawn_with_stats(&mut commands, bundle, stats)
-            },
-
-
-            EnemyType::Tumbleweed => {
-                let mut bundle = TumbleweedEnemyBundle::from_assets(&tumbleweed_assets);
-                configure_enemy(&mut bundle.enemy, &enemy);
-
-                let stats = match enemy.difficulty {
-                    EnemyDifficulty::Mellow => TUMBLEWEED_MELLOW,
-                    EnemyDifficulty::Easy => TUMBLEWEED_EASY,
-                    EnemyDifficulty::Medium => TUMBLEWEED_MEDIUM,
-                    EnemyDifficulty::Hard => TUMBLEWEED_HARD
-                }.randomized(enemy.rand_range.clone());
-
-                TumbleweedEnemyBundle::spawn_with_stats(&mut commands, bundle, stats)
-            },
-
-            _ => {
-                panic!("Unknown enemy type {:?}", enemy.ty);
+        let desc = match registry.get(enemy.ty.name()) {
+            Ok(desc) => *desc,
+            Err(e) => {
+                error!("{}", e);
+                continue;
             }
         };
 
-        if let Some(func) = enemy.extra_components {
-            func(&mut commands, id);
+        // stats are rolled here, in the order the spawns were asked for, so
+        // the enemy stream stays the same from run to run
+        let stats = match stat_tables
+            .get(&stats_handle.0)
+            .and_then(|table| table.get(enemy.ty, enemy.difficulty)) {
+            Some(stats) => stats,
+            None => {
+                error!("No stats for {:?} enemy on {:?} difficulty", enemy.ty, enemy.difficulty);
+                continue;
+            }
+        };
+
+        if let Err(e) = desc.check_stats(&stats) {
+            error!("Skipping {} enemy on {:?} difficulty: {}", enemy.ty.name(), enemy.difficulty, e);
+            continue;
         }
+
+        let stats = stats.randomized(rng.stream(RngStream::Enemies), enemy.rand_range.clone());
+
+        let ev = enemy.clone();
+        commands.add(move |world: &mut World| desc.spawn(world, &ev, stats));
     }
-}
\ No newline at end of file
+}
@@ -1,8 +1,14 @@
+use std::collections::HashMap;
 use std::ops::Range;
 use bevy::prelude::*;
+use bevy::reflect::TypeUuid;
+use bevy_common_assets::toml::TomlAssetPlugin;
 use rand::prelude::*;
+use serde::Deserialize;
+use crate::enemies::registry::EnemyRegistry;
+use crate::enemies::spawner::{EnemyDifficulty, EnemyType};
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Deserialize)]
 pub struct EnemyStats {
     pub jump_speed: f32,
     pub patrol_speed: f32,
@@ -14,9 +20,7 @@ pub struct EnemyStats {
 }
 
 impl EnemyStats {
-    pub fn randomized(mut self, range: Range<f32>) -> Self {
-        let mut rng = thread_rng();
-
+    pub fn randomized<R: Rng + ?Sized>(mut self, rng: &mut R, range: Range<f32>) -> Self {
         self.speed *= rng.gen_range(range.clone());
         self.patrol_speed *= rng.gen_range(range.clone());
         self.jump_speed *= rng.gen_range(range.clone());
@@ -28,6 +32,11 @@ impl EnemyStats {
                 extra.atk_cd *= rng.gen_range(range.clone());
                 extra.proj_speed *= rng.gen_range(range.clone());
             },
+            CustomEnemyStats::Burrow(extra) => {
+                extra.burrow_time *= rng.gen_range(range.clone());
+                extra.burrow_cd *= rng.gen_range(range.clone());
+                extra.burrow_speed *= rng.gen_range(range.clone());
+            },
             _ => {}
         }
 
@@ -36,17 +45,155 @@ impl EnemyStats {
     }
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Deserialize)]
 pub enum CustomEnemyStats {
     Fly,
     Ranged(RangedStats),
-    Melee
+    Melee,
+    Burrow(BurrowStats)
+}
+
+impl CustomEnemyStats {
+    // As written in `enemies.stats.toml`
+    pub fn name(&self) -> &'static str {
+        match self {
+            CustomEnemyStats::Fly => "Fly",
+            CustomEnemyStats::Ranged(_) => "Ranged",
+            CustomEnemyStats::Melee => "Melee",
+            CustomEnemyStats::Burrow(_) => "Burrow"
+        }
+    }
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, Deserialize)]
 pub struct RangedStats {
     pub proj_speed: f32,
     pub atk_pause: f32,
     pub atk_cd: f32,
     pub max_shoot_dist: f32,
-}
\ No newline at end of file
+}
+
+#[derive(Component, Debug, Clone, Copy, Deserialize)]
+pub struct BurrowStats {
+    pub burrow_time: f32,
+    pub surface_time: f32,
+    pub burrow_cd: f32,
+    pub burrow_dist: f32,
+    pub burrow_speed: f32,
+}
+
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct DifficultyStats {
+    pub mellow: EnemyStats,
+    pub easy: EnemyStats,
+    pub medium: EnemyStats,
+    pub hard: EnemyStats
+}
+
+impl DifficultyStats {
+    pub fn get(&self, difficulty: EnemyDifficulty) -> EnemyStats {
+        match difficulty {
+            EnemyDifficulty::Mellow => self.mellow,
+            EnemyDifficulty::Easy => self.easy,
+            EnemyDifficulty::Medium => self.medium,
+            EnemyDifficulty::Hard => self.hard
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EnemyDifficulty, EnemyStats)> + '_ {
+        [EnemyDifficulty::Mellow, EnemyDifficulty::Easy, EnemyDifficulty::Medium, EnemyDifficulty::Hard]
+            .into_iter()
+            .map(|difficulty| (difficulty, self.get(difficulty)))
+    }
+}
+
+// Loaded from `enemies.stats.toml`, keyed by the enemy type's name
+#[derive(Debug, Deserialize, TypeUuid)]
+#[uuid = "5d1c5a7e-3f0e-4b9a-9e57-2f1f6c0b8d34"]
+#[serde(transparent)]
+pub struct EnemyStatTable {
+    pub enemies: HashMap<String, DifficultyStats>
+}
+
+impl EnemyStatTable {
+    pub fn get(&self, ty: EnemyType, difficulty: EnemyDifficulty) -> Option<EnemyStats> {
+        self.enemies.get(ty.name()).map(|stats| stats.get(difficulty))
+    }
+
+    // Everything wrong with the table, checked against the registered enemy
+    // types. Enemies with broken stats are skipped when they spawn.
+    pub fn validate(&self, registry: &EnemyRegistry) -> Vec<String> {
+        let mut errors = vec![];
+
+        let mut names = registry.names();
+        names.sort();
+
+        for name in names {
+            if !self.enemies.contains_key(name) {
+                errors.push(format!("No stats for {}", name));
+            }
+        }
+
+        for (name, stats) in self.enemies.iter() {
+            let desc = match registry.get(name) {
+                Ok(desc) => desc,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                }
+            };
+
+            for (difficulty, stats) in stats.iter() {
+                if let Err(e) = desc.check_stats(&stats) {
+                    errors.push(format!("{} on {:?} difficulty: {}", name, difficulty, e));
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct EnemyStatsHandle(pub Handle<EnemyStatTable>);
+
+pub fn register_enemy_stats(app: &mut App) {
+    app
+        .add_plugin(TomlAssetPlugin::<EnemyStatTable>::new(&["stats.toml"]))
+        .add_startup_system(load_enemy_stats)
+        .add_system(log_enemy_stats_reload)
+        .add_system(validate_enemy_stats);
+}
+
+fn load_enemy_stats(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(EnemyStatsHandle(asset_server.load("enemies.stats.toml")));
+}
+
+fn log_enemy_stats_reload(mut events: EventReader<AssetEvent<EnemyStatTable>>) {
+    for ev in events.iter() {
+        if let AssetEvent::Modified { .. } = ev {
+            info!("Reloaded enemy stats");
+        }
+    }
+}
+
+fn validate_enemy_stats(
+    mut events: EventReader<AssetEvent<EnemyStatTable>>,
+    tables: Res<Assets<EnemyStatTable>>,
+    registry: Res<EnemyRegistry>
+) {
+    for ev in events.iter() {
+        let handle = match ev {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue
+        };
+
+        if let Some(table) = tables.get(handle) {
+            for e in table.validate(&registry) {
+                error!("enemies.stats.toml: {}", e);
+            }
+        }
+    }
+}
@@ -1,4 +1,3 @@
-pub mod stats;
 pub mod state_machine;
 
 use bevy::prelude::*;
@@ -12,6 +11,8 @@ use crate::{
 use crate::coin::drops::CoinHolder;
 use crate::combat::{AttackStrength, ColliderAttackBundle, Immunity};
 
+use crate::enemies::registry::EnemyKind;
+use crate::enemies::spawner::{configure_enemy, EnemySpawnEvent, EnemyType};
 use crate::enemies::stats::EnemyStats;
 use crate::anim::Animator;
 
@@ -19,15 +20,25 @@ use crate::assets::TumbleweedEnemyAssets;
 use crate::util::Facing;
 
 
-
-pub fn register_tumbleweed_enemy(app: &mut App) {
-    let _ = app;
-}
-
-
 #[derive(Component, Debug, Default)]
 pub struct TumbleweedEnemy;
 
+impl EnemyKind for TumbleweedEnemy {
+    const TYPE: EnemyType = EnemyType("Tumbleweed");
+    type Assets = TumbleweedEnemyAssets;
+
+    fn spawn(
+        commands: &mut Commands,
+        assets: &TumbleweedEnemyAssets,
+        ev: &EnemySpawnEvent,
+        stats: EnemyStats
+    ) -> Entity {
+        let mut bundle = TumbleweedEnemyBundle::from_assets(assets);
+        configure_enemy(&mut bundle.enemy, ev);
+        TumbleweedEnemyBundle::spawn_with_stats(commands, bundle, stats)
+    }
+}
+
 #[derive(Bundle)]
 pub struct TumbleweedEnemyBundle {
     #[bundle]
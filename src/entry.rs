@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 use bevy::window::WindowResizeConstraints;
+use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 use seldom_state::prelude::*;
 use kayak_ui::prelude::*;
@@ -14,9 +15,10 @@ use crate::{
     assets::AssetLoaderPlugin,
     player::PlayerPlugin,
     input::InputPlugin,
+    config::ConfigPlugin,
     level::LevelPlugin,
     enemies::EnemyPlugin,
-    camera::CameraPlugin,
+    camera::{CameraPlugin, VIRTUAL_RESOLUTION},
     pathfind::PathfindingPlugin,
     combat::AttackPlugin,
     fx::EffectsPlugin,
@@ -25,7 +27,17 @@ use crate::{
     ui::GameUiPlugin,
     shop::ShopPlugin,
     interact::InteractPlugin,
-    anim::AnimationPlugin
+    anim::AnimationPlugin,
+    save::SavePlugin,
+    audio::GameAudioPlugin,
+    fx::shake::ScreenShakeEvent,
+    fx::smoke::SmokeEvent,
+    fx::indicator::Indicator,
+    ui::menu::GotoMenuEvent,
+    rng::{self, GameRng},
+    replay::{self, Replay, ReplayPlayback, ReplayPlugin, ReplayRecorder, ReplayWindowPlugin},
+    run_stats::RunStatsPlugin,
+    leaderboard::LeaderboardPlugin
 };
 
 pub struct ShroomBoomPlugin;
@@ -36,12 +48,22 @@ impl Plugin for ShroomBoomPlugin {
             .add_plugins(
                 DefaultPlugins
                     .set(ImagePlugin::default_nearest())
+                    .set(AssetPlugin {
+                        // lets tuning files like enemies.stats.toml hot reload
+                        watch_for_changes: true,
+                        ..default()
+                    })
                     .set(WindowPlugin {
                         window: WindowDescriptor {
                             width: 1280.0,
                             height: 720.0,
 
-                            resizable: false,
+                            resizable: true,
+                            resize_constraints: WindowResizeConstraints {
+                                min_width: VIRTUAL_RESOLUTION.x as f32,
+                                min_height: VIRTUAL_RESOLUTION.y as f32,
+                                ..default()
+                            },
                             title: "Shroom Boom!".to_string(),
                             ..default()
                         },
@@ -49,29 +71,69 @@ impl Plugin for ShroomBoomPlugin {
                     })
             )
 
-            .add_plugin(KayakContextPlugin)
-            .add_plugin(KayakWidgets)
+            .add_plugin(LdtkPlugin)
+            .add_plugin(GameplayPlugin)
+            .add_plugin(PresentationPlugin)
+
+            .add_startup_system(setup_rapier);
+
+        if let Some(seed) = rng::seed_from_args() {
+            info!("Using seed {} from the command line", seed);
+            app.insert_resource(GameRng::new(seed));
+        }
+
+        let (record, playback) = replay::replay_args();
+
+        // the last run is kept around for the main menu demo
+        app.insert_resource(ReplayRecorder {
+            enabled: true,
+            path: Some(record.unwrap_or_else(replay::last_run_path)),
+            ..default()
+        });
+
+        if let Some(path) = playback {
+            match Replay::load(&path) {
+                Ok(r) => {
+                    app.insert_resource(ReplayPlayback::new(r));
+                }
+
+                Err(e) => error!("Failed to load replay {:?}: {}", path, e)
+            }
+        }
+    }
+}
 
+// Everything needed to simulate a run: physics, levels, the player, enemies,
+// combat, coins, the boss and the shop. Nothing in here needs a window, so
+// it's also what the headless app in `headless.rs` is built from. Levels
+// are loaded by `LdtkPlugin`, which isn't added here since it also sets up
+// the tilemap renderer.
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
             // physics
             .add_plugin(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
-            // .add_plugin(RapierDebugRenderPlugin::default())
-            .add_plugin(EguiPlugin)
-            // .add_plugin(WorldInspectorPlugin::new())
             .add_plugin(EasingsPlugin)
 
-            .add_plugin(bevy_debug_text_overlay::OverlayPlugin::default())
-
-            .insert_resource(ClearColor(Color::rgb(0.015, 0.015, 0.1)))
-
             // state machine
             .add_plugin(StateMachinePlugin)
 
             // subsystems
-            .add_plugin(CameraPlugin)
             .add_plugin(AssetLoaderPlugin)
             .add_plugin(InputPlugin)
+            .init_resource::<GameRng>()
+            .add_plugin(ReplayPlugin)
+
+            // sent by gameplay code, but only read by the presentation plugins
+            .add_event::<ScreenShakeEvent>()
+            .add_event::<SmokeEvent>()
+            .add_event::<Indicator>()
+            .init_resource::<GotoMenuEvent>()
 
-            // gameplay
+            // gameplay, each of these puts its systems in the `UpdateStage`
+            // phases, which run one after another before the physics steps
             .add_plugin(PlayerPlugin)
             .add_plugin(EnemyPlugin)
             .add_plugin(LevelPlugin)
@@ -79,13 +141,41 @@ impl Plugin for ShroomBoomPlugin {
             .add_plugin(AttackPlugin)
             .add_plugin(CoinPlugin)
             .add_plugin(BossPlugin)
-            .add_plugin(GameUiPlugin)
-            .add_plugin(EffectsPlugin)
             .add_plugin(ShopPlugin)
             .add_plugin(InteractPlugin)
             .add_plugin(AnimationPlugin)
+            .add_plugin(RunStatsPlugin);
+    }
+}
 
-            .add_startup_system(setup_rapier);
+// The window, camera, menus, HUD, effects, audio, config file, save files
+// and leaderboard
+pub struct PresentationPlugin;
+
+impl Plugin for PresentationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(KayakContextPlugin)
+            .add_plugin(KayakWidgets)
+
+            // .add_plugin(RapierDebugRenderPlugin::default())
+            .add_plugin(EguiPlugin)
+            // .add_plugin(WorldInspectorPlugin::new())
+
+            .add_plugin(bevy_debug_text_overlay::OverlayPlugin::default())
+
+            .insert_resource(ClearColor(Color::rgb(0.015, 0.015, 0.1)))
+
+            // subsystems
+            .add_plugin(CameraPlugin)
+            .add_plugin(ConfigPlugin)
+            .add_plugin(GameAudioPlugin)
+
+            .add_plugin(GameUiPlugin)
+            .add_plugin(EffectsPlugin)
+            .add_plugin(SavePlugin)
+            .add_plugin(ReplayWindowPlugin)
+            .add_plugin(LeaderboardPlugin);
     }
 }
 
@@ -4,6 +4,7 @@ pub mod spore;
 pub mod indicator;
 pub mod shake;
 pub mod smoke;
+pub mod transition;
 
 pub struct EffectsPlugin;
 
@@ -13,5 +14,6 @@ impl Plugin for EffectsPlugin {
         indicator::register_indicators(app);
         shake::register_screen_shake(app);
         smoke::register_smoke(app);
+        transition::register_transition_masks(app);
     }
 }
\ No newline at end of file
@@ -2,7 +2,9 @@ use bevy::prelude::*;
 use bevy_easings::Lerp;
 use rand::prelude::*;
 use crate::camera::{camera_track_player, GameCamera};
+use crate::config::GameSettings;
 use crate::state::GameState;
+use crate::rng::{GameRng, RngStream};
 
 pub const SHAKE_DECAY_RATE: f32 = 5.0;
 
@@ -51,23 +53,25 @@ pub fn register_screen_shake(app: &mut App) {
 
 fn handle_events(
     mut mgr: ResMut<ScreenShakeManager>,
-    mut ev: EventReader<ScreenShakeEvent>
+    mut ev: EventReader<ScreenShakeEvent>,
+    settings: Res<GameSettings>
 ) {
     for shake in ev.iter() {
-        mgr.intensity += shake.intensity;
+        mgr.intensity += shake.intensity * settings.screen_shake;
     }
 }
 
 fn shake_update(
     time: Res<Time>,
     mut mgr: ResMut<ScreenShakeManager>,
-    mut cam: Query<&mut Transform, With<GameCamera>>
+    mut cam: Query<&mut Transform, With<GameCamera>>,
+    mut rng: ResMut<GameRng>
 ) {
     if cam.is_empty() {
         return;
     }
 
-    let mut rng = thread_rng();
+    let rng = rng.stream(RngStream::Presentation);
     let mut tf = cam.single_mut();
 
     mgr.intensity = mgr.intensity.lerp(&0.0_f32, &(SHAKE_DECAY_RATE * time.delta().as_secs_f32()));
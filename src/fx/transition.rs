@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+// Above everything in the level, below the UI
+const MASK_Z: f32 = 998.0;
+
+const IRIS_TEXTURE_SIZE: u32 = 128;
+
+// The pixelate effect's grid, one block per cell
+const PIXELATE_COLUMNS: usize = 16;
+const PIXELATE_ROWS: usize = 9;
+
+#[derive(Resource, Clone, Default)]
+pub struct TransitionAssets {
+    // black with a round hole in the middle
+    pub iris: Handle<Image>
+}
+
+pub fn register_transition_masks(app: &mut App) {
+    app
+        .init_resource::<TransitionAssets>()
+        .add_startup_system(create_iris_texture);
+}
+
+fn create_iris_texture(mut assets: ResMut<TransitionAssets>, mut images: ResMut<Assets<Image>>) {
+    let size = IRIS_TEXTURE_SIZE;
+    let radius = size as f32 / 2.0;
+
+    let data = (0..size * size)
+        .flat_map(|i| {
+            let pos = Vec2::new((i % size) as f32 + 0.5, (i / size) as f32 + 0.5);
+            let alpha = if pos.distance(Vec2::splat(radius)) < radius { 0 } else { 255 };
+            [0, 0, 0, alpha]
+        })
+        .collect();
+
+    assets.iris = images.add(Image::new(
+        Extent3d { width: size, height: size, depth_or_array_layers: 1 },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb
+    ));
+}
+
+// What the screen looks like this frame, in world units
+pub struct TransitionView {
+    pub center: Vec2,
+    pub size: Vec2,
+    // where the player is, what the iris closes on
+    pub focus: Vec2
+}
+
+pub type MaskQuery<'w, 's> = Query<'w, 's, (&'static mut Transform, &'static mut Sprite, &'static mut Visibility)>;
+
+// Hides the level while it's swapped for the next one. `coverage` goes from
+// 0, nothing hidden, up to 1 while the old level is cleaned up and the new
+// one spawned, then back down to 0.
+pub trait Transition: Send + Sync {
+    fn spawn(&mut self, commands: &mut Commands, assets: &TransitionAssets);
+
+    fn update(&self, coverage: f32, view: &TransitionView, masks: &mut MaskQuery);
+
+    // Everything `spawn` spawned, despawned once the transition is over
+    fn masks(&self) -> &[Entity];
+}
+
+fn spawn_mask(commands: &mut Commands, color: Color, texture: Handle<Image>) -> Entity {
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color,
+            custom_size: Some(Vec2::ZERO),
+            ..default()
+        },
+        texture,
+        transform: Transform::from_xyz(0.0, 0.0, MASK_Z),
+        ..default()
+    }).id()
+}
+
+// Stretches a mask over the rectangle between two corners
+fn place_mask(masks: &mut MaskQuery, mask: Entity, min: Vec2, max: Vec2) {
+    if let Ok((mut tf, mut spr, _)) = masks.get_mut(mask) {
+        let center = (min + max) / 2.0;
+        tf.translation.x = center.x;
+        tf.translation.y = center.y;
+        spr.custom_size = Some((max - min).max(Vec2::ZERO));
+    }
+}
+
+#[derive(Default)]
+pub struct FadeTransition {
+    pub mask: Vec<Entity>,
+    pub color: Color
+}
+
+impl Transition for FadeTransition {
+    fn spawn(&mut self, commands: &mut Commands, _: &TransitionAssets) {
+        self.mask = vec![spawn_mask(commands, self.color, default())];
+    }
+
+    fn update(&self, coverage: f32, view: &TransitionView, masks: &mut MaskQuery) {
+        place_mask(masks, self.mask[0], view.center - view.size / 2.0, view.center + view.size / 2.0);
+
+        if let Ok((_, mut spr, _)) = masks.get_mut(self.mask[0]) {
+            spr.color.set_a(coverage);
+        }
+    }
+
+    fn masks(&self) -> &[Entity] {
+        &self.mask
+    }
+}
+
+// A curtain drawn across the screen from the left, and back
+#[derive(Default)]
+pub struct WipeTransition {
+    pub mask: Vec<Entity>,
+    pub color: Color
+}
+
+impl Transition for WipeTransition {
+    fn spawn(&mut self, commands: &mut Commands, _: &TransitionAssets) {
+        self.mask = vec![spawn_mask(commands, self.color, default())];
+    }
+
+    fn update(&self, coverage: f32, view: &TransitionView, masks: &mut MaskQuery) {
+        let min = view.center - view.size / 2.0;
+        let max = Vec2::new(min.x + view.size.x * coverage, min.y + view.size.y);
+
+        place_mask(masks, self.mask[0], min, max);
+    }
+
+    fn masks(&self) -> &[Entity] {
+        &self.mask
+    }
+}
+
+// A circle closing in on the player. The hole is a texture, the rest of the
+// screen around it is covered by four plain masks.
+#[derive(Default)]
+pub struct IrisTransition {
+    pub masks: Vec<Entity>
+}
+
+impl Transition for IrisTransition {
+    fn spawn(&mut self, commands: &mut Commands, assets: &TransitionAssets) {
+        self.masks = vec![spawn_mask(commands, Color::WHITE, assets.iris.clone())];
+
+        for _ in 0..4 {
+            self.masks.push(spawn_mask(commands, Color::BLACK, default()));
+        }
+    }
+
+    fn update(&self, coverage: f32, view: &TransitionView, masks: &mut MaskQuery) {
+        let min = view.center - view.size / 2.0;
+        let max = view.center + view.size / 2.0;
+
+        // wide enough to uncover the farthest corner
+        let furthest = [min, max, Vec2::new(min.x, max.y), Vec2::new(max.x, min.y)]
+            .iter()
+            .map(|corner| corner.distance(view.focus))
+            .fold(0.0, f32::max);
+
+        let radius = furthest * (1.0 - coverage);
+        let hole_min = view.focus - radius;
+        let hole_max = view.focus + radius;
+
+        place_mask(masks, self.masks[0], hole_min, hole_max);
+        place_mask(masks, self.masks[1], min, Vec2::new(hole_min.x, max.y));
+        place_mask(masks, self.masks[2], Vec2::new(hole_max.x, min.y), max);
+        place_mask(masks, self.masks[3], Vec2::new(hole_min.x, hole_max.y), Vec2::new(hole_max.x, max.y));
+        place_mask(masks, self.masks[4], Vec2::new(hole_min.x, min.y), Vec2::new(hole_max.x, hole_min.y));
+    }
+
+    fn masks(&self) -> &[Entity] {
+        &self.masks
+    }
+}
+
+// The screen breaks up into big black blocks, each showing up at its own
+// point of the transition
+#[derive(Default)]
+pub struct PixelateTransition {
+    pub blocks: Vec<Entity>,
+    pub color: Color
+}
+
+impl PixelateTransition {
+    // Scrambled so the blocks don't fill in row by row
+    fn threshold(i: usize) -> f32 {
+        let count = PIXELATE_COLUMNS * PIXELATE_ROWS;
+        ((i * 89) % count) as f32 / count as f32
+    }
+}
+
+impl Transition for PixelateTransition {
+    fn spawn(&mut self, commands: &mut Commands, _: &TransitionAssets) {
+        self.blocks = (0..PIXELATE_COLUMNS * PIXELATE_ROWS)
+            .map(|_| spawn_mask(commands, self.color, default()))
+            .collect();
+    }
+
+    fn update(&self, coverage: f32, view: &TransitionView, masks: &mut MaskQuery) {
+        let min = view.center - view.size / 2.0;
+        let block = view.size / Vec2::new(PIXELATE_COLUMNS as f32, PIXELATE_ROWS as f32);
+
+        for (i, mask) in self.blocks.iter().enumerate() {
+            let cell = Vec2::new((i % PIXELATE_COLUMNS) as f32, (i / PIXELATE_COLUMNS) as f32);
+            let block_min = min + cell * block;
+
+            place_mask(masks, *mask, block_min, block_min + block);
+
+            if let Ok((_, _, mut visibility)) = masks.get_mut(*mask) {
+                visibility.is_visible = coverage > Self::threshold(i);
+            }
+        }
+    }
+
+    fn masks(&self) -> &[Entity] {
+        &self.blocks
+    }
+}
+
+// Nothing to look at, the level is swapped as soon as it's loaded
+pub struct NoTransition;
+
+impl Transition for NoTransition {
+    fn spawn(&mut self, _: &mut Commands, _: &TransitionAssets) {}
+
+    fn update(&self, _: f32, _: &TransitionView, _: &mut MaskQuery) {}
+
+    fn masks(&self) -> &[Entity] {
+        &[]
+    }
+}
@@ -0,0 +1,354 @@
+use std::time::{Duration, Instant};
+use bevy::asset::LoadState;
+use bevy::ecs::schedule::SingleThreadedExecutor;
+use bevy::input::InputPlugin as BevyInputPlugin;
+use bevy::prelude::*;
+use bevy::time::{FixedTimesteps, TimePlugin, TimeSystem};
+use bevy_ecs_ldtk::app::{LdtkEntityMap, LdtkIntCellMap};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_ecs_ldtk::{systems, LdtkLevelLoader, LdtkLoader, LdtkStage, LdtkSystemLabel, LevelEvent};
+use bevy_rapier2d::prelude::*;
+use crate::common::PHYSICS_STEP_DELTA;
+use crate::entry::GameplayPlugin;
+use crate::input::{InputAction, ScriptedInput};
+use crate::level::checkpoint::{CheckpointState, RespawnSettings};
+use crate::level::consts::DEFAULT_ENTRY_POINT;
+use crate::level::memory::LevelMemory;
+use crate::level::transition::LevelTransition;
+use crate::player::Player;
+use crate::player::logic::PlayerScore;
+use crate::replay::{Replay, ReplayPlayback};
+use crate::rng::GameRng;
+use crate::run_stats::RunStats;
+use crate::state::GameState;
+
+// Runs the gameplay plugins without a window, renderer, UI or audio. Time
+// only moves when the app is stepped, by one physics step per frame unless
+// `set_frame_time` says otherwise, and the RNG is seeded, so a scripted run
+// plays out the same way every time:
+//
+//     let mut sim = HeadlessApp::with_seed(1234);
+//     assert!(sim.start_level("Level_0"));
+//     sim.hold(InputAction::RunRight);
+//     assert!(sim.run_until(3600, |sim| sim.current_level() == "Level_1"));
+//
+// See tests/ for more.
+
+// Frames to wait for levels.ldtk to load. The loader runs on another thread,
+// so this is generous, each of these frames is very cheap.
+pub const MAX_LOAD_FRAMES: u32 = 100_000;
+
+// Frames to wait for the level transition to finish in `start_level`
+pub const MAX_TRANSITION_FRAMES: u32 = 600;
+
+#[derive(Resource)]
+struct FixedClock {
+    now: Instant,
+    dt: Duration
+}
+
+pub struct HeadlessApp {
+    pub app: App,
+    levels: Handle<LdtkAsset>
+}
+
+impl HeadlessApp {
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut app = App::new();
+
+        app
+            .add_plugins(MinimalPlugins.build().disable::<TimePlugin>())
+            .add_plugin(TransformPlugin)
+            .add_plugin(HierarchyPlugin)
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(BevyInputPlugin)
+
+            // normally registered by the rendering plugins, the asset loaders
+            // still hand out handles to these
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Font>()
+            // read by the level spawning, for the level background
+            .init_resource::<ClearColor>()
+
+            .init_resource::<Time>()
+            .init_resource::<FixedTimesteps>()
+            .insert_resource(FixedClock {
+                now: Instant::now(),
+                dt: Duration::from_secs_f32(PHYSICS_STEP_DELTA)
+            })
+            // stands in for Bevy's own time update, so whatever runs after
+            // that runs after this
+            .add_system_to_stage(CoreStage::First, advance_clock.label(TimeSystem))
+
+            .add_plugin(HeadlessLdtkPlugin)
+            .add_plugin(GameplayPlugin)
+            .insert_resource(GameRng::new(seed))
+            // same as the game, so replays recorded there step the same way
+            .insert_resource(RapierConfiguration {
+                timestep_mode: TimestepMode::Variable {
+                    max_dt: PHYSICS_STEP_DELTA,
+                    time_scale: 1.0,
+                    substeps: 1
+                },
+                ..default()
+            })
+
+            .insert_resource(ScriptedInput {
+                enabled: true,
+                ..default()
+            });
+
+        // the parallel executor runs systems that don't depend on each other
+        // in whatever order the threads get to them, which is enough to make
+        // a replay end up a frame off
+        let stages: Vec<_> = app.schedule.iter_stages().map(|(label, _)| label).collect();
+        for label in stages {
+            if let Some(stage) = app.schedule.get_stage_mut::<SystemStage>(label) {
+                stage.set_executor(Box::<SingleThreadedExecutor>::default());
+            }
+        }
+
+        // keep the levels alive so the LdtkWorldBundle spawned on the first
+        // transition reuses this load instead of starting another one
+        let levels = app.world.resource::<AssetServer>().load("levels/levels.ldtk");
+
+        Self {
+            app,
+            levels
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    // How much time passes on each of the following steps
+    pub fn set_frame_time(&mut self, dt: Duration) {
+        self.app.world.resource_mut::<FixedClock>().dt = dt;
+    }
+
+    pub fn step_frames(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    // Steps until `done` returns true, or gives up after `max_frames`
+    pub fn run_until(&mut self, max_frames: u32, mut done: impl FnMut(&mut Self) -> bool) -> bool {
+        for _ in 0..max_frames {
+            self.step();
+
+            if done(self) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // False if levels.ldtk failed to load, or didn't within `MAX_LOAD_FRAMES`.
+    // Also waits for the game to leave `AssetLoading`, which it does once the
+    // enemy stats are in.
+    fn wait_for_levels(&mut self) -> bool {
+        for _ in 0..MAX_LOAD_FRAMES {
+            self.step();
+
+            match self.app.world.resource::<AssetServer>().get_load_state(&self.levels) {
+                LoadState::Loaded if self.state() != GameState::AssetLoading => return true,
+                LoadState::Failed => {
+                    error!("Failed to load levels/levels.ldtk");
+                    return false;
+                }
+                _ => {}
+            }
+
+            // give the loader thread a chance to run
+            std::thread::yield_now();
+        }
+
+        error!("levels/levels.ldtk didn't load within {} frames", MAX_LOAD_FRAMES);
+        false
+    }
+
+    fn begin_run(&mut self, level: &str) {
+        self.app.world.insert_resource(RunStats::default());
+
+        let checkpoints = CheckpointState::new_run(self.app.world.resource::<RespawnSettings>());
+        self.app.world.insert_resource(checkpoints);
+        self.app.world.insert_resource(LevelMemory::default());
+
+        let mut trans = self.app.world.resource_mut::<LevelTransition>();
+        trans.next = level.to_string();
+        trans.entry_point = DEFAULT_ENTRY_POINT;
+
+        self.app.world.insert_resource(LevelSelection::Identifier(String::from("Init")));
+        self.app.world
+            .resource_mut::<State<GameState>>()
+            .overwrite_set(GameState::LevelTransition)
+            .unwrap();
+    }
+
+    // Starts a fresh run at `level`, the same way New Game from the main
+    // menu does, and steps until the level transition is over. False if the
+    // level never finished loading.
+    pub fn start_level(&mut self, level: &str) -> bool {
+        if !self.wait_for_levels() {
+            return false;
+        }
+
+        self.app.world.resource_mut::<GameRng>().restart();
+        self.begin_run(level);
+
+        self.run_until(MAX_TRANSITION_FRAMES, |sim| sim.state() == GameState::Gameplay)
+    }
+
+    // Plays `replay` from the start, stepping with the frame times it was
+    // recorded with. Returns false if the levels didn't load, or the game
+    // stopped simulating (won, lost or quit to the menu) before all of its
+    // frames were used up.
+    pub fn play_replay(&mut self, replay: &Replay) -> bool {
+        if !self.wait_for_levels() {
+            return false;
+        }
+
+        self.app.world.resource_mut::<GameRng>().reseed(replay.seed);
+        self.app.world.insert_resource(ReplayPlayback {
+            started: true,
+            ..ReplayPlayback::new(replay.clone())
+        });
+        self.begin_run(&replay.level);
+
+        // menus in between don't use up frames, so leave some slack for them
+        let max_steps = replay.frames.len() + MAX_TRANSITION_FRAMES as usize;
+
+        for _ in 0..max_steps {
+            let next = self.app.world.resource::<ReplayPlayback>().next_frame();
+            let next = match next {
+                Some(f) => f,
+                None => break
+            };
+
+            self.set_frame_time(next.dt());
+            self.step();
+        }
+
+        self.set_frame_time(Duration::from_secs_f32(PHYSICS_STEP_DELTA));
+        self.app.world.resource::<ReplayPlayback>().finished()
+    }
+
+    pub fn hold(&mut self, action: InputAction) {
+        self.app.world.resource_mut::<ScriptedInput>().pressed.insert(action);
+    }
+
+    pub fn release(&mut self, action: InputAction) {
+        self.app.world.resource_mut::<ScriptedInput>().pressed.remove(&action);
+    }
+
+    pub fn release_all(&mut self) {
+        self.app.world.resource_mut::<ScriptedInput>().pressed.clear();
+    }
+
+    // Holds the action for a single frame, e.g. a jump or a slash
+    pub fn tap(&mut self, action: InputAction) {
+        self.hold(action);
+        self.step();
+        self.release(action);
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app.world.resource::<State<GameState>>().current().clone()
+    }
+
+    pub fn current_level(&self) -> String {
+        match self.app.world.resource::<LevelSelection>() {
+            LevelSelection::Identifier(id) => id.clone(),
+            sel => panic!("Unexpected level selection {:?}", sel)
+        }
+    }
+
+    pub fn score(&self) -> u32 {
+        self.app.world.resource::<PlayerScore>().score
+    }
+
+    pub fn stats(&self) -> &RunStats {
+        self.app.world.resource::<RunStats>()
+    }
+
+    pub fn player(&mut self) -> Option<Entity> {
+        let mut q = self.app.world.query_filtered::<Entity, With<Player>>();
+        q.iter(&self.app.world).next()
+    }
+}
+
+impl Default for HeadlessApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// `LdtkPlugin` without `TilemapPlugin`, which needs a render device to build.
+// The tilemaps still get spawned, nothing draws them.
+struct HeadlessLdtkPlugin;
+
+impl Plugin for HeadlessLdtkPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_stage_after(CoreStage::Update, LdtkStage::ProcessApi, SystemStage::parallel())
+            .init_non_send_resource::<LdtkEntityMap>()
+            .init_non_send_resource::<LdtkIntCellMap>()
+            .init_resource::<LdtkSettings>()
+            .add_asset::<LdtkAsset>()
+            .init_asset_loader::<LdtkLoader>()
+            .add_asset::<LdtkLevel>()
+            .init_asset_loader::<LdtkLevelLoader>()
+            .add_event::<LevelEvent>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                systems::process_ldtk_assets.label(LdtkSystemLabel::ProcessAssets)
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                systems::process_ldtk_levels.label(LdtkSystemLabel::LevelSpawning)
+            )
+            .add_system_to_stage(
+                LdtkStage::ProcessApi,
+                systems::worldly_adoption.label(LdtkSystemLabel::Other)
+            )
+            .add_system_to_stage(
+                LdtkStage::ProcessApi,
+                systems::apply_level_selection.label(LdtkSystemLabel::LevelSelection)
+            )
+            .add_system_to_stage(
+                LdtkStage::ProcessApi,
+                systems::apply_level_set
+                    .label(LdtkSystemLabel::LevelSet)
+                    .after(LdtkSystemLabel::LevelSelection)
+            )
+            .add_system_to_stage(LdtkStage::ProcessApi, systems::clean_respawn_entities.at_end())
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                systems::detect_level_spawned_events
+                    .pipe(systems::fire_level_transformed_events)
+                    .label(LdtkSystemLabel::Other)
+            );
+    }
+}
+
+fn advance_clock(mut clock: ResMut<FixedClock>, mut time: ResMut<Time>) {
+    let dt = clock.dt;
+    clock.now += dt;
+    time.update_with_instant(clock.now);
+}
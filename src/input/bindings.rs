@@ -0,0 +1,485 @@
+use std::fmt;
+use bevy::prelude::*;
+use leafwing_input_manager::axislike::SingleAxis;
+use leafwing_input_manager::user_input::{InputKind, Modifier, UserInput};
+
+// (key, name used in config.toml, name shown to the player)
+const KEYS: &[(KeyCode, &str, &str)] = &[
+    (KeyCode::Key1, "Key1", "1"),
+    (KeyCode::Key2, "Key2", "2"),
+    (KeyCode::Key3, "Key3", "3"),
+    (KeyCode::Key4, "Key4", "4"),
+    (KeyCode::Key5, "Key5", "5"),
+    (KeyCode::Key6, "Key6", "6"),
+    (KeyCode::Key7, "Key7", "7"),
+    (KeyCode::Key8, "Key8", "8"),
+    (KeyCode::Key9, "Key9", "9"),
+    (KeyCode::Key0, "Key0", "0"),
+    (KeyCode::A, "A", "A"),
+    (KeyCode::B, "B", "B"),
+    (KeyCode::C, "C", "C"),
+    (KeyCode::D, "D", "D"),
+    (KeyCode::E, "E", "E"),
+    (KeyCode::F, "F", "F"),
+    (KeyCode::G, "G", "G"),
+    (KeyCode::H, "H", "H"),
+    (KeyCode::I, "I", "I"),
+    (KeyCode::J, "J", "J"),
+    (KeyCode::K, "K", "K"),
+    (KeyCode::L, "L", "L"),
+    (KeyCode::M, "M", "M"),
+    (KeyCode::N, "N", "N"),
+    (KeyCode::O, "O", "O"),
+    (KeyCode::P, "P", "P"),
+    (KeyCode::Q, "Q", "Q"),
+    (KeyCode::R, "R", "R"),
+    (KeyCode::S, "S", "S"),
+    (KeyCode::T, "T", "T"),
+    (KeyCode::U, "U", "U"),
+    (KeyCode::V, "V", "V"),
+    (KeyCode::W, "W", "W"),
+    (KeyCode::X, "X", "X"),
+    (KeyCode::Y, "Y", "Y"),
+    (KeyCode::Z, "Z", "Z"),
+    (KeyCode::Escape, "Escape", "Esc"),
+    (KeyCode::F1, "F1", "F1"),
+    (KeyCode::F2, "F2", "F2"),
+    (KeyCode::F3, "F3", "F3"),
+    (KeyCode::F4, "F4", "F4"),
+    (KeyCode::F5, "F5", "F5"),
+    (KeyCode::F6, "F6", "F6"),
+    (KeyCode::F7, "F7", "F7"),
+    (KeyCode::F8, "F8", "F8"),
+    (KeyCode::F9, "F9", "F9"),
+    (KeyCode::F10, "F10", "F10"),
+    (KeyCode::F11, "F11", "F11"),
+    (KeyCode::F12, "F12", "F12"),
+    (KeyCode::F13, "F13", "F13"),
+    (KeyCode::F14, "F14", "F14"),
+    (KeyCode::F15, "F15", "F15"),
+    (KeyCode::F16, "F16", "F16"),
+    (KeyCode::F17, "F17", "F17"),
+    (KeyCode::F18, "F18", "F18"),
+    (KeyCode::F19, "F19", "F19"),
+    (KeyCode::F20, "F20", "F20"),
+    (KeyCode::F21, "F21", "F21"),
+    (KeyCode::F22, "F22", "F22"),
+    (KeyCode::F23, "F23", "F23"),
+    (KeyCode::F24, "F24", "F24"),
+    (KeyCode::Snapshot, "Snapshot", "Print Screen"),
+    (KeyCode::Scroll, "Scroll", "Scroll Lock"),
+    (KeyCode::Pause, "Pause", "Pause"),
+    (KeyCode::Insert, "Insert", "Insert"),
+    (KeyCode::Home, "Home", "Home"),
+    (KeyCode::Delete, "Delete", "Delete"),
+    (KeyCode::End, "End", "End"),
+    (KeyCode::PageDown, "PageDown", "Page Down"),
+    (KeyCode::PageUp, "PageUp", "Page Up"),
+    (KeyCode::Left, "Left", "Left Arrow"),
+    (KeyCode::Up, "Up", "Up Arrow"),
+    (KeyCode::Right, "Right", "Right Arrow"),
+    (KeyCode::Down, "Down", "Down Arrow"),
+    (KeyCode::Back, "Back", "Backspace"),
+    (KeyCode::Return, "Return", "Enter"),
+    (KeyCode::Space, "Space", "Space"),
+    (KeyCode::Compose, "Compose", "Compose"),
+    (KeyCode::Caret, "Caret", "Caret"),
+    (KeyCode::Numlock, "Numlock", "Num Lock"),
+    (KeyCode::Numpad0, "Numpad0", "Numpad 0"),
+    (KeyCode::Numpad1, "Numpad1", "Numpad 1"),
+    (KeyCode::Numpad2, "Numpad2", "Numpad 2"),
+    (KeyCode::Numpad3, "Numpad3", "Numpad 3"),
+    (KeyCode::Numpad4, "Numpad4", "Numpad 4"),
+    (KeyCode::Numpad5, "Numpad5", "Numpad 5"),
+    (KeyCode::Numpad6, "Numpad6", "Numpad 6"),
+    (KeyCode::Numpad7, "Numpad7", "Numpad 7"),
+    (KeyCode::Numpad8, "Numpad8", "Numpad 8"),
+    (KeyCode::Numpad9, "Numpad9", "Numpad 9"),
+    (KeyCode::AbntC1, "AbntC1", "AbntC1"),
+    (KeyCode::AbntC2, "AbntC2", "AbntC2"),
+    (KeyCode::NumpadAdd, "NumpadAdd", "Numpad +"),
+    (KeyCode::Apostrophe, "Apostrophe", "'"),
+    (KeyCode::Apps, "Apps", "Apps"),
+    (KeyCode::Asterisk, "Asterisk", "*"),
+    (KeyCode::Plus, "Plus", "+"),
+    (KeyCode::At, "At", "@"),
+    (KeyCode::Ax, "Ax", "Ax"),
+    (KeyCode::Backslash, "Backslash", "\\"),
+    (KeyCode::Calculator, "Calculator", "Calculator"),
+    (KeyCode::Capital, "Capital", "Caps Lock"),
+    (KeyCode::Colon, "Colon", ":"),
+    (KeyCode::Comma, "Comma", ","),
+    (KeyCode::Convert, "Convert", "Convert"),
+    (KeyCode::NumpadDecimal, "NumpadDecimal", "Numpad ."),
+    (KeyCode::NumpadDivide, "NumpadDivide", "Numpad /"),
+    (KeyCode::Equals, "Equals", "="),
+    (KeyCode::Grave, "Grave", "`"),
+    (KeyCode::Kana, "Kana", "Kana"),
+    (KeyCode::Kanji, "Kanji", "Kanji"),
+    (KeyCode::LAlt, "LAlt", "Left Alt"),
+    (KeyCode::LBracket, "LBracket", "["),
+    (KeyCode::LControl, "LControl", "Left Ctrl"),
+    (KeyCode::LShift, "LShift", "Left Shift"),
+    (KeyCode::LWin, "LWin", "Left Super"),
+    (KeyCode::Mail, "Mail", "Mail"),
+    (KeyCode::MediaSelect, "MediaSelect", "Media Select"),
+    (KeyCode::MediaStop, "MediaStop", "Media Stop"),
+    (KeyCode::Minus, "Minus", "-"),
+    (KeyCode::NumpadMultiply, "NumpadMultiply", "Numpad *"),
+    (KeyCode::Mute, "Mute", "Mute"),
+    (KeyCode::MyComputer, "MyComputer", "My Computer"),
+    (KeyCode::NavigateForward, "NavigateForward", "Navigate Forward"),
+    (KeyCode::NavigateBackward, "NavigateBackward", "Navigate Backward"),
+    (KeyCode::NextTrack, "NextTrack", "Next Track"),
+    (KeyCode::NoConvert, "NoConvert", "No Convert"),
+    (KeyCode::NumpadComma, "NumpadComma", "Numpad ,"),
+    (KeyCode::NumpadEnter, "NumpadEnter", "Numpad Enter"),
+    (KeyCode::NumpadEquals, "NumpadEquals", "Numpad ="),
+    (KeyCode::Oem102, "Oem102", "Oem102"),
+    (KeyCode::Period, "Period", "."),
+    (KeyCode::PlayPause, "PlayPause", "Play/Pause"),
+    (KeyCode::Power, "Power", "Power"),
+    (KeyCode::PrevTrack, "PrevTrack", "Previous Track"),
+    (KeyCode::RAlt, "RAlt", "Right Alt"),
+    (KeyCode::RBracket, "RBracket", "]"),
+    (KeyCode::RControl, "RControl", "Right Ctrl"),
+    (KeyCode::RShift, "RShift", "Right Shift"),
+    (KeyCode::RWin, "RWin", "Right Super"),
+    (KeyCode::Semicolon, "Semicolon", ";"),
+    (KeyCode::Slash, "Slash", "/"),
+    (KeyCode::Sleep, "Sleep", "Sleep"),
+    (KeyCode::Stop, "Stop", "Stop"),
+    (KeyCode::NumpadSubtract, "NumpadSubtract", "Numpad -"),
+    (KeyCode::Sysrq, "Sysrq", "SysRq"),
+    (KeyCode::Tab, "Tab", "Tab"),
+    (KeyCode::Underline, "Underline", "Underline"),
+    (KeyCode::Unlabeled, "Unlabeled", "Unlabeled"),
+    (KeyCode::VolumeDown, "VolumeDown", "Volume Down"),
+    (KeyCode::VolumeUp, "VolumeUp", "Volume Up"),
+    (KeyCode::Wake, "Wake", "Wake"),
+    (KeyCode::WebBack, "WebBack", "Web Back"),
+    (KeyCode::WebFavorites, "WebFavorites", "Web Favorites"),
+    (KeyCode::WebForward, "WebForward", "Web Forward"),
+    (KeyCode::WebHome, "WebHome", "Web Home"),
+    (KeyCode::WebRefresh, "WebRefresh", "Web Refresh"),
+    (KeyCode::WebSearch, "WebSearch", "Web Search"),
+    (KeyCode::WebStop, "WebStop", "Web Stop"),
+    (KeyCode::Yen, "Yen", "Yen"),
+    (KeyCode::Copy, "Copy", "Copy"),
+    (KeyCode::Paste, "Paste", "Paste"),
+    (KeyCode::Cut, "Cut", "Cut"),
+];
+
+// Other spellings that are accepted in config.toml
+const KEY_ALIASES: &[(&str, KeyCode)] = &[
+    ("0", KeyCode::Key0),
+    ("1", KeyCode::Key1),
+    ("2", KeyCode::Key2),
+    ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4),
+    ("5", KeyCode::Key5),
+    ("6", KeyCode::Key6),
+    ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8),
+    ("9", KeyCode::Key9),
+    ("ESC", KeyCode::Escape),
+    ("ENTER", KeyCode::Return),
+    ("BACKSPACE", KeyCode::Back),
+    ("CAPSLOCK", KeyCode::Capital),
+    ("PRINTSCREEN", KeyCode::Snapshot),
+    ("LCTRL", KeyCode::LControl),
+    ("RCTRL", KeyCode::RControl),
+];
+
+const MODIFIERS: &[(Modifier, &str, &[&str])] = &[
+    (Modifier::Control, "Ctrl", &["CTRL", "CONTROL"]),
+    (Modifier::Shift, "Shift", &["SHIFT"]),
+    (Modifier::Alt, "Alt", &["ALT", "OPTION"]),
+    (Modifier::Win, "Super", &["SUPER", "WIN", "CMD", "META"]),
+];
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BindingButton {
+    Key(KeyCode),
+    Mouse(MouseButton)
+}
+
+// A key or mouse button, optionally held together with modifiers, e.g. "Ctrl+Shift+J"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Binding {
+    pub modifiers: Vec<Modifier>,
+    pub button: BindingButton
+}
+
+impl Binding {
+    pub fn key(key: KeyCode) -> Self {
+        Self {
+            modifiers: vec![],
+            button: BindingButton::Key(key)
+        }
+    }
+
+    pub fn mouse(button: MouseButton) -> Self {
+        Self {
+            modifiers: vec![],
+            button: BindingButton::Mouse(button)
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parts: Vec<&str> = text.split('+').map(|p| p.trim()).collect();
+        let button_name = parts.pop().unwrap();
+
+        if button_name.is_empty() {
+            return Err(format!("missing key in {:?}", text));
+        }
+
+        let mut modifiers = vec![];
+        for part in parts {
+            match modifier_from_name(part) {
+                Some(m) => modifiers.push(m),
+                None => return Err(format!("unknown modifier {:?} in {:?}", part, text))
+            }
+        }
+
+        let button = match mouse_button_from_name(button_name) {
+            Some(m) => BindingButton::Mouse(m),
+            None => match key_code_from_name(button_name) {
+                Some(k) => BindingButton::Key(k),
+                None => return Err(format!("unknown key {:?} in {:?}", button_name, text))
+            }
+        };
+
+        Ok(Self { modifiers, button })
+    }
+
+    pub fn user_input(&self) -> UserInput {
+        let button = match self.button {
+            BindingButton::Key(k) => InputKind::Keyboard(k),
+            BindingButton::Mouse(m) => InputKind::Mouse(m)
+        };
+
+        if self.modifiers.is_empty() {
+            UserInput::Single(button)
+        } else {
+            UserInput::chord(
+                self.modifiers.iter()
+                    .map(|m| InputKind::Modifier(*m))
+                    .chain(std::iter::once(button))
+            )
+        }
+    }
+
+    // The inverse of `Binding::parse`, used when writing bindings back to config.toml
+    pub fn config_name(&self) -> String {
+        let mut out = String::new();
+
+        for m in self.modifiers.iter() {
+            out.push_str(modifier_name(*m));
+            out.push('+');
+        }
+
+        match self.button {
+            BindingButton::Key(k) => out.push_str(key_entry(k).map(|e| e.1).unwrap_or("Unlabeled")),
+            BindingButton::Mouse(m) => out.push_str(&match m {
+                MouseButton::Left => "MouseLeft".to_string(),
+                MouseButton::Right => "MouseRight".to_string(),
+                MouseButton::Middle => "MouseMiddle".to_string(),
+                // not "Mouse{n}", "Mouse1" to "Mouse3" are read back as
+                // Left, Right and Middle
+                MouseButton::Other(n) => format!("MouseOther{}", n)
+            })
+        }
+
+        out
+    }
+}
+
+// Friendly name, e.g. "Ctrl+Left Arrow" or "Left Mouse"
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for m in self.modifiers.iter() {
+            write!(f, "{}+", modifier_name(*m))?;
+        }
+
+        match self.button {
+            BindingButton::Key(k) => write!(f, "{}", key_display_name(k)),
+            BindingButton::Mouse(MouseButton::Left) => write!(f, "Left Mouse"),
+            BindingButton::Mouse(MouseButton::Right) => write!(f, "Right Mouse"),
+            BindingButton::Mouse(MouseButton::Middle) => write!(f, "Middle Mouse"),
+            BindingButton::Mouse(MouseButton::Other(n)) => write!(f, "Mouse {}", n)
+        }
+    }
+}
+
+// Joins several bindings for display, e.g. "A / Left Arrow"
+pub fn describe_bindings(bindings: &[Binding]) -> String {
+    bindings
+        .iter()
+        .map(|b| b.to_string())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+fn key_entry(key: KeyCode) -> Option<&'static (KeyCode, &'static str, &'static str)> {
+    KEYS.iter().find(|e| e.0 == key)
+}
+
+pub fn key_display_name(key: KeyCode) -> &'static str {
+    key_entry(key).map(|e| e.2).unwrap_or("?")
+}
+
+pub fn key_code_from_name(name: &str) -> Option<KeyCode> {
+    let upper = name.to_uppercase();
+
+    KEYS.iter()
+        .find(|(_, config_name, _)| config_name.to_uppercase() == upper)
+        .map(|e| e.0)
+        .or_else(|| {
+            KEY_ALIASES.iter()
+                .find(|(alias, _)| *alias == upper)
+                .map(|e| e.1)
+        })
+}
+
+fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+    let upper = name.to_uppercase();
+
+    match upper.as_str() {
+        "MOUSELEFT" | "MOUSE1" => Some(MouseButton::Left),
+        "MOUSERIGHT" | "MOUSE2" => Some(MouseButton::Right),
+        "MOUSEMIDDLE" | "MOUSE3" => Some(MouseButton::Middle),
+        _ => upper
+            .strip_prefix("MOUSEOTHER")
+            .or_else(|| upper.strip_prefix("MOUSE"))
+            .and_then(|n| n.parse::<u16>().ok())
+            .map(MouseButton::Other)
+    }
+}
+
+fn modifier_from_name(name: &str) -> Option<Modifier> {
+    let upper = name.to_uppercase();
+
+    MODIFIERS.iter()
+        .find(|(_, _, aliases)| aliases.contains(&upper.as_str()))
+        .map(|e| e.0)
+}
+
+fn modifier_name(modifier: Modifier) -> &'static str {
+    MODIFIERS.iter()
+        .find(|e| e.0 == modifier)
+        .map(|e| e.1)
+        .unwrap()
+}
+
+
+const GAMEPAD_BUTTONS: &[(GamepadButtonType, &str)] = &[
+    (GamepadButtonType::South, "South"),
+    (GamepadButtonType::East, "East"),
+    (GamepadButtonType::North, "North"),
+    (GamepadButtonType::West, "West"),
+    (GamepadButtonType::C, "C"),
+    (GamepadButtonType::Z, "Z"),
+    (GamepadButtonType::LeftTrigger, "LeftBumper"),
+    (GamepadButtonType::RightTrigger, "RightBumper"),
+    (GamepadButtonType::LeftTrigger2, "LeftTrigger"),
+    (GamepadButtonType::RightTrigger2, "RightTrigger"),
+    (GamepadButtonType::Select, "Select"),
+    (GamepadButtonType::Start, "Start"),
+    (GamepadButtonType::Mode, "Mode"),
+    (GamepadButtonType::LeftThumb, "LeftThumb"),
+    (GamepadButtonType::RightThumb, "RightThumb"),
+    (GamepadButtonType::DPadUp, "DPadUp"),
+    (GamepadButtonType::DPadDown, "DPadDown"),
+    (GamepadButtonType::DPadLeft, "DPadLeft"),
+    (GamepadButtonType::DPadRight, "DPadRight"),
+];
+
+// (axis, positive direction, name)
+const GAMEPAD_AXES: &[(GamepadAxisType, bool, &str)] = &[
+    (GamepadAxisType::LeftStickX, false, "LeftStickLeft"),
+    (GamepadAxisType::LeftStickX, true, "LeftStickRight"),
+    (GamepadAxisType::LeftStickY, true, "LeftStickUp"),
+    (GamepadAxisType::LeftStickY, false, "LeftStickDown"),
+    (GamepadAxisType::RightStickX, false, "RightStickLeft"),
+    (GamepadAxisType::RightStickX, true, "RightStickRight"),
+    (GamepadAxisType::RightStickY, true, "RightStickUp"),
+    (GamepadAxisType::RightStickY, false, "RightStickDown"),
+];
+
+// How far a stick has to be pushed before it counts as a press
+pub const GAMEPAD_STICK_THRESHOLD: f32 = 0.5;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GamepadBinding {
+    Button(GamepadButtonType),
+    Axis(GamepadAxisType, bool)
+}
+
+impl GamepadBinding {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let upper = text.trim().to_uppercase();
+
+        if let Some(b) = GAMEPAD_BUTTONS.iter().find(|e| e.1.to_uppercase() == upper) {
+            return Ok(GamepadBinding::Button(b.0));
+        }
+
+        if let Some(a) = GAMEPAD_AXES.iter().find(|e| e.2.to_uppercase() == upper) {
+            return Ok(GamepadBinding::Axis(a.0, a.1));
+        }
+
+        Err(format!("unknown gamepad input {:?}", text))
+    }
+
+    pub fn user_input(&self) -> UserInput {
+        match *self {
+            GamepadBinding::Button(b) => UserInput::Single(InputKind::GamepadButton(b)),
+            GamepadBinding::Axis(axis, true) => {
+                UserInput::Single(InputKind::SingleAxis(
+                    SingleAxis::positive_only(axis, GAMEPAD_STICK_THRESHOLD)
+                ))
+            },
+            GamepadBinding::Axis(axis, false) => {
+                UserInput::Single(InputKind::SingleAxis(
+                    SingleAxis::negative_only(axis, -GAMEPAD_STICK_THRESHOLD)
+                ))
+            }
+        }
+    }
+
+    pub fn config_name(&self) -> &'static str {
+        match *self {
+            GamepadBinding::Button(b) => GAMEPAD_BUTTONS.iter().find(|e| e.0 == b).unwrap().1,
+            GamepadBinding::Axis(axis, positive) => {
+                GAMEPAD_AXES.iter().find(|e| e.0 == axis && e.1 == positive).unwrap().2
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_names_read_back_as_the_same_binding() {
+        let mut bindings: Vec<Binding> = KEYS.iter().map(|e| Binding::key(e.0)).collect();
+
+        bindings.extend(
+            [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                .into_iter()
+                .chain((0..=8).map(MouseButton::Other))
+                .map(Binding::mouse)
+        );
+
+        for (m, _, _) in MODIFIERS.iter() {
+            bindings.push(Binding { modifiers: vec![*m], ..Binding::key(KeyCode::J) });
+            bindings.push(Binding { modifiers: vec![*m], ..Binding::mouse(MouseButton::Other(2)) });
+        }
+
+        for binding in bindings {
+            let name = binding.config_name();
+            assert_eq!(Binding::parse(&name), Ok(binding), "{:?} read back as something else", name);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use leafwing_input_manager::plugin::InputManagerSystem;
+use crate::player::Player;
+
+pub mod bindings;
+
+use bindings::{Binding, GamepadBinding};
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct PlayerControls {
+    pub move_left: Vec<Binding>,
+    pub move_right: Vec<Binding>,
+
+    pub crouch: Vec<Binding>,
+    pub jump: Vec<Binding>,
+
+    pub slash: Vec<Binding>,
+    pub shoot: Vec<Binding>,
+    pub dash: Vec<Binding>,
+
+    pub interact: Vec<Binding>,
+    pub pause: Vec<Binding>,
+
+    pub gamepad: GamepadControls,
+}
+
+impl Default for PlayerControls {
+    fn default() -> Self {
+       Self {
+           move_left: vec![Binding::key(KeyCode::A)],
+           move_right: vec![Binding::key(KeyCode::D)],
+           crouch: vec![Binding::key(KeyCode::S)],
+           jump: vec![Binding::key(KeyCode::Space)],
+           slash: vec![Binding::key(KeyCode::Left)],
+           shoot: vec![Binding::key(KeyCode::Up)],
+           dash: vec![Binding::key(KeyCode::Right)],
+           interact: vec![Binding::key(KeyCode::E)],
+           pause: vec![Binding::key(KeyCode::Escape)],
+           gamepad: GamepadControls::default(),
+       }
+    }
+}
+
+impl PlayerControls {
+    // (name in config.toml, bindings, action)
+    pub fn actions(&self) -> [(&'static str, &Vec<Binding>, InputAction); 9] {
+        use InputAction::*;
+
+        [
+            ("move_left", &self.move_left, RunLeft),
+            ("move_right", &self.move_right, RunRight),
+            ("crouch", &self.crouch, Crouch),
+            ("jump", &self.jump, Jump),
+            ("slash", &self.slash, Slash),
+            ("shoot", &self.shoot, Shoot),
+            ("dash", &self.dash, Dash),
+            ("interact", &self.interact, Interact),
+            ("pause", &self.pause, Pause),
+        ]
+    }
+
+    pub fn bindings_mut(&mut self, name: &str) -> Option<&mut Vec<Binding>> {
+        match name {
+            "move_left" => Some(&mut self.move_left),
+            "move_right" => Some(&mut self.move_right),
+            "crouch" => Some(&mut self.crouch),
+            "jump" => Some(&mut self.jump),
+            "slash" => Some(&mut self.slash),
+            "shoot" => Some(&mut self.shoot),
+            "dash" => Some(&mut self.dash),
+            "interact" => Some(&mut self.interact),
+            "pause" => Some(&mut self.pause),
+            _ => None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GamepadControls {
+    pub move_left: Vec<GamepadBinding>,
+    pub move_right: Vec<GamepadBinding>,
+
+    pub crouch: Vec<GamepadBinding>,
+    pub jump: Vec<GamepadBinding>,
+
+    pub slash: Vec<GamepadBinding>,
+    pub shoot: Vec<GamepadBinding>,
+    pub dash: Vec<GamepadBinding>,
+
+    pub interact: Vec<GamepadBinding>,
+    pub pause: Vec<GamepadBinding>,
+}
+
+impl Default for GamepadControls {
+    fn default() -> Self {
+        use GamepadBinding::*;
+        use GamepadButtonType::*;
+
+        Self {
+            move_left: vec![Button(DPadLeft), Axis(GamepadAxisType::LeftStickX, false)],
+            move_right: vec![Button(DPadRight), Axis(GamepadAxisType::LeftStickX, true)],
+            crouch: vec![Button(DPadDown), Axis(GamepadAxisType::LeftStickY, false)],
+            jump: vec![Button(South)],
+            slash: vec![Button(West)],
+            shoot: vec![Button(North)],
+            dash: vec![Button(East)],
+            interact: vec![Button(RightTrigger)],
+            pause: vec![Button(Start)],
+        }
+    }
+}
+
+impl GamepadControls {
+    // (name in config.toml, bindings, action)
+    pub fn actions(&self) -> [(&'static str, &Vec<GamepadBinding>, InputAction); 9] {
+        use InputAction::*;
+
+        [
+            ("move_left", &self.move_left, RunLeft),
+            ("move_right", &self.move_right, RunRight),
+            ("crouch", &self.crouch, Crouch),
+            ("jump", &self.jump, Jump),
+            ("slash", &self.slash, Slash),
+            ("shoot", &self.shoot, Shoot),
+            ("dash", &self.dash, Dash),
+            ("interact", &self.interact, Interact),
+            ("pause", &self.pause, Pause),
+        ]
+    }
+
+    pub fn bindings_mut(&mut self, name: &str) -> Option<&mut Vec<GamepadBinding>> {
+        match name {
+            "move_left" => Some(&mut self.move_left),
+            "move_right" => Some(&mut self.move_right),
+            "crouch" => Some(&mut self.crouch),
+            "jump" => Some(&mut self.jump),
+            "slash" => Some(&mut self.slash),
+            "shoot" => Some(&mut self.shoot),
+            "dash" => Some(&mut self.dash),
+            "interact" => Some(&mut self.interact),
+            "pause" => Some(&mut self.pause),
+            _ => None
+        }
+    }
+}
+
+
+#[derive(Actionlike, PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum InputAction {
+    RunLeft,
+    RunRight,
+    Crouch,
+    Jump,
+
+    Slash,
+    Dash,
+    Shoot,
+
+    Interact,
+    Pause,
+}
+
+impl InputAction {
+    pub fn input_map(controls: &PlayerControls) -> InputMap<Self> {
+        let mut map = InputMap::default();
+
+        for (_, bindings, action) in controls.actions() {
+            for binding in bindings.iter() {
+                map.insert(binding.user_input(), action);
+            }
+        }
+
+        for (_, bindings, action) in controls.gamepad.actions() {
+            for binding in bindings.iter() {
+                map.insert(binding.user_input(), action);
+            }
+        }
+
+        map
+    }
+
+    pub fn input_manager_bundle(controls: &PlayerControls) -> InputManagerBundle<Self> {
+        InputManagerBundle {
+            action_state: ActionState::default(),
+            input_map: Self::input_map(&controls)
+        }
+    }
+}
+
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(InputManagerPlugin::<InputAction>::default())
+            .init_resource::<PlayerControls>()
+            .init_resource::<ScriptedInput>()
+            .add_system(rebuild_input_maps)
+            .add_system(assign_gamepad.after(rebuild_input_maps))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                apply_scripted_input.after(InputManagerSystem::Update)
+            );
+    }
+}
+
+// When enabled, the player's actions come from `pressed` instead of the
+// keyboard or a controller. Used by replays and the headless app.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ScriptedInput {
+    pub enabled: bool,
+    pub pressed: HashSet<InputAction>
+}
+
+
+
+// Hands the first connected controller to the player, and picks another
+// one if that controller gets unplugged
+fn assign_gamepad(
+    gamepads: Res<Gamepads>,
+    mut q: Query<&mut InputMap<InputAction>>
+) {
+    for mut map in q.iter_mut() {
+        let connected = map.gamepad().map(|g| gamepads.contains(g)).unwrap_or(false);
+        if connected {
+            continue;
+        }
+
+        if let Some(gamepad) = gamepads.iter().next() {
+            map.set_gamepad(gamepad);
+        }
+    }
+}
+
+// Applies rebinds from the settings menu or config.toml to a player that's
+// already been spawned
+fn rebuild_input_maps(
+    controls: Res<PlayerControls>,
+    mut q: Query<&mut InputMap<InputAction>>
+) {
+    if !controls.is_changed() {
+        return;
+    }
+
+    for mut map in q.iter_mut() {
+        let gamepad = map.gamepad();
+        *map = InputAction::input_map(&controls);
+
+        if let Some(gamepad) = gamepad {
+            map.set_gamepad(gamepad);
+        }
+    }
+}
+
+pub fn apply_scripted_input(
+    mut commands: Commands,
+    script: Res<ScriptedInput>,
+    controls: Res<PlayerControls>,
+    mut players: Query<(Entity, &mut ActionState<InputAction>, Option<&InputMap<InputAction>>), With<Player>>
+) {
+    if !script.enabled {
+        // hand control back to the keyboard once a replay is over
+        for (entity, _, map) in players.iter() {
+            if map.is_none() {
+                commands.entity(entity).insert(InputAction::input_map(&controls));
+            }
+        }
+
+        return;
+    }
+
+    for (entity, mut actions, map) in players.iter_mut() {
+        // leafwing only updates action states that have an input map, so
+        // without one the script is the only thing pressing buttons
+        if map.is_some() {
+            commands.entity(entity).remove::<InputMap<InputAction>>();
+        }
+
+        for action in InputAction::variants() {
+            if script.pressed.contains(&action) {
+                actions.press(action);
+            } else {
+                actions.release(action);
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use bevy::math::Vec3Swizzles;
 use leafwing_input_manager::prelude::ActionState;
 
 
+use crate::common::UpdateStage;
 use crate::input::InputAction;
 use crate::player::Player;
 
@@ -19,7 +20,7 @@ pub struct InteractPlugin;
 impl Plugin for InteractPlugin {
     fn build(&self, app: &mut App) {
        app.add_system_set(
-           SystemSet::new()
+           UpdateStage::GameLogic.set()
                .with_system(interact_update)
                .with_system(interact_spawn_text)
                .with_system(despawn_text)
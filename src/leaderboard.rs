@@ -0,0 +1,275 @@
+use std::fs;
+use std::path::PathBuf;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::player::logic::PlayerScore;
+use crate::replay::ReplayPlayback;
+use crate::rng::GameRng;
+use crate::run_stats::{format_time, RunStats};
+use crate::save::save_dir;
+use crate::state::GameState;
+
+// Bump this whenever the layout of `LeaderboardEntry` changes, old
+// leaderboards get ignored
+pub const LEADERBOARD_VERSION: u32 = 1;
+
+pub const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct LeaderboardEntry {
+    pub score: u32,
+    pub won: bool,
+    pub level: String,
+    pub time: f32,
+
+    pub kills: u32,
+    pub deaths: u32,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+
+    pub seed: u64
+}
+
+impl LeaderboardEntry {
+    // "1. 120 coins  Won  12:34"
+    pub fn describe(&self, rank: usize) -> String {
+        let result = if self.won { "Won".to_string() } else { self.level.clone() };
+        format!("{}. {} coins  {}  {}", rank + 1, self.score, result, format_time(self.time))
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct Leaderboard {
+    pub version: u32,
+    pub entries: Vec<LeaderboardEntry>,
+
+    // where the run that just ended placed, if it made it on at all
+    #[serde(skip)]
+    pub last_rank: Option<usize>
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self {
+            version: LEADERBOARD_VERSION,
+            entries: vec![],
+            last_rank: None
+        }
+    }
+}
+
+pub fn leaderboard_file_path() -> PathBuf {
+    save_dir().join("leaderboard.ron")
+}
+
+impl Leaderboard {
+    pub fn load() -> Self {
+        let path = leaderboard_file_path();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => return Self::default()
+        };
+
+        let board: Leaderboard = match ron::from_str(&text) {
+            Ok(board) => board,
+            Err(e) => {
+                warn!("Failed to parse leaderboard {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        if board.version != LEADERBOARD_VERSION {
+            warn!(
+                "Ignoring leaderboard {:?} with version {} (expected {})",
+                path, board.version, LEADERBOARD_VERSION
+            );
+            return Self::default();
+        }
+
+        board
+    }
+
+    pub fn write(&self) {
+        let path = leaderboard_file_path();
+
+        if let Err(e) = fs::create_dir_all(save_dir()) {
+            error!("Failed to create save directory {:?}: {}", save_dir(), e);
+            return;
+        }
+
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap();
+
+        if let Err(e) = fs::write(&path, text) {
+            error!("Failed to write leaderboard {:?}: {}", path, e);
+        }
+    }
+
+    // Highest score first, faster runs break ties. Returns where the entry
+    // ended up, or None if it didn't make the cut.
+    pub fn insert(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let rank = self.entries
+            .iter()
+            .position(|e| entry.score > e.score || entry.score == e.score && entry.time < e.time)
+            .unwrap_or(self.entries.len());
+
+        if rank >= LEADERBOARD_SIZE {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(LEADERBOARD_SIZE);
+
+        Some(rank)
+    }
+}
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Leaderboard::load());
+
+        for state in [GameState::GameWonMenu, GameState::GameLostMenu] {
+            app.add_system_set(
+                SystemSet::on_enter(state.clone())
+                    .with_system(record_run)
+            );
+        }
+    }
+}
+
+fn record_run(
+    state: Res<State<GameState>>,
+    stats: Res<RunStats>,
+    score: Res<PlayerScore>,
+    rng: Res<GameRng>,
+    playback: Res<ReplayPlayback>,
+    mut board: ResMut<Leaderboard>
+) {
+    board.last_rank = None;
+
+    // runs played back from a replay file don't count
+    if playback.started {
+        return;
+    }
+
+    let won = *state.current() == GameState::GameWonMenu;
+
+    let entry = LeaderboardEntry {
+        score: score.score,
+        won,
+        level: stats.last_level().unwrap_or("Init").to_string(),
+        time: stats.total_time(),
+
+        kills: stats.total_kills(),
+        deaths: stats.deaths,
+        damage_dealt: stats.damage_dealt,
+        damage_taken: stats.damage_taken,
+
+        seed: rng.seed()
+    };
+
+    board.last_rank = board.insert(entry);
+
+    if board.last_rank.is_some() {
+        board.write();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u32, time: f32) -> LeaderboardEntry {
+        LeaderboardEntry {
+            score,
+            won: false,
+            level: "Level_0".to_string(),
+            time,
+            kills: 0,
+            deaths: 0,
+            damage_dealt: 0,
+            damage_taken: 0,
+            seed: 0
+        }
+    }
+
+    fn scores(board: &Leaderboard) -> Vec<(u32, f32)> {
+        board.entries.iter().map(|e| (e.score, e.time)).collect()
+    }
+
+    // 100, 90, ... 10, all in 60 seconds
+    fn full_board() -> Leaderboard {
+        let mut board = Leaderboard::default();
+
+        for i in 0..LEADERBOARD_SIZE {
+            board.insert(entry(100 - i as u32 * 10, 60.0));
+        }
+
+        board
+    }
+
+    #[test]
+    fn highest_score_first() {
+        let mut board = Leaderboard::default();
+
+        assert_eq!(board.insert(entry(50, 60.0)), Some(0));
+        assert_eq!(board.insert(entry(80, 60.0)), Some(0));
+        assert_eq!(board.insert(entry(20, 60.0)), Some(2));
+        assert_eq!(board.insert(entry(60, 60.0)), Some(1));
+
+        assert_eq!(scores(&board), vec![(80, 60.0), (60, 60.0), (50, 60.0), (20, 60.0)]);
+    }
+
+    #[test]
+    fn full_board_drops_the_lowest() {
+        let mut board = full_board();
+        assert_eq!(board.entries.len(), LEADERBOARD_SIZE);
+
+        assert_eq!(board.insert(entry(55, 60.0)), Some(5));
+
+        assert_eq!(board.entries.len(), LEADERBOARD_SIZE);
+        assert_eq!(board.entries[5].score, 55);
+        assert_eq!(board.entries.last().unwrap().score, 20);
+    }
+
+    #[test]
+    fn ties_go_to_the_faster_run() {
+        let mut board = full_board();
+
+        // faster than the 50 already on the board, so ahead of it
+        assert_eq!(board.insert(entry(50, 30.0)), Some(5));
+        // slower than both, behind them
+        assert_eq!(board.insert(entry(50, 90.0)), Some(7));
+        // as fast as the one already there, which got there first
+        assert_eq!(board.insert(entry(50, 60.0)), Some(7));
+
+        assert_eq!(
+            scores(&board)[5..9],
+            [(50, 30.0), (50, 60.0), (50, 60.0), (50, 90.0)]
+        );
+        assert_eq!(board.entries.len(), LEADERBOARD_SIZE);
+    }
+
+    #[test]
+    fn too_low_to_make_the_cut() {
+        let mut board = full_board();
+        let before = board.entries.clone();
+
+        assert_eq!(board.insert(entry(5, 60.0)), None);
+        // ties with the last place, but isn't faster
+        assert_eq!(board.insert(entry(10, 60.0)), None);
+        assert_eq!(board.insert(entry(10, 90.0)), None);
+
+        assert_eq!(board.entries, before);
+    }
+
+    #[test]
+    fn last_place_taken_by_a_faster_tie() {
+        let mut board = full_board();
+
+        assert_eq!(board.insert(entry(10, 30.0)), Some(LEADERBOARD_SIZE - 1));
+        assert_eq!(board.entries.last().unwrap().time, 30.0);
+        assert_eq!(board.entries.len(), LEADERBOARD_SIZE);
+    }
+}
@@ -3,9 +3,11 @@ use std::collections::HashMap;
 use bevy_ecs_ldtk::prelude::*;
 use crate::assets::BossAssets;
 use crate::bossfight::{BossBundle, BossConfig};
-use crate::level::{coord, LevelInfo, util};
+use crate::level::{coord, LevelInfo};
+use crate::level::util::LdtkFields;
 use crate::level::consts::RENDERED_TILE_SIZE;
-use crate::state::GameState;
+use crate::pathfind::Region;
+use crate::level::LevelSystems;
 
 
 #[derive(Component, Copy, Clone, Default)]
@@ -37,7 +39,7 @@ pub fn register_boss_spawnpoints(app: &mut App) {
         .register_ldtk_entity::<RegionBundle>("SummonRegion")
         .register_ldtk_entity::<BossSpawnpointBundle>("BossSpawnpoint")
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Boss.on_update()
                 .with_system(spawn_boss)
         );
 }
@@ -60,49 +62,11 @@ fn spawn_boss(
     for inst in boss.iter() {
         let mut boss = BossBundle::from_assets(&assets);
 
-        boss.config = {
-            let (charge_left, charge_right, hover_base, slam_base) = {
-                let mut v: [Vec2; 4] = [Vec2::ZERO; 4];
-
-                for i in 0..v.len() {
-                    let p = util::val_expect_point(&inst.field_instances[i].value).unwrap();
-                    println!("{:?} {:?}", i, p);
-                    v[i] = coord::grid_coord_to_translation(p, lvl_info.grid_size.as_ivec2());
-                }
-
-                (v[0], v[1], v[2], v[3])
-            };
-
-            let boom_region = {
-                let id = util::val_expect_ent_ref(&inst.field_instances[4].value).unwrap();
-                region_map[&id.entity_iid]
-            };
-
-            let summon_region = {
-                let id = util::val_expect_ent_ref(&inst.field_instances[5].value).unwrap();
-                region_map[&id.entity_iid]
-            };
-
-            println!("summon region: {:?}", summon_region);
-
-            let rightmost = charge_right - Vec2::new(256.0 - RENDERED_TILE_SIZE, 0.0);
-            let leftmost = charge_left + Vec2::new(256.0, 0.0);
-            let bottommost = slam_base + Vec2::new(0.0, 256.0 - RENDERED_TILE_SIZE);
-
-            BossConfig {
-                boom_region,
-                summon_region,
-
-                hover_base,
-                slam_base: bottommost,
-
-                charge_left: leftmost,
-                charge_right: rightmost,
-
-                relocate_point: rightmost,
-
-                x_min: charge_left.x,
-                x_max: charge_right.x
+        boss.config = match read_boss_config(inst, &region_map, &lvl_info) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                error!("Skipping boss spawnpoint: {}", e);
+                continue;
             }
         };
 
@@ -113,4 +77,53 @@ fn spawn_boss(
 
         BossBundle::spawn(&mut commands, boss);
     }
-}
\ No newline at end of file
+}
+
+fn read_boss_config(
+    inst: &EntityInstance,
+    region_map: &HashMap<String, Region>,
+    lvl_info: &LevelInfo
+) -> Result<BossConfig, String> {
+    let point = |id: &str| -> Result<Vec2, String> {
+        let p = inst.field_point(id)?;
+        Ok(coord::grid_coord_to_translation(p, lvl_info.grid_size.as_ivec2()))
+    };
+
+    let region = |id: &str| -> Result<Region, String> {
+        let e_ref = inst.field_ent_ref(id)?;
+        region_map
+            .get(&e_ref.entity_iid)
+            .copied()
+            .ok_or_else(|| format!("{}: {} {} isn't in this level", inst.describe(), id, e_ref.entity_iid))
+    };
+
+    let charge_left = point("ChargeLeft")?;
+    let charge_right = point("ChargeRight")?;
+    let hover_base = point("HoverBase")?;
+    let slam_base = point("SlamBase")?;
+
+    let boom_region = region("BoomRegion")?;
+    let summon_region = region("SummonRegion")?;
+
+    debug!("Summon region: {:?}", summon_region);
+
+    let rightmost = charge_right - Vec2::new(256.0 - RENDERED_TILE_SIZE, 0.0);
+    let leftmost = charge_left + Vec2::new(256.0, 0.0);
+    let bottommost = slam_base + Vec2::new(0.0, 256.0 - RENDERED_TILE_SIZE);
+
+    Ok(BossConfig {
+        boom_region,
+        summon_region,
+
+        hover_base,
+        slam_base: bottommost,
+
+        charge_left: leftmost,
+        charge_right: rightmost,
+
+        relocate_point: rightmost,
+
+        x_min: charge_left.x,
+        x_max: charge_right.x
+    })
+}
@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_ecs_tilemap::prelude::*;
+use bevy_rapier2d::prelude::*;
+use crate::coin::drops::CoinHolder;
+use crate::combat::ExplosionEvent;
+use crate::entity_states::Die;
+use crate::level::{coord, LevelInfo};
+use crate::level::consts::{BREAKABLE_TILE_VALUE, RENDERED_TILE_SIZE, TILES_LAYER};
+use crate::level::solid::SolidTile;
+use crate::pathfind::grid::PathfindingGrid;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+// What a block drops when it's blown open, so levels can hide coins behind
+// breakable walls
+const BREAKABLE_TILE_COINS: i32 = 5;
+
+#[derive(Default, Component)]
+pub struct BreakableTileSpawnMarker;
+
+#[derive(Bundle, LdtkIntCell)]
+pub struct BreakableTileBundle {
+    marker: BreakableTileSpawnMarker
+}
+
+// Unlike solid tiles these aren't merged, every block breaks on its own.
+// Added to the IntGrid cell itself, which is also the cracked tile LDtk
+// draws there, so the whole block goes away when it's broken.
+#[derive(Component, Copy, Clone, Debug)]
+pub struct BreakableTile {
+    // in `PathfindingGrid` coordinates
+    pub cell: IVec2
+}
+
+pub fn register_breakable_tiles(app: &mut App) {
+    app
+        .register_ldtk_int_cell_for_layer::<BreakableTileBundle>(TILES_LAYER, BREAKABLE_TILE_VALUE)
+        .add_system_set(
+            LevelSystems::Breakables.on_update()
+                .with_system(add_breakable_tiles)
+        )
+        .add_system_set(
+            UpdateStage::Combat.on_update(GameState::Gameplay)
+                .with_system(break_tiles_in_explosions)
+        );
+}
+
+fn add_breakable_tiles(
+    mut commands: Commands,
+    q: Query<(Entity, &GridCoords), Added<BreakableTileSpawnMarker>>,
+    lvl_info: Res<LevelInfo>
+) {
+    for (e, coords) in q.iter() {
+        let cell = IVec2::new(coords.x, lvl_info.grid_size.y as i32 - coords.y - 1);
+
+        commands.entity(e).insert((
+            BreakableTile { cell },
+            SolidTile,
+            CoinHolder { total_value: BREAKABLE_TILE_COINS },
+            Collider::cuboid(4., 4.),
+            RigidBody::Fixed
+        ));
+    }
+}
+
+// Dying drops the block's coins through `coin::drops` and despawns it
+fn break_tiles_in_explosions(
+    mut commands: Commands,
+    mut explosions: EventReader<ExplosionEvent>,
+    tiles: Query<(Entity, &BreakableTile, &TilePos, &TilemapId), Without<Die>>,
+    mut tilemaps: Query<&mut TileStorage>,
+    mut grid: ResMut<PathfindingGrid>,
+    lvl_info: Res<LevelInfo>
+) {
+    for explosion in explosions.iter() {
+        for (e, tile, tile_pos, tilemap) in tiles.iter() {
+            // not the cell's `GlobalTransform`, Rapier writes the body back
+            // into it without the level's scale
+            let top_left = coord::grid_coord_to_translation(tile.cell, lvl_info.grid_size.as_ivec2());
+            let center = coord::top_left_to_center(top_left, Vec2::splat(RENDERED_TILE_SIZE));
+            let dist = center.distance(explosion.pos);
+
+            // anywhere on the block counts, not just its center
+            if dist > explosion.radius + RENDERED_TILE_SIZE / 2.0 {
+                continue;
+            }
+
+            grid.solids.remove(&tile.cell);
+
+            if let Ok(mut storage) = tilemaps.get_mut(tilemap.0) {
+                storage.remove(tile_pos);
+            }
+
+            commands.entity(e).insert(Die { should_despawn: true });
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use crate::level::{coord, LevelInfo};
+use crate::level::util::LdtkFields;
+use crate::level::LevelSystems;
+
+#[derive(Component, Copy, Clone, Default)]
+pub struct CameraZoneMarker;
+
+#[derive(Bundle, Default, LdtkEntity)]
+pub struct CameraZoneBundle {
+    marker: CameraZoneMarker,
+
+    #[from_entity_instance]
+    inst: EntityInstance
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CameraZoneMode {
+    // the camera doesn't leave the zone while the player is in it
+    Lock,
+    // the whole zone is kept in view, zooming out if it doesn't fit
+    Frame,
+    // pulls the camera towards the zone's center, 0 to 1
+    Bias(f32)
+}
+
+// Added to the zone's LDtk entity, so it goes away with the level
+#[derive(Component, Copy, Clone, Debug)]
+pub struct CameraZone {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub mode: CameraZoneMode
+}
+
+impl CameraZone {
+    pub fn contains(&self, pos: Vec2) -> bool {
+        pos.cmpge(self.min).all() && pos.cmple(self.max).all()
+    }
+
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn area(&self) -> f32 {
+        self.size().x * self.size().y
+    }
+}
+
+pub fn register_camera_zones(app: &mut App) {
+    app
+        .register_ldtk_entity::<CameraZoneBundle>("CameraZone")
+        .add_system_set(
+            LevelSystems::CameraZones.on_update()
+                .with_system(add_camera_zones)
+        );
+}
+
+pub fn read_camera_zone_mode(inst: &EntityInstance) -> Result<CameraZoneMode, String> {
+    match inst.field_enum("Mode")?.as_str() {
+        "Lock" => Ok(CameraZoneMode::Lock),
+        "Frame" => Ok(CameraZoneMode::Frame),
+        "Bias" => {
+            let bias = inst.field_f32("Bias")?;
+
+            if !(0.0..=1.0).contains(&bias) {
+                return Err(format!("{}: Bias should be between 0 and 1, found {}", inst.describe(), bias));
+            }
+
+            Ok(CameraZoneMode::Bias(bias))
+        }
+        mode => Err(format!("{}: Unknown camera zone mode {:?}", inst.describe(), mode))
+    }
+}
+
+fn add_camera_zones(
+    mut commands: Commands,
+    q: Query<(Entity, &EntityInstance), Added<CameraZoneMarker>>,
+    lvl_info: Res<LevelInfo>
+) {
+    for (e, inst) in q.iter() {
+        let mode = match read_camera_zone_mode(inst) {
+            Ok(mode) => mode,
+            Err(e) => {
+                error!("Skipping camera zone: {}", e);
+                continue;
+            }
+        };
+
+        // regions are stored top left to bottom right, y goes up in the world
+        let region = coord::grid_coords_to_region(inst, lvl_info.grid_size);
+
+        commands.entity(e).insert(CameraZone {
+            min: region.tl.min(region.br),
+            max: region.tl.max(region.br),
+            mode
+        });
+    }
+}
@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::coin::drops::CoinHolder;
+use crate::combat::Health;
+use crate::entity_states::Die;
+use crate::level::{coord, FinishedTransitioning, LevelInfo};
+use crate::level::consts::SCALE_FACTOR;
+use crate::level::memory::{current_level, LevelMemory, RoomMemory};
+use crate::player::ammo::Ammo;
+use crate::player::consts::PLAYER_SIZE_PX;
+use crate::player::Player;
+use crate::player::skill::PlayerSkillLevels;
+use crate::save::SaveData;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+// What dying costs when there's a checkpoint to go back to, read from the
+// `[respawn]` section of config.toml
+#[derive(Resource, Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct RespawnSettings {
+    pub penalty: RespawnPenalty,
+    // share of the checkpoint's coins lost on every respawn, for `Coins`
+    pub coin_loss: f32,
+    // respawns before the run is over, for `Lives`
+    pub lives: u32
+}
+
+impl Default for RespawnSettings {
+    fn default() -> Self {
+        Self {
+            penalty: RespawnPenalty::Coins,
+            coin_loss: 0.25,
+            lives: 3
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RespawnPenalty {
+    Coins,
+    Lives
+}
+
+#[derive(Component, Default)]
+pub struct CheckpointTileMarker;
+
+#[derive(Bundle, LdtkEntity)]
+pub struct CheckpointTileBundle {
+    marker: CheckpointTileMarker,
+    #[from_entity_instance]
+    instance: EntityInstance
+}
+
+#[derive(Component)]
+pub struct Checkpoint {
+    pub iid: String,
+    pub spawn_pos: Vec2
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckpointSnapshot {
+    // None for the start of a level
+    pub iid: Option<String>,
+    pub pos: Vec2,
+    pub save: SaveData,
+    // what had been cleared out of the level by then
+    pub room: RoomMemory
+}
+
+// The last checkpoint reached in this run. Entering a level counts as
+// reaching a checkpoint at its entry point.
+#[derive(Resource, Default, Debug)]
+pub struct CheckpointState {
+    pub snapshot: Option<CheckpointSnapshot>,
+    pub lives_left: u32,
+    pub respawning: bool
+}
+
+impl CheckpointState {
+    pub fn new_run(settings: &RespawnSettings) -> Self {
+        Self {
+            lives_left: settings.lives,
+            ..default()
+        }
+    }
+
+    // Charges the respawn penalty, returns the level to reload or None if
+    // the run is over
+    pub fn begin_respawn(&mut self, settings: &RespawnSettings) -> Option<String> {
+        let snapshot = self.snapshot.as_mut()?;
+
+        match settings.penalty {
+            RespawnPenalty::Coins => {
+                let lost = snapshot.save.coins as f32 * settings.coin_loss.clamp(0.0, 1.0);
+                snapshot.save.coins -= lost.ceil() as i32;
+            }
+
+            RespawnPenalty::Lives => {
+                if self.lives_left == 0 {
+                    return None;
+                }
+
+                self.lives_left -= 1;
+            }
+        }
+
+        self.respawning = true;
+        Some(snapshot.save.level.clone())
+    }
+}
+
+pub fn register_checkpoints(app: &mut App) {
+    app
+        .register_ldtk_entity::<CheckpointTileBundle>("Checkpoint")
+        .init_resource::<RespawnSettings>()
+        .init_resource::<CheckpointState>()
+        .add_system_set(
+            SystemSet::on_enter(GameState::MainMenu)
+                .with_system(reset_checkpoints)
+        )
+        .add_system_set(
+            LevelSystems::Checkpoints.on_enter()
+                .with_system(restore_room_memory)
+        )
+        .add_system_set(
+            LevelSystems::Checkpoints.on_update()
+                .with_system(add_checkpoints)
+                .with_system(restore_checkpoint.after(add_checkpoints))
+        )
+        .add_system_set(
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .with_system(reach_checkpoints)
+        );
+}
+
+fn reset_checkpoints(mut state: ResMut<CheckpointState>, settings: Res<RespawnSettings>) {
+    *state = CheckpointState::new_run(&settings);
+}
+
+fn add_checkpoints(
+    mut commands: Commands,
+    q: Query<&EntityInstance, Added<CheckpointTileMarker>>,
+    lvl_info: Res<LevelInfo>
+) {
+    for inst in q.iter() {
+        // `px` is wherever the entity's pivot is, LDtk's grid is by its top
+        // left corner
+        let size = Vec2::new(inst.width as f32, inst.height as f32);
+        let top_left_px = inst.px - (inst.pivot * size).as_ivec2();
+
+        let top_left = coord::px_to_translation(top_left_px, lvl_info.grid_size.as_ivec2());
+        let extents = size * SCALE_FACTOR;
+        let center = coord::top_left_to_center(top_left, extents);
+
+        // same offset as the level's entry point
+        let pos = coord::grid_coord_to_translation(inst.grid, lvl_info.grid_size.as_ivec2());
+        let spawn_pos = pos + Vec2::new(PLAYER_SIZE_PX.x / 2.0, 0.0);
+
+        commands.spawn(checkpoint_bundle(&inst.iid, center, extents / 2.0, spawn_pos));
+    }
+}
+
+fn checkpoint_bundle(iid: &str, center: Vec2, half_extents: Vec2, spawn_pos: Vec2) -> impl Bundle {
+    (
+        Checkpoint {
+            iid: iid.to_string(),
+            spawn_pos
+        },
+        Sensor,
+        RigidBody::Fixed,
+        // the player is kinematic, see `hazard_tile_bundle`
+        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+        Collider::cuboid(half_extents.x, half_extents.y),
+        TransformBundle::from_transform(Transform::from_xyz(center.x, center.y, 0.0))
+    )
+}
+
+// Enemies killed and doors opened after the checkpoint come back with the
+// coins they were worth
+fn restore_room_memory(state: Res<CheckpointState>, mut memory: ResMut<LevelMemory>) {
+    if !state.respawning {
+        return;
+    }
+
+    if let Some(snapshot) = &state.snapshot {
+        *memory.room_mut(&snapshot.save.level) = snapshot.room.clone();
+    }
+}
+
+// Runs once the player has been placed at the level's entry point
+fn restore_checkpoint(
+    mut state: ResMut<CheckpointState>,
+    memory: Res<LevelMemory>,
+    mut player: Query<(
+        &mut Transform,
+        &mut PlayerSkillLevels,
+        &mut CoinHolder,
+        &mut Health,
+        &mut Ammo
+    ), (With<Player>, Added<FinishedTransitioning>)>,
+    sel: Res<LevelSelection>
+) {
+    let (mut tf, mut skills, mut coins, mut health, mut ammo) = match player.get_single_mut() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    if state.respawning {
+        state.respawning = false;
+
+        if let Some(snapshot) = &state.snapshot {
+            snapshot.save.apply(&mut skills, &mut coins, &mut health, &mut ammo);
+            tf.translation.x = snapshot.pos.x;
+            tf.translation.y = snapshot.pos.y;
+
+            info!("Respawned at {:?} in {}", snapshot.pos, snapshot.save.level);
+        }
+
+        return;
+    }
+
+    let level = match current_level(&sel) {
+        Some(level) => level.to_string(),
+        None => return
+    };
+
+    state.snapshot = Some(CheckpointSnapshot {
+        iid: None,
+        pos: tf.translation.truncate(),
+        save: SaveData::capture(&level, &skills, &coins, &health, &ammo),
+        room: memory.room(&level).cloned().unwrap_or_default()
+    });
+}
+
+fn reach_checkpoints(
+    player: Query<(
+        Entity,
+        &PlayerSkillLevels,
+        &CoinHolder,
+        &Health,
+        &Ammo
+    ), (With<Player>, Without<Die>)>,
+    checkpoints: Query<(Entity, &Checkpoint)>,
+    rapier: Res<RapierContext>,
+    memory: Res<LevelMemory>,
+    mut state: ResMut<CheckpointState>
+) {
+    let (p, skills, coins, health, ammo) = match player.get_single() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    for (entity, checkpoint) in checkpoints.iter() {
+        let current = state.snapshot
+            .as_ref()
+            .and_then(|s| s.iid.as_ref());
+
+        if current == Some(&checkpoint.iid) || rapier.intersection_pair(entity, p).is_none() {
+            continue;
+        }
+
+        let level = match &state.snapshot {
+            Some(s) => s.save.level.clone(),
+            None => continue
+        };
+
+        info!("Reached checkpoint {} in {}", checkpoint.iid, level);
+
+        state.snapshot = Some(CheckpointSnapshot {
+            iid: Some(checkpoint.iid.clone()),
+            pos: checkpoint.spawn_pos,
+            save: SaveData::capture(&level, skills, coins, health, ammo),
+            room: memory.room(&level).cloned().unwrap_or_default()
+        });
+    }
+}
@@ -4,6 +4,25 @@ pub const TILE_SIZE: f32 = 8.0;
 pub const RENDERED_TILE_SIZE: f32 = 32.0;
 pub const SCALE_FACTOR: f32 = RENDERED_TILE_SIZE / TILE_SIZE;
 
+// Where a new run starts
+pub const FIRST_LEVEL: &str = "Level_0";
+
+// The EntryPoint_ID a level is entered at when nothing asks for another one,
+// every level needs an entry point with it
+pub const DEFAULT_ENTRY_POINT: i32 = 0;
+
+// IntGrid layers and their values, as named in the LDtk project
+pub const TILES_LAYER: &str = "Tiles";
+pub const SOLID_TILE_VALUE: i32 = 1;
+pub const ONE_WAY_TILE_VALUE: i32 = 2;
+pub const BREAKABLE_TILE_VALUE: i32 = 3;
+
+pub const SPECIAL_TILES_LAYER: &str = "SpecialTiles";
+pub const DOOR_TILE_VALUE: i32 = 1;
+pub const SPIKE_TILE_VALUE: i32 = 2;
+pub const PIT_TILE_VALUE: i32 = 3;
+pub const BRAMBLE_TILE_VALUE: i32 = 4;
+
 
 pub const SOLID_PLATFORM_GROUP_MASK: u32        = 0b00000001;
 pub const ONE_WAY_PLATFORM_GROUP_MASK: u32      = 0b00000010;
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{EntityInstance, GridCoords};
-use crate::level::consts::{RENDERED_TILE_SIZE, TILE_SIZE};
+use crate::level::consts::{RENDERED_TILE_SIZE, SCALE_FACTOR, TILE_SIZE};
 use crate::pathfind::Region;
 
 
@@ -24,11 +24,12 @@ pub fn grid_coords_to_region(
     region
 }
 
+// y goes up in the world, so the center is below the top left corner
 pub fn top_left_to_center(
     top_left: Vec2,
     extents: Vec2
 ) -> Vec2 {
-    top_left + extents / 2.0
+    top_left + Vec2::new(extents.x, -extents.y) / 2.0
 }
 
 pub fn grid_coord_to_translation(
@@ -41,6 +42,17 @@ pub fn grid_coord_to_translation(
     ) * RENDERED_TILE_SIZE
 }
 
+// For an entity's `px`, which is where its pivot is in the level's pixels
+pub fn px_to_translation(
+    px: IVec2,
+    world_grid_size: IVec2,
+) -> Vec2 {
+    Vec2::new(
+        px.x as f32,
+        (world_grid_size.y as f32 * TILE_SIZE) - px.y as f32
+    ) * SCALE_FACTOR
+}
+
 pub fn world_to_grid(
     world: Vec2,
     lvl_grid_size: Vec2,
@@ -1,11 +1,26 @@
+use std::collections::{HashMap, HashSet};
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
 use crate::anim::{AnimationChangeEvent, AnimationPlugin, Animator};
 use crate::anim::map::AnimationMap;
-use crate::assets::LevelAssets;
+use crate::assets::{LevelAssets, UiAssets};
 use crate::enemies::Enemy;
+use crate::entity_states::Die;
+use crate::interact::Interact;
+use crate::level::{coord, LevelInfo};
+use crate::level::consts::{DOOR_TILE_VALUE, SCALE_FACTOR, SPECIAL_TILES_LAYER, TILE_SIZE};
+use crate::level::memory::{current_level, LevelMemory, SpawnedFrom};
+use crate::level::util::LdtkFields;
+use crate::pathfind::grid::PathfindingGrid;
+use crate::player::Player;
 use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+// The group of every door tile no DoorGroup covers, those open once every
+// enemy in the level is dead
+pub const LEVEL_DOOR_GROUP: &str = "level";
 
 #[derive(Default, Component)]
 pub struct DoorTileSpawnMarker;
@@ -15,35 +30,263 @@ pub struct DoorTileBundle {
     marker: DoorTileSpawnMarker
 }
 
-#[derive(Component, Default, Debug, Copy, Clone)]
+#[derive(Component, Default)]
+pub struct DoorGroupMarker;
+
+#[derive(Bundle, LdtkEntity)]
+pub struct DoorGroupBundle {
+    marker: DoorGroupMarker,
+    #[from_entity_instance]
+    instance: EntityInstance
+}
+
+#[derive(Component, Default)]
+pub struct DoorTriggerMarker;
+
+#[derive(Bundle, LdtkEntity)]
+pub struct DoorTriggerBundle {
+    marker: DoorTriggerMarker,
+    #[from_entity_instance]
+    instance: EntityInstance
+}
+
+#[derive(Component, Debug, Copy, Clone)]
 pub struct DoorTile {
-    pub cleared: bool
+    pub group: Entity,
+    // in `PathfindingGrid` coordinates
+    pub cell: IVec2,
+    pub open: bool
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DoorTriggerKind {
+    // picked up by walking into it
+    Key,
+    // held down by standing on it, the doors close again when let go
+    Switch,
+    // pulled with Interact
+    Lever
+}
+
+impl DoorTriggerKind {
+    pub fn from_identifier(identifier: &str) -> Option<Self> {
+        match identifier {
+            "Key" => Some(DoorTriggerKind::Key),
+            "PressureSwitch" => Some(DoorTriggerKind::Switch),
+            "Lever" => Some(DoorTriggerKind::Lever),
+            _ => None
+        }
+    }
+
+    // The LDtk entity a door group with this condition points at
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            DoorTriggerKind::Key => "Key",
+            DoorTriggerKind::Switch => "PressureSwitch",
+            DoorTriggerKind::Lever => "Lever"
+        }
+    }
+
+    fn color(&self, active: bool) -> Color {
+        match (self, active) {
+            (DoorTriggerKind::Key, _) => Color::rgb(1.0, 0.9, 0.38),
+            (DoorTriggerKind::Switch, false) => Color::rgb(0.55, 0.6, 0.7),
+            (DoorTriggerKind::Switch, true) => Color::rgb(0.35, 0.4, 0.45),
+            (DoorTriggerKind::Lever, false) => Color::rgb(0.72, 0.44, 0.31),
+            (DoorTriggerKind::Lever, true) => Color::rgb(0.39, 0.78, 0.3)
+        }
+    }
+}
+
+// The DoorGroup "Condition" enum, None for "Enemies"
+pub fn parse_door_condition(name: &str) -> Result<Option<DoorTriggerKind>, String> {
+    match name {
+        "Enemies" => Ok(None),
+        "Key" => Ok(Some(DoorTriggerKind::Key)),
+        "Switch" => Ok(Some(DoorTriggerKind::Switch)),
+        "Lever" => Ok(Some(DoorTriggerKind::Lever)),
+        _ => Err(format!("Unknown door condition {:?}", name))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DoorCondition {
+    // every enemy from these spawnpoints, by iid, is dead. None for every
+    // enemy in the level
+    Enemies(Option<HashSet<String>>),
+    // the key, switch or lever with this iid
+    Trigger(DoorTriggerKind, String)
+}
+
+impl DoorCondition {
+    // Whether the doors stay open once the condition has been met
+    pub fn is_permanent(&self) -> bool {
+        !matches!(self, DoorCondition::Trigger(DoorTriggerKind::Switch, _))
+    }
+}
+
+// The doors a DoorGroup entity covers, opening and closing together
+#[derive(Component, Clone, Debug)]
+pub struct DoorGroup {
+    // the DoorGroup's iid, or LEVEL_DOOR_GROUP
+    pub id: String,
+    pub condition: DoorCondition,
+    pub open: bool
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct DoorTrigger {
+    pub iid: String,
+    pub kind: DoorTriggerKind,
+    // picked up, held down or pulled
+    pub active: bool
 }
 
 pub fn register_doors(app: &mut App) {
     app
-        .register_ldtk_int_cell_for_layer::<DoorTileBundle>("SpecialTiles", 1)
+        .register_ldtk_int_cell_for_layer::<DoorTileBundle>(SPECIAL_TILES_LAYER, DOOR_TILE_VALUE)
+        .register_ldtk_entity::<DoorGroupBundle>("DoorGroup")
+        .register_ldtk_entity::<DoorTriggerBundle>("Key")
+        .register_ldtk_entity::<DoorTriggerBundle>("PressureSwitch")
+        .register_ldtk_entity::<DoorTriggerBundle>("Lever")
 
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Doors.on_update()
                 .with_system(spawn_doors)
+                .with_system(spawn_door_triggers.after(spawn_doors))
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
-                .with_system(clear_doors_on_enemy_deaths)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .with_system(pick_up_keys)
+                .with_system(press_switches)
+                .with_system(pull_levers)
+                .with_system(update_trigger_sprites)
+                .with_system(update_door_groups)
+                .with_system(open_and_close_doors)
         );
 }
 
+// Whether a cell, counted from the top like `EntityInstance::grid`, is inside
+// a rectangle entity
+fn covers(region: &EntityInstance, cell: IVec2) -> bool {
+    let tl = region.grid;
+    let br = tl + IVec2::new(region.width, region.height) / TILE_SIZE as i32;
+
+    cell.x >= tl.x && cell.y >= tl.y && cell.x < br.x && cell.y < br.y
+}
+
+fn read_door_group(
+    inst: &EntityInstance,
+    instances: &HashMap<&str, &EntityInstance>
+) -> Result<DoorCondition, String> {
+    let kind = parse_door_condition(&inst.field_enum("Condition")?)
+        .map_err(|e| format!("{}: {}", inst.describe(), e))?;
+
+    let trigger = inst.field_opt_ent_ref("Trigger")?;
+
+    match (kind, trigger) {
+        (None, None) => Ok(DoorCondition::Enemies(None)),
+
+        // the trigger is the region the enemies were placed in
+        (None, Some(e_ref)) => {
+            let region = instances
+                .get(e_ref.entity_iid.as_str())
+                .ok_or_else(|| format!("{}: region {} isn't in this level", inst.describe(), e_ref.entity_iid))?;
+
+            let spawnpoints = instances
+                .values()
+                .filter(|i| i.identifier == "EnemySpawnpoint" && covers(region, i.grid))
+                .map(|i| i.iid.clone())
+                .collect();
+
+            Ok(DoorCondition::Enemies(Some(spawnpoints)))
+        }
+
+        (Some(kind), Some(e_ref)) => Ok(DoorCondition::Trigger(kind, e_ref.entity_iid)),
+
+        (Some(kind), None) => Err(format!(
+            "{}: a {:?} door group needs a Trigger pointing at a {}",
+            inst.describe(), kind, kind.identifier()
+        ))
+    }
+}
+
+// Returns the group and whether it's already open
+fn spawn_door_group(
+    commands: &mut Commands,
+    memory: &LevelMemory,
+    level: &str,
+    id: &str,
+    condition: DoorCondition
+) -> (Entity, bool) {
+    let open = memory.doors_open(level, id);
+    let e = commands.spawn(DoorGroup { id: id.to_string(), condition, open }).id();
+
+    (e, open)
+}
+
 fn spawn_doors(
     mut commands: Commands,
-    q: Query<Entity, Added<DoorTileSpawnMarker>>,
-    assets: Res<LevelAssets>
+    tiles: Query<(Entity, &GridCoords), Added<DoorTileSpawnMarker>>,
+    groups: Query<&EntityInstance, Added<DoorGroupMarker>>,
+    instances: Query<&EntityInstance, Added<EntityInstance>>,
+    assets: Res<LevelAssets>,
+    lvl_info: Res<LevelInfo>,
+    sel: Res<LevelSelection>,
+    memory: Res<LevelMemory>,
+    mut grid: ResMut<PathfindingGrid>
 ) {
-    for e in q.iter() {
+    if tiles.is_empty() {
+        return;
+    }
+
+    let level = current_level(&sel).unwrap_or_default();
+
+    let instances: HashMap<&str, &EntityInstance> = instances
+        .iter()
+        .map(|inst| (inst.iid.as_str(), inst))
+        .collect();
+
+    let mut spawned_groups = vec![];
+
+    for inst in groups.iter() {
+        match read_door_group(inst, &instances) {
+            Ok(condition) => spawned_groups.push((
+                inst,
+                spawn_door_group(&mut commands, &memory, level, &inst.iid, condition)
+            )),
+            Err(e) => error!("Skipping door group: {}", e)
+        }
+    }
+
+    let mut level_group = None;
+
+    for (e, coords) in tiles.iter() {
+        let cell = IVec2::new(coords.x, lvl_info.grid_size.y as i32 - coords.y - 1);
+
+        let group = spawned_groups
+            .iter()
+            .find(|(inst, _)| covers(inst, cell))
+            .map(|(_, group)| *group);
+
+        let (group, open) = match group {
+            Some(group) => group,
+            None => *level_group.get_or_insert_with(|| spawn_door_group(
+                &mut commands, &memory, level, LEVEL_DOOR_GROUP, DoorCondition::Enemies(None)
+            ))
+        };
+
+        // the doors stay open once they've been opened for good
+        if open {
+            continue;
+        }
+
+        grid.solids.insert(cell);
+
         commands.entity(e).with_children(|parent| {
             parent.spawn((
-                DoorTile { cleared: false },
-                Collider::cuboid(4., 4.),
+                DoorTile { group, cell, open: false },
+                door_collider(),
                 Animator::new(assets.anims["SOLID"].clone()),
                 assets.anims.clone(),
 
@@ -61,27 +304,222 @@ fn spawn_doors(
     }
 }
 
-fn clear_doors_on_enemy_deaths(
+fn spawn_door_triggers(
     mut commands: Commands,
-    enemies: Query<&Enemy>,
-    mut doors: Query<(Entity, &mut DoorTile, &Animator)>,
-    mut ev: EventWriter<AnimationChangeEvent>,
-    assets: Res<LevelAssets>
+    triggers: Query<&EntityInstance, Added<DoorTriggerMarker>>,
+    groups: Query<&EntityInstance, Added<DoorGroupMarker>>,
+    ui_assets: Res<UiAssets>,
+    lvl_info: Res<LevelInfo>,
+    sel: Res<LevelSelection>,
+    memory: Res<LevelMemory>
 ) {
-    if !enemies.is_empty() {
-        return;
-    }
+    let level = current_level(&sel).unwrap_or_default();
 
-    for (entity, mut door, animator) in doors.iter_mut() {
-        door.cleared = true;
+    for inst in triggers.iter() {
+        let kind = match DoorTriggerKind::from_identifier(&inst.identifier) {
+            Some(kind) => kind,
+            None => continue
+        };
 
-        ev.send(AnimationChangeEvent {
-            e: entity,
-            new_anim: assets.anims["DISINTEGRATE"].clone()
+        // keys that have been used don't come back, levers stay pulled
+        let active = groups.iter().any(|group| {
+            let points_here = group.field_opt_ent_ref("Trigger")
+                .ok()
+                .flatten()
+                .map_or(false, |e_ref| e_ref.entity_iid == inst.iid);
+
+            points_here && memory.doors_open(level, &group.iid)
         });
 
-        if animator.anim.name == "DISINTEGRATE" && animator.total_looped == 1 {
-            commands.entity(entity).despawn_recursive();
+        let pos = coord::px_to_translation(inst.px, lvl_info.grid_size.as_ivec2());
+        let size = Vec2::new(inst.width as f32, inst.height as f32) * SCALE_FACTOR;
+
+        let mut trigger = commands.spawn((
+            DoorTrigger { iid: inst.iid.clone(), kind, active },
+            Collider::cuboid(size.x / 2.0, size.y / 2.0),
+            Sensor,
+            // without a body the sensor counts as fixed, and the player and
+            // enemies are kinematic
+            ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+
+            SpriteBundle {
+                sprite: Sprite {
+                    color: kind.color(active),
+                    custom_size: Some(size),
+                    ..default()
+                },
+                visibility: Visibility {
+                    is_visible: !(kind == DoorTriggerKind::Key && active)
+                },
+                transform: Transform::from_translation(pos.extend(5.0)),
+                ..default()
+            }
+        ));
+
+        if kind == DoorTriggerKind::Lever {
+            trigger.insert(Interact {
+                content: Text::from_section("Pull [E]", ui_assets.text_style.clone()),
+                max_dist: 128.0,
+                text_offset: Vec2::new(0.0, size.y),
+                ..default()
+            });
+        }
+    }
+}
+
+fn pick_up_keys(
+    mut keys: Query<(Entity, &mut DoorTrigger)>,
+    player: Query<Entity, (With<Player>, Without<Die>)>,
+    rapier: Res<RapierContext>
+) {
+    let p = match player.get_single() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    for (e, mut key) in keys.iter_mut() {
+        if key.kind == DoorTriggerKind::Key && !key.active && rapier.intersection_pair(e, p) == Some(true) {
+            info!("Picked up key {}", key.iid);
+            key.active = true;
+        }
+    }
+}
+
+// The player and enemies are both heavy enough to hold a switch down
+fn press_switches(
+    mut switches: Query<(Entity, &mut DoorTrigger)>,
+    weights: Query<(), (Or<(With<Player>, With<Enemy>)>, Without<Die>)>,
+    rapier: Res<RapierContext>
+) {
+    for (e, mut switch) in switches.iter_mut() {
+        if switch.kind != DoorTriggerKind::Switch {
+            continue;
+        }
+
+        let held = rapier
+            .intersections_with(e)
+            .any(|(a, b, hit)| hit && weights.contains(if a == e { b } else { a }));
+
+        if switch.active != held {
+            switch.active = held;
+        }
+    }
+}
+
+fn pull_levers(mut levers: Query<(&mut DoorTrigger, &Interact)>) {
+    for (mut lever, interact) in levers.iter_mut() {
+        if lever.kind == DoorTriggerKind::Lever && !lever.active && interact.interacted_with() {
+            info!("Pulled lever {}", lever.iid);
+            lever.active = true;
         }
     }
-}
\ No newline at end of file
+}
+
+fn update_trigger_sprites(
+    mut q: Query<(&DoorTrigger, &mut Sprite, &mut Visibility), Changed<DoorTrigger>>
+) {
+    for (trigger, mut sprite, mut visibility) in q.iter_mut() {
+        sprite.color = trigger.kind.color(trigger.active);
+
+        if trigger.kind == DoorTriggerKind::Key {
+            visibility.is_visible = !trigger.active;
+        }
+    }
+}
+
+fn update_door_groups(
+    mut groups: Query<&mut DoorGroup>,
+    triggers: Query<&DoorTrigger>,
+    enemies: Query<Option<&SpawnedFrom>, (With<Enemy>, Without<Die>)>,
+    sel: Res<LevelSelection>,
+    mut memory: ResMut<LevelMemory>
+) {
+    for mut group in groups.iter_mut() {
+        let permanent = group.condition.is_permanent();
+
+        if group.open && permanent {
+            continue;
+        }
+
+        let met = match &group.condition {
+            DoorCondition::Enemies(None) => enemies.is_empty(),
+
+            DoorCondition::Enemies(Some(spawnpoints)) => !enemies
+                .iter()
+                .any(|spawned| spawned.map_or(false, |s| spawnpoints.contains(&s.0))),
+
+            DoorCondition::Trigger(_, iid) => triggers
+                .iter()
+                .any(|trigger| trigger.iid == *iid && trigger.active)
+        };
+
+        if group.open == met {
+            continue;
+        }
+
+        group.open = met;
+
+        if met && permanent {
+            if let Some(level) = current_level(&sel) {
+                memory.room_mut(level).open_doors.insert(group.id.clone());
+            }
+        }
+    }
+}
+
+// Open doors have no collider at all, closing one gives it this back
+fn door_collider() -> Collider {
+    Collider::cuboid(4., 4.)
+}
+
+fn open_and_close_doors(
+    mut commands: Commands,
+    groups: Query<&DoorGroup>,
+    mut doors: Query<(Entity, &mut DoorTile, &Animator, &mut Visibility)>,
+    mut ev: EventWriter<AnimationChangeEvent>,
+    assets: Res<LevelAssets>,
+    mut grid: ResMut<PathfindingGrid>
+) {
+    for (entity, mut door, animator, mut visibility) in doors.iter_mut() {
+        let should_open = groups.get(door.group).map_or(false, |group| group.open);
+
+        match (should_open, door.open) {
+            // the door only lets anyone through once it's done crumbling
+            (true, false) => {
+                if animator.anim.name != "DISINTEGRATE" {
+                    ev.send(AnimationChangeEvent {
+                        e: entity,
+                        new_anim: assets.anims["DISINTEGRATE"].clone()
+                    });
+                } else if animator.total_looped >= 1 {
+                    door.open = true;
+                    visibility.is_visible = false;
+                    commands.entity(entity).remove::<Collider>();
+                    grid.solids.remove(&door.cell);
+                }
+            }
+
+            (false, true) => {
+                door.open = false;
+                visibility.is_visible = true;
+                commands.entity(entity).insert(door_collider());
+                grid.solids.insert(door.cell);
+
+                ev.send(AnimationChangeEvent {
+                    e: entity,
+                    new_anim: assets.anims["SOLID"].clone()
+                });
+            }
+
+            // let go of before it was done opening
+            (false, false) if animator.anim.name != "SOLID" => {
+                ev.send(AnimationChangeEvent {
+                    e: entity,
+                    new_anim: assets.anims["SOLID"].clone()
+                });
+            }
+
+            _ => {}
+        }
+    }
+}
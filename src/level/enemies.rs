@@ -1,14 +1,16 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 
-use crate::{
-    state::GameState,
-    level::{util, coord},
-};
+use crate::level::coord;
 
 use std::collections::HashMap;
+use crate::enemies::registry::EnemyRegistry;
 use crate::enemies::spawner::{EnemyDifficulty, EnemyLocation, EnemySpawnEvent, EnemyType};
 use crate::level::LevelInfo;
+use crate::level::memory::{current_level, LevelMemory};
+use crate::level::util::LdtkFields;
+use crate::pathfind::Region;
+use crate::level::LevelSystems;
 
 #[derive(Component, Default)]
 pub struct EnemySpawnpointMarker;
@@ -30,21 +32,64 @@ pub struct PatrolRegionBundle {
     instance: EntityInstance
 }
 
+pub fn parse_difficulty(name: &str) -> Result<EnemyDifficulty, String> {
+    match name {
+        "Mellow" => Ok(EnemyDifficulty::Mellow),
+        "Easy" => Ok(EnemyDifficulty::Easy),
+        "Medium" => Ok(EnemyDifficulty::Medium),
+        "Hard" => Ok(EnemyDifficulty::Hard),
+        _ => Err(format!("Unknown enemy difficulty {:?}", name))
+    }
+}
+
+// What an enemy placed in the level drops when it dies
+pub fn coins_for_difficulty(difficulty: EnemyDifficulty) -> i32 {
+    match difficulty {
+        EnemyDifficulty::Mellow => 5,
+        EnemyDifficulty::Easy => 10,
+        EnemyDifficulty::Medium => 15,
+        EnemyDifficulty::Hard => 20,
+    }
+}
+
 pub fn register_enemy_spawnpoints(app: &mut App) {
     app
         .register_ldtk_entity::<PatrolRegionBundle>("PatrolRegion")
         .register_ldtk_entity::<EnemySpawnpointBundle>("EnemySpawnpoint")
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Enemies.on_update()
                 .with_system(spawn_enemies)
         );
 }
 
+fn read_spawnpoint(
+    inst: &EntityInstance,
+    patrol_regions: &HashMap<String, Region>,
+    registry: &EnemyRegistry
+) -> Result<(Region, EnemyType, EnemyDifficulty), String> {
+    let e_ref = inst.field_ent_ref("PatrolRegion")?;
+    let patrol_region = *patrol_regions
+        .get(&e_ref.entity_iid)
+        .ok_or_else(|| format!("{}: patrol region {} isn't in this level", inst.describe(), e_ref.entity_iid))?;
+
+    let ty = registry
+        .lookup(&inst.field_enum("EnemyType")?)
+        .map_err(|e| format!("{}: {}", inst.describe(), e))?;
+
+    let difficulty = parse_difficulty(&inst.field_enum("Difficulty")?)
+        .map_err(|e| format!("{}: {}", inst.describe(), e))?;
+
+    Ok((patrol_region, ty, difficulty))
+}
+
 fn spawn_enemies(
     // mut commands: Commands,
     enemies: Query<&EntityInstance, Added<EnemySpawnpointMarker>>,
     patrol_regions: Query<&EntityInstance, Added<PatrolRegionMarker>>,
     lvl_info: Res<LevelInfo>,
+    registry: Res<EnemyRegistry>,
+    sel: Res<LevelSelection>,
+    memory: Res<LevelMemory>,
 
     mut spawns: EventWriter<EnemySpawnEvent>
 ) {
@@ -57,54 +102,26 @@ fn spawn_enemies(
         );
     }
 
-    for inst in enemies.iter() {
-        let e_ref = util::val_expect_ent_ref(&inst.field_instances[1].value).unwrap();
-        let patrol_region = patrol_regions_map[&e_ref.entity_iid];
-
-
-
-        // let n_coins = util::val_expect_i32(&inst.field_instances[2].value).unwrap();
+    let level = current_level(&sel).unwrap_or_default();
 
-        let enemy_ty = {
-            let enemy_type = match &inst.field_instances[0].value {
-                FieldValue::Enum(Some(name)) => name.clone(),
-                _ => panic!()
-            };
+    for inst in enemies.iter() {
+        if memory.is_killed(level, &inst.iid) {
+            continue;
+        }
 
-            match enemy_type.as_str() {
-                "Flower" => EnemyType::Flower,
-                "Pumpkin" => EnemyType::Pumpkin,
-                "Dandelion" => EnemyType::Dandelion,
-                "Tumbleweed" => EnemyType::Tumbleweed,
-                _ => panic!()
-            }
-        };
+        let spawnpoint = read_spawnpoint(inst, &patrol_regions_map, &registry);
 
-        let enemy_difficulty = {
-            let d = match &inst.field_instances[2].value {
-                FieldValue::Enum(Some(name)) => name.clone(),
-                _ => panic!()
-            };
-
-            match d.as_str() {
-                "Mellow" => EnemyDifficulty::Mellow,
-                "Easy" => EnemyDifficulty::Easy,
-                "Medium" => EnemyDifficulty::Medium,
-                "Hard" => EnemyDifficulty::Hard,
-                _ => panic!()
+        let (patrol_region, enemy_ty, enemy_difficulty) = match spawnpoint {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Skipping enemy spawnpoint: {}", e);
+                continue;
             }
         };
 
-        let n_coins = match enemy_difficulty {
-            EnemyDifficulty::Mellow => 5,
-            EnemyDifficulty::Easy => 10,
-            EnemyDifficulty::Medium => 15,
-            EnemyDifficulty::Hard => 20,
-        };
-        
         let ev = EnemySpawnEvent {
             ty: enemy_ty,
-            coins: n_coins,
+            coins: coins_for_difficulty(enemy_difficulty),
             difficulty: enemy_difficulty,
             location: EnemyLocation {
                 pos: coord::grid_coord_to_translation(
@@ -113,6 +130,7 @@ fn spawn_enemies(
                 patrol_region
             },
             rand_range: 0.9..1.1,
+            spawnpoint: Some(inst.iid.clone()),
             extra_components: None
         };
 
@@ -4,15 +4,17 @@ use bevy_rapier2d::prelude::*;
 
 use crate::{
     level::{
-        util,
+        util::LdtkFields,
         LevelRegion, FinishedTransitioning,
-        transition::{LevelTransition, TransitionEffect}
+        transition::{LevelTransition, TransitionEffect, TransitionSettings}
     },
     state::GameState,
     player::Player,
 };
 use crate::level::{coord, LevelInfo};
 use crate::level::consts::SCALE_FACTOR;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
 
 #[derive(Component, Default)]
 pub struct ExitTileMarker;
@@ -27,29 +29,49 @@ pub struct ExitTileBundle {
 #[derive(Component)]
 pub struct LevelExit {
     pub link: String,
+    pub entry_point: i32,
+    // None for the one set in config.toml
+    pub transition: Option<TransitionEffect>
 }
 
 pub fn register_exit_entity(app: &mut App) {
     app
         .register_ldtk_entity::<ExitTileBundle>("Exit")
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Exits.on_update()
                 .with_system(add_exit_entities)
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_system(on_player_collide_with_exit)
                 .with_system(on_player_out_of_bounds)
         );
 }
 
+fn read_exit(inst: &EntityInstance) -> Result<LevelExit, String> {
+    let transition = TransitionEffect::from_ldtk(&inst.field_enum("Transition")?)
+        .map_err(|e| format!("{}: {}", inst.describe(), e))?;
+
+    Ok(LevelExit {
+        link: inst.field_string("NextLevel")?,
+        entry_point: inst.field_i32("EntryPoint")?,
+        transition
+    })
+}
+
 fn add_exit_entities(
     mut commands: Commands,
     q: Query<&EntityInstance, Added<ExitTileMarker>>,
     lvl_info: Res<LevelInfo>
 ) {
     for inst in q.iter() {
-        let link = util::val_expect_string(&inst.field_instances[0].value).unwrap();
+        let exit = match read_exit(inst) {
+            Ok(exit) => exit,
+            Err(e) => {
+                error!("Skipping exit: {}", e);
+                continue;
+            }
+        };
 
         let tl = coord::grid_coord_to_translation(inst.grid, lvl_info.grid_size.as_ivec2());
 
@@ -63,9 +85,7 @@ fn add_exit_entities(
                     .with_scale(Vec3::new(SCALE_FACTOR, SCALE_FACTOR, 1.0))
             ),
 
-            LevelExit {
-                link
-            }
+            exit
         ));
     }
 }
@@ -74,6 +94,7 @@ fn on_player_collide_with_exit(
     player_q: Query<Entity, With<Player>>,
     exit_q: Query<(Entity, &LevelExit)>,
     rapier: Res<RapierContext>,
+    settings: Res<TransitionSettings>,
     mut sel: ResMut<LevelTransition>
 ) {
     if player_q.is_empty() {
@@ -85,7 +106,9 @@ fn on_player_collide_with_exit(
         if rapier.intersection_pair(exit, p).is_some() {
             *sel = LevelTransition {
                 next: info.link.clone(),
-                transition_effect: TransitionEffect::default()
+                entry_point: info.entry_point,
+                transition_effect: info.transition.unwrap_or(settings.effect),
+                reload: false
             };
         }
     }
@@ -0,0 +1,235 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use crate::combat::{AttackSystems, CombatEvent, handle_hits, Health, HurtAbility};
+use crate::entity_states::Die;
+use crate::level::consts::{BRAMBLE_TILE_VALUE, PIT_TILE_VALUE, RENDERED_TILE_SIZE, SPECIAL_TILES_LAYER, SPIKE_TILE_VALUE};
+use crate::level::merge::{cells_by_layer, merge_cells};
+use crate::player::Player;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HazardKind {
+    Spikes,
+    // bottomless, sends the player back to the last safe ground
+    Pit,
+    Brambles
+}
+
+impl HazardKind {
+    pub const ALL: [HazardKind; 3] = [HazardKind::Spikes, HazardKind::Pit, HazardKind::Brambles];
+
+    pub fn from_value(value: i32) -> Option<Self> {
+        match value {
+            SPIKE_TILE_VALUE => Some(HazardKind::Spikes),
+            PIT_TILE_VALUE => Some(HazardKind::Pit),
+            BRAMBLE_TILE_VALUE => Some(HazardKind::Brambles),
+            _ => None
+        }
+    }
+
+    pub fn damage(&self) -> i32 {
+        match self {
+            HazardKind::Spikes => 20,
+            HazardKind::Pit => 15,
+            HazardKind::Brambles => 10
+        }
+    }
+}
+
+#[derive(Default, Component)]
+pub struct HazardTileSpawnMarker;
+
+#[derive(Bundle, LdtkIntCell)]
+pub struct HazardTileBundle {
+    marker: HazardTileSpawnMarker,
+    #[from_int_grid_cell]
+    cell: IntGridCell
+}
+
+#[derive(Component, Copy, Clone, Debug)]
+pub struct HazardTile {
+    pub kind: HazardKind,
+    // in world coordinates. Rapier writes the sensor's position back into its
+    // `Transform` without the level's scale, so that can't be trusted
+    pub center: Vec2
+}
+
+// Where the player last stood on solid ground without touching a hazard,
+// forgotten on every level transition
+#[derive(Resource, Default, Debug)]
+pub struct SafeGround {
+    pub pos: Option<Vec2>
+}
+
+pub fn register_hazards(app: &mut App) {
+    app
+        .register_ldtk_int_cell_for_layer::<HazardTileBundle>(SPECIAL_TILES_LAYER, SPIKE_TILE_VALUE)
+        .register_ldtk_int_cell_for_layer::<HazardTileBundle>(SPECIAL_TILES_LAYER, PIT_TILE_VALUE)
+        .register_ldtk_int_cell_for_layer::<HazardTileBundle>(SPECIAL_TILES_LAYER, BRAMBLE_TILE_VALUE)
+        .init_resource::<SafeGround>()
+        .add_system_set(
+            LevelSystems::Hazards.on_enter()
+                .with_system(reset_safe_ground)
+        )
+        .add_system_set(
+            LevelSystems::Hazards.on_update()
+                .with_system(add_hazard_tiles)
+        )
+        .add_system_set(
+            UpdateStage::Combat.on_update(GameState::Gameplay)
+                .with_system(
+                    hazard_damage
+                        .label(AttackSystems::Hazards)
+                        .after(AttackSystems::SporeClouds)
+                        .before(handle_hits)
+                )
+                .with_system(return_from_pits.after(hazard_damage))
+                .with_system(track_safe_ground.after(return_from_pits))
+        );
+}
+
+fn add_hazard_tiles(
+    mut commands: Commands,
+    cells: Query<(&Parent, &GridCoords, &IntGridCell), Added<HazardTileSpawnMarker>>
+) {
+    if cells.is_empty() {
+        return;
+    }
+
+    for kind in HazardKind::ALL {
+        let of_kind = cells
+            .iter()
+            .filter(|(_, _, cell)| HazardKind::from_value(cell.value) == Some(kind))
+            .map(|(parent, coords, _)| (parent, coords));
+
+        for (layer, cells) in cells_by_layer(of_kind) {
+            let rects = merge_cells(&cells, i32::MAX);
+
+            commands.entity(layer).with_children(|parent| {
+                for rect in rects {
+                    // `GridCoords` count up from the bottom left, like the world
+                    let world_center = (rect.min + rect.max + IVec2::ONE).as_vec2() / 2.0 * RENDERED_TILE_SIZE;
+
+                    parent.spawn(hazard_tile_bundle(kind, rect.center(), rect.half_extents(), world_center));
+                }
+            });
+        }
+    }
+}
+
+fn hazard_tile_bundle(kind: HazardKind, center: Vec2, half_extents: Vec2, world_center: Vec2) -> impl Bundle {
+    (
+        HazardTile { kind, center: world_center },
+        Sensor,
+        RigidBody::Fixed,
+        // the player and enemies are kinematic, which Rapier doesn't check
+        // fixed bodies against unless asked to
+        ActiveCollisionTypes::default() | ActiveCollisionTypes::KINEMATIC_STATIC,
+        Collider::cuboid(half_extents.x, half_extents.y),
+        TransformBundle::from_transform(Transform::from_xyz(center.x, center.y, 0.0))
+    )
+}
+
+fn hazard_knockback(kind: HazardKind, hazard_pos: Vec2, target_pos: Vec2) -> Vec2 {
+    match kind {
+        HazardKind::Spikes => Vec2::new(0.0, 1.5),
+        HazardKind::Pit => Vec2::ZERO,
+        // out the nearest side of the patch
+        HazardKind::Brambles => Vec2::new((target_pos.x - hazard_pos.x).signum(), 0.5)
+    }
+}
+
+fn hazard_damage(
+    hazards: Query<(Entity, &HazardTile)>,
+    targets: Query<(&GlobalTransform, &Health), (With<HurtAbility>, Without<Die>)>,
+    players: Query<(), With<Player>>,
+    rapier: Res<RapierContext>,
+    mut hits: EventWriter<CombatEvent>
+) {
+    for (hazard, tile) in hazards.iter() {
+        for (a, b, intersecting) in rapier.intersections_with(hazard) {
+            let target = if a == hazard { b } else { a };
+
+            let (target_tf, health) = match targets.get(target) {
+                Ok(t) if intersecting => t,
+                _ => continue
+            };
+
+            // enemies have no safe ground to go back to
+            let damage = if tile.kind == HazardKind::Pit && !players.contains(target) {
+                health.hp
+            } else {
+                tile.kind.damage()
+            };
+
+            hits.send(CombatEvent {
+                target,
+                damage,
+                kb: hazard_knockback(
+                    tile.kind,
+                    tile.center,
+                    target_tf.translation().truncate()
+                )
+            });
+        }
+    }
+}
+
+fn touching_hazard(
+    rapier: &RapierContext,
+    player: Entity,
+    hazards: &Query<(Entity, &HazardTile)>,
+    kind: Option<HazardKind>
+) -> bool {
+    hazards.iter().any(|(hazard, tile)| {
+        kind.map_or(true, |k| k == tile.kind) && rapier.intersection_pair(hazard, player) == Some(true)
+    })
+}
+
+fn return_from_pits(
+    mut player: Query<(Entity, &mut Transform, &mut Player), Without<Die>>,
+    hazards: Query<(Entity, &HazardTile)>,
+    safe: Res<SafeGround>,
+    rapier: Res<RapierContext>
+) {
+    let (p, mut tf, mut player) = match player.get_single_mut() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    let pos = match safe.pos {
+        Some(pos) => pos,
+        None => return
+    };
+
+    if touching_hazard(&rapier, p, &hazards, Some(HazardKind::Pit)) {
+        info!("Fell into a pit, back to {:?}", pos);
+
+        tf.translation.x = pos.x;
+        tf.translation.y = pos.y;
+        player.vel = Vec2::ZERO;
+    }
+}
+
+fn track_safe_ground(
+    player: Query<(Entity, &Transform, &Player), Without<Die>>,
+    hazards: Query<(Entity, &HazardTile)>,
+    mut safe: ResMut<SafeGround>,
+    rapier: Res<RapierContext>
+) {
+    let (p, tf, player) = match player.get_single() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    if player.grounded && !touching_hazard(&rapier, p, &hazards, None) {
+        safe.pos = Some(tf.translation.truncate());
+    }
+}
+
+fn reset_safe_ground(mut safe: ResMut<SafeGround>) {
+    safe.pos = None;
+}
@@ -0,0 +1,83 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use crate::entity_states::Die;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+
+// Which LDtk spawnpoint an enemy came from, by iid
+#[derive(Component, Clone, Debug)]
+pub struct SpawnedFrom(pub String);
+
+// What's been cleared out of a level this run
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RoomMemory {
+    // iids of the spawnpoints whose enemies are dead
+    pub killed: HashSet<String>,
+    // door groups that have opened for good, see `door::DoorGroup::id`
+    pub open_doors: HashSet<String>
+}
+
+// Remembers every level the player has been through, so walking back into
+// one doesn't bring its enemies and doors back
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LevelMemory {
+    rooms: HashMap<String, RoomMemory>
+}
+
+impl LevelMemory {
+    pub fn room(&self, level: &str) -> Option<&RoomMemory> {
+        self.rooms.get(level)
+    }
+
+    pub fn room_mut(&mut self, level: &str) -> &mut RoomMemory {
+        self.rooms.entry(level.to_string()).or_default()
+    }
+
+    pub fn is_killed(&self, level: &str, iid: &str) -> bool {
+        self.room(level).map_or(false, |room| room.killed.contains(iid))
+    }
+
+    pub fn doors_open(&self, level: &str, group: &str) -> bool {
+        self.room(level).map_or(false, |room| room.open_doors.contains(group))
+    }
+}
+
+pub fn current_level(sel: &LevelSelection) -> Option<&str> {
+    match sel {
+        LevelSelection::Identifier(id) => Some(id.as_str()),
+        _ => None
+    }
+}
+
+pub fn register_level_memory(app: &mut App) {
+    app
+        .init_resource::<LevelMemory>()
+        .add_system_set(
+            SystemSet::on_enter(GameState::MainMenu)
+                .with_system(reset_level_memory)
+        )
+        .add_system_set(
+            UpdateStage::Combat.on_update(GameState::Gameplay)
+                .with_system(remember_kills)
+        );
+}
+
+fn reset_level_memory(mut memory: ResMut<LevelMemory>) {
+    *memory = LevelMemory::default();
+}
+
+fn remember_kills(
+    dead: Query<&SpawnedFrom, Added<Die>>,
+    sel: Res<LevelSelection>,
+    mut memory: ResMut<LevelMemory>
+) {
+    let level = match current_level(&sel) {
+        Some(level) => level,
+        None => return
+    };
+
+    for spawned in dead.iter() {
+        memory.room_mut(level).killed.insert(spawned.0.clone());
+    }
+}
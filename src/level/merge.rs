@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::GridCoords;
+use crate::level::consts::TILE_SIZE;
+
+// A rectangle of IntGrid cells, both corners included
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CellRect {
+    pub min: IVec2,
+    pub max: IVec2
+}
+
+impl CellRect {
+    pub fn size(&self) -> IVec2 {
+        self.max - self.min + IVec2::ONE
+    }
+
+    // Cell (0, 0) is centered on the layer's origin
+    pub fn center(&self) -> Vec2 {
+        (self.min + self.max).as_vec2() / 2.0 * TILE_SIZE
+    }
+
+    pub fn half_extents(&self) -> Vec2 {
+        self.size().as_vec2() * TILE_SIZE / 2.0
+    }
+}
+
+// Covers the cells with as few rectangles as it can, greedily: every
+// rectangle grows right as far as the row goes, then up for as long as the
+// rows above are filled across its whole width. Rectangles never cover a
+// cell that isn't in `cells`, so gaps between platforms stay gaps.
+pub fn merge_cells(cells: &[IVec2], max_height: i32) -> Vec<CellRect> {
+    let filled: HashSet<IVec2> = cells.iter().copied().collect();
+    let mut covered = HashSet::new();
+
+    let mut sorted: Vec<IVec2> = filled.iter().copied().collect();
+    sorted.sort_by_key(|c| (c.y, c.x));
+
+    let free = |c: IVec2, covered: &HashSet<IVec2>| filled.contains(&c) && !covered.contains(&c);
+
+    let mut rects = vec![];
+
+    for start in sorted {
+        if covered.contains(&start) {
+            continue;
+        }
+
+        let mut max = start;
+
+        while free(max + IVec2::X, &covered) {
+            max.x += 1;
+        }
+
+        while max.y - start.y + 1 < max_height
+            && (start.x..=max.x).all(|x| free(IVec2::new(x, max.y + 1), &covered)) {
+            max.y += 1;
+        }
+
+        for y in start.y..=max.y {
+            for x in start.x..=max.x {
+                covered.insert(IVec2::new(x, y));
+            }
+        }
+
+        rects.push(CellRect { min: start, max });
+    }
+
+    rects
+}
+
+// Groups freshly spawned IntGrid cells by the layer they belong to, layers
+// in the order their first cell comes in so they're spawned in that order
+pub fn cells_by_layer<'a>(cells: impl Iterator<Item = (&'a Parent, &'a GridCoords)>) -> Vec<(Entity, Vec<IVec2>)> {
+    let mut layers: Vec<(Entity, Vec<IVec2>)> = vec![];
+
+    for (parent, coords) in cells {
+        let cell = IVec2::new(coords.x, coords.y);
+
+        match layers.iter_mut().find(|(layer, _)| *layer == parent.get()) {
+            Some((_, layer_cells)) => layer_cells.push(cell),
+            None => layers.push((parent.get(), vec![cell]))
+        }
+    }
+
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(y: i32, xs: impl IntoIterator<Item = i32>) -> Vec<IVec2> {
+        xs.into_iter().map(|x| IVec2::new(x, y)).collect()
+    }
+
+    fn covered_cells(rects: &[CellRect]) -> Vec<IVec2> {
+        rects
+            .iter()
+            .flat_map(|r| (r.min.y..=r.max.y).flat_map(move |y| (r.min.x..=r.max.x).map(move |x| IVec2::new(x, y))))
+            .collect()
+    }
+
+    #[test]
+    fn platforms_across_a_gap_stay_apart() {
+        let cells = [row(4, 0..3), row(4, 6..10)].concat();
+
+        let rects = merge_cells(&cells, i32::MAX);
+
+        assert_eq!(rects, vec![
+            CellRect { min: IVec2::new(0, 4), max: IVec2::new(2, 4) },
+            CellRect { min: IVec2::new(6, 4), max: IVec2::new(9, 4) }
+        ]);
+    }
+
+    #[test]
+    fn a_filled_block_is_one_rectangle() {
+        let cells: Vec<IVec2> = (0..5).flat_map(|y| row(y, 2..8)).collect();
+
+        let rects = merge_cells(&cells, i32::MAX);
+
+        assert_eq!(rects, vec![CellRect { min: IVec2::new(2, 0), max: IVec2::new(7, 4) }]);
+    }
+
+    #[test]
+    fn rectangles_cover_exactly_the_cells() {
+        // a staircase with a hole in it and a lone cell off to the side
+        let mut cells: Vec<IVec2> = (0..6).flat_map(|y| row(y, 0..(8 - y))).collect();
+        cells.retain(|c| *c != IVec2::new(2, 1));
+        cells.push(IVec2::new(12, 3));
+
+        for max_height in [1, 2, i32::MAX] {
+            let rects = merge_cells(&cells, max_height);
+            let covered = covered_cells(&rects);
+
+            assert!(rects.len() < cells.len(), "no fewer colliders than cells");
+            assert!(rects.iter().all(|r| r.size().y <= max_height));
+            assert_eq!(covered.len(), cells.len(), "a cell is covered twice");
+            assert_eq!(covered.iter().copied().collect::<HashSet<_>>(), cells.iter().copied().collect());
+        }
+    }
+}
@@ -2,15 +2,23 @@ pub mod consts;
 pub mod coord;
 
 pub mod boss;
+pub mod breakable;
+pub mod camera_zone;
+pub mod checkpoint;
 pub mod door;
 pub mod enemies;
 pub mod exit;
+pub mod hazard;
+pub mod memory;
+pub mod merge;
 pub mod one_way;
+pub mod platform;
 pub mod shop;
 pub mod solid;
 pub mod transition;
 pub mod tutorial;
 pub mod util;
+pub mod validate;
 
 use crate::level::consts::{RENDERED_TILE_SIZE, TILE_SIZE};
 use crate::pathfind::Region;
@@ -19,7 +27,9 @@ use bevy::prelude::*;
 use bevy_debug_text_overlay::screen_print;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
-use crate::level::transition::LevelTransition;
+use crate::level::transition::{ActiveTransition, LevelTransition};
+use crate::level::util::LdtkFields;
+use crate::common::UpdateStage;
 
 #[derive(Resource, Default, Copy, Clone, Debug)]
 pub struct LevelInfo {
@@ -48,37 +58,116 @@ pub struct PlayerTileBundle {
 #[derive(Component)]
 pub struct LevelRegion;
 
+// What sets a level up runs in `UpdateStage::Level` in this order, so the
+// tiles, colliders and enemies of a level are spawned in the same order every
+// time and the physics and queries go through them the same way
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LevelSystems {
+    Transition,
+    Load,
+    Player,
+    Checkpoints,
+    Solids,
+    OneWays,
+    Hazards,
+    Breakables,
+    Platforms,
+    Doors,
+    Exits,
+    CameraZones,
+    Shops,
+    Tutorials,
+    Enemies,
+    Boss,
+    Grid,
+    Stats
+}
+
+impl LevelSystems {
+    const ORDER: [LevelSystems; 18] = [
+        LevelSystems::Transition,
+        LevelSystems::Load,
+        LevelSystems::Player,
+        LevelSystems::Checkpoints,
+        LevelSystems::Solids,
+        LevelSystems::OneWays,
+        LevelSystems::Hazards,
+        LevelSystems::Breakables,
+        LevelSystems::Platforms,
+        LevelSystems::Doors,
+        LevelSystems::Exits,
+        LevelSystems::CameraZones,
+        LevelSystems::Shops,
+        LevelSystems::Tutorials,
+        LevelSystems::Enemies,
+        LevelSystems::Boss,
+        LevelSystems::Grid,
+        LevelSystems::Stats
+    ];
+
+    fn previous(self) -> Option<LevelSystems> {
+        let i = Self::ORDER.iter().position(|s| *s == self).unwrap();
+        i.checked_sub(1).map(|i| Self::ORDER[i])
+    }
+
+    pub fn on_update(self) -> SystemSet {
+        self.after_previous(UpdateStage::Level.on_update(GameState::LevelTransition))
+    }
+
+    pub fn on_enter(self) -> SystemSet {
+        self.after_previous(UpdateStage::Level.on_enter(GameState::LevelTransition))
+    }
+
+    fn after_previous(self, set: SystemSet) -> SystemSet {
+        let set = set.label(self);
+
+        match self.previous() {
+            Some(previous) => set.after(previous),
+            None => set
+        }
+    }
+}
+
 pub struct LevelPlugin;
 
 impl Plugin for LevelPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(LdtkPlugin)
+        // `LdtkPlugin` itself is added by whoever builds the app, it pulls in
+        // the tilemap renderer
+        app
             .insert_resource(LdtkSettings {
                 int_grid_rendering: IntGridRendering::Invisible,
                 level_background: LevelBackground::Nonexistent,
                 ..default()
             })
             .init_resource::<LevelInfo>()
-            .insert_resource(LevelSelection::Identifier(String::from("Level_0")))
+            .insert_resource(LevelSelection::Identifier(String::from(consts::FIRST_LEVEL)))
             .register_ldtk_entity::<PlayerTileBundle>("EntryPoint");
 
         solid::register_solid_tile(app);
         one_way::register_one_way_tile(app);
+        breakable::register_breakable_tiles(app);
+        camera_zone::register_camera_zones(app);
+        platform::register_platforms(app);
         exit::register_exit_entity(app);
+        checkpoint::register_checkpoints(app);
+        memory::register_level_memory(app);
         transition::register_transition_systems(app);
         enemies::register_enemy_spawnpoints(app);
         door::register_doors(app);
+        hazard::register_hazards(app);
         boss::register_boss_spawnpoints(app);
         shop::register_shop_spawnpoints(app);
         tutorial::register_tutorial_text(app);
+        validate::register_level_validation(app);
 
-        app.add_system_set(SystemSet::on_enter(GameState::LevelTransition).with_system(load_level));
+        app.add_system_set(LevelSystems::Load.on_enter().with_system(load_level));
 
         app.add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Load.on_update()
                 .with_system(move_player)
-                .with_system(refresh_level)
-                .with_system(reconfigure_region_to_fit_level),
+                .with_system(refresh_level.after(move_player))
+                .with_system(reconfigure_region_to_fit_level.after(refresh_level)),
         );
 
         app.register_type::<GridCoords>();
@@ -161,25 +250,30 @@ pub struct FinishedTransitioning;
 
 fn move_player(
     mut commands: Commands,
-    // transition: Res<transition::LevelTransition>,
+    transition: Res<LevelTransition>,
+    mut active: ResMut<ActiveTransition>,
     mut q: Query<(Entity, &mut Transform), With<Player>>,
     pos: Query<&EntityInstance, Added<PlayerTileMarker>>,
     lvl_info: Res<LevelInfo>,
 ) {
-    for inst in pos.iter() {
-        // let entry_point_id = util::val_expect_i32(&inst.field_instances[0].value).unwrap();
+    if pos.is_empty() {
+        return;
+    }
 
-        // if entry_point_id != transition.entry_point_id {
-        //     continue;
-        // }
+    let inst = match pos.iter().find(|inst| inst.field_i32("EntryPoint_ID") == Ok(transition.entry_point)) {
+        Some(inst) => inst,
+        None => {
+            warn!("No entry point {} in {}, using the first one", transition.entry_point, transition.next);
+            pos.iter().next().unwrap()
+        }
+    };
 
-        let (e, mut tf) = q.single_mut();
-        tf.translation =
-            coord::grid_coord_to_translation(inst.grid, lvl_info.grid_size.as_ivec2()).extend(1.0);
+    let (e, mut tf) = q.single_mut();
+    tf.translation =
+        coord::grid_coord_to_translation(inst.grid, lvl_info.grid_size.as_ivec2()).extend(1.0);
 
-        tf.translation.x += PLAYER_SIZE_PX.x / 2.0;
+    tf.translation.x += PLAYER_SIZE_PX.x / 2.0;
 
-        commands.entity(e).insert(FinishedTransitioning);
-        return;
-    }
+    commands.entity(e).insert(FinishedTransitioning);
+    active.level_ready = true;
 }
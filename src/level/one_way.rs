@@ -2,7 +2,6 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 
 use bevy_rapier2d::prelude::*;
-use std::collections::HashMap;
 
 use crate::{
     level::consts::ONE_WAY_PLATFORMS_COLLISION_GROUP,
@@ -12,7 +11,10 @@ use crate::{
         state_machine as s,
     }
 };
-use crate::level::consts::{SOLIDS_COLLISION_GROUP, TILE_SIZE};
+use crate::level::consts::{ONE_WAY_TILE_VALUE, SOLIDS_COLLISION_GROUP, TILES_LAYER};
+use crate::level::merge::{cells_by_layer, merge_cells};
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
 
 #[derive(Default, Component)]
 pub struct OneWayTileSpawnMarker;
@@ -27,24 +29,18 @@ pub struct OneWayTile;
 
 pub fn register_one_way_tile(app: &mut App) {
     app
-        .register_ldtk_int_cell_for_layer::<OneWayTileBundle>("Tiles", 2)
+        .register_ldtk_int_cell_for_layer::<OneWayTileBundle>(TILES_LAYER, ONE_WAY_TILE_VALUE)
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::OneWays.on_update()
                 .with_system(add_one_way_tiles)
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
                 .with_system(enable_one_way_colliders)
                 .with_system(disable_one_way_colliders)
         );
 }
 
-#[derive(Default)]
-struct PlatformChunk {
-    start: i32,
-    end: i32
-}
-
 pub fn add_one_way_tiles(
     mut commands: Commands,
     colliders: Query<(&Parent, &GridCoords), Added<OneWayTileSpawnMarker>>
@@ -53,41 +49,24 @@ pub fn add_one_way_tiles(
        return;
     }
 
-    let mut common_parent = Entity::from_raw(0);
-    let mut platforms_by_y = HashMap::new();
-
-    for (parent, coords) in colliders.iter() {
-        platforms_by_y
-            .entry(coords.y)
-            .or_insert(vec![])
-            .push(coords.x);
-
-        common_parent = parent.get();
-    }
-
-    for (y_level, vec) in platforms_by_y.iter() {
-        let mut sorted_x = vec.clone();
-        sorted_x.sort();
-
-        let mut chunk = PlatformChunk::default();
-        chunk.start = sorted_x[0];
-        chunk.end = sorted_x[sorted_x.len() - 1];
-
-        commands.entity(common_parent).with_children(|parent| {
-            parent.spawn((
-                OneWayTile,
-                Collider::cuboid(
-                    (chunk.end - chunk.start + 1) as f32 * TILE_SIZE / 2.,
-                    TILE_SIZE / 2.
-                ),
-                RigidBody::Fixed,
-                TransformBundle::from_transform(Transform::from_xyz(
-                    ((chunk.start + chunk.end) as f32 / 2.0) * TILE_SIZE,
-                    *y_level as f32 * TILE_SIZE,
-                    0.0
-                )),
-                ONE_WAY_PLATFORMS_COLLISION_GROUP
-            ));
+    for (layer, cells) in cells_by_layer(colliders.iter()) {
+        // one tile thick, the player has to be able to jump up through them
+        let rects = merge_cells(&cells, 1);
+        info!("Merged {} one-way tiles into {} colliders", cells.len(), rects.len());
+
+        commands.entity(layer).with_children(|parent| {
+            for rect in rects {
+                let center = rect.center();
+                let half_extents = rect.half_extents();
+
+                parent.spawn((
+                    OneWayTile,
+                    Collider::cuboid(half_extents.x, half_extents.y),
+                    RigidBody::Fixed,
+                    TransformBundle::from_transform(Transform::from_xyz(center.x, center.y, 0.0)),
+                    ONE_WAY_PLATFORMS_COLLISION_GROUP
+                ));
+            }
         });
     }
 }
@@ -0,0 +1,346 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use crate::assets::LevelAssets;
+use crate::entity_states::Die;
+use crate::level::{coord, LevelInfo};
+use crate::level::consts::{ONE_WAY_PLATFORMS_COLLISION_GROUP, RENDERED_TILE_SIZE, SCALE_FACTOR, TILE_SIZE};
+use crate::level::one_way::OneWayTile;
+use crate::level::util::LdtkFields;
+use crate::player::Player;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+// How far below the player's feet a platform still counts as stood on
+const STANDING_DISTANCE: f32 = 2.0;
+
+const CRUMBLE_FALL_SPEED: f32 = 600.0;
+
+#[derive(Component, Default)]
+pub struct PlatformSpawnMarker;
+
+#[derive(Bundle, LdtkEntity)]
+pub struct PlatformBundle {
+    marker: PlatformSpawnMarker,
+    #[from_entity_instance]
+    instance: EntityInstance
+}
+
+// Every platform behaves like a one-way tile, see `one_way.rs`. They're
+// despawned with the rest of the level on a transition and spawned again
+// from LDtk, `start` is where crumbling platforms come back to.
+#[derive(Component)]
+pub struct Platform {
+    pub start: Vec2,
+    // collider half extents, so a fallen platform can get its collider back
+    pub half_extents: Vec2
+}
+
+// Goes back and forth along `path`, carrying whoever stands on it
+#[derive(Component)]
+pub struct MovingPlatform {
+    pub path: Vec<Vec2>,
+    // world units per second
+    pub speed: f32,
+    pub next: usize,
+    pub forward: bool
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CrumbleState {
+    Solid,
+    Crumbling,
+    Fallen
+}
+
+#[derive(Component)]
+pub struct CrumblingPlatform {
+    pub state: CrumbleState,
+    pub crumble: Timer,
+    pub respawn: Timer
+}
+
+#[derive(Component)]
+pub struct BouncePad {
+    // the player's vertical velocity when launched
+    pub strength: f32
+}
+
+enum PlatformKind {
+    Moving(MovingPlatform),
+    Crumbling(CrumblingPlatform),
+    Bounce(BouncePad)
+}
+
+pub fn register_platforms(app: &mut App) {
+    app
+        .register_ldtk_entity::<PlatformBundle>("MovingPlatform")
+        .register_ldtk_entity::<PlatformBundle>("CrumblingPlatform")
+        .register_ldtk_entity::<PlatformBundle>("BouncePad")
+        .add_system_set(
+            LevelSystems::Platforms.on_update()
+                .with_system(add_platforms)
+        )
+        .add_system_set(
+            UpdateStage::Physics.on_update(GameState::Gameplay)
+                .with_system(move_platforms)
+        )
+        .add_system_set(
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .with_system(crumble_platforms)
+                .with_system(bounce_pads)
+        );
+}
+
+fn platform_kind(inst: &EntityInstance, lvl_info: &LevelInfo) -> Result<PlatformKind, String> {
+    match inst.identifier.as_str() {
+        "MovingPlatform" => {
+            let start = coord::px_to_translation(inst.px, lvl_info.grid_size.as_ivec2());
+            let points = inst.field_points("Path")?;
+
+            // the path points are cells, the platform's center goes through
+            // the middle of each
+            let path = std::iter::once(start)
+                .chain(points.iter().map(|p| coord::px_to_translation(
+                    *p * TILE_SIZE as i32 + IVec2::splat(TILE_SIZE as i32 / 2),
+                    lvl_info.grid_size.as_ivec2()
+                )))
+                .collect();
+
+            Ok(PlatformKind::Moving(MovingPlatform {
+                path,
+                speed: inst.field_f32("Speed")? * RENDERED_TILE_SIZE,
+                next: 1,
+                forward: true
+            }))
+        }
+
+        "CrumblingPlatform" => Ok(PlatformKind::Crumbling(CrumblingPlatform {
+            state: CrumbleState::Solid,
+            crumble: Timer::from_seconds(inst.field_f32("CrumbleDelay")?, TimerMode::Once),
+            respawn: Timer::from_seconds(inst.field_f32("RespawnDelay")?, TimerMode::Once)
+        })),
+
+        "BouncePad" => Ok(PlatformKind::Bounce(BouncePad {
+            strength: inst.field_f32("Strength")?
+        })),
+
+        _ => Err(format!("{} isn't a platform", inst.describe()))
+    }
+}
+
+fn add_platforms(
+    mut commands: Commands,
+    q: Query<&EntityInstance, Added<PlatformSpawnMarker>>,
+    lvl_info: Res<LevelInfo>,
+    assets: Res<LevelAssets>
+) {
+    for inst in q.iter() {
+        let kind = match platform_kind(inst, &lvl_info) {
+            Ok(kind) => kind,
+            Err(e) => {
+                error!("Skipping platform: {}", e);
+                continue;
+            }
+        };
+
+        let pos = coord::px_to_translation(inst.px, lvl_info.grid_size.as_ivec2());
+        let size = Vec2::new(inst.width as f32, inst.height as f32);
+        let half_extents = size / 2.0;
+
+        let mut platform = commands.spawn((
+            Platform { start: pos, half_extents },
+            OneWayTile,
+            Collider::cuboid(half_extents.x, half_extents.y),
+            RigidBody::KinematicPositionBased,
+            ONE_WAY_PLATFORMS_COLLISION_GROUP,
+            SpatialBundle::from_transform(
+                Transform::default()
+                    .with_translation(pos.extend(0.0))
+                    .with_scale(Vec3::new(SCALE_FACTOR, SCALE_FACTOR, 1.0))
+            )
+        ));
+
+        match kind {
+            PlatformKind::Moving(moving) => platform.insert(moving),
+            PlatformKind::Crumbling(crumbling) => platform.insert(crumbling),
+            PlatformKind::Bounce(bounce) => platform.insert(bounce)
+        };
+
+        // the tile picked for the entity in LDtk, repeated across it
+        if let Some(tile) = &inst.tile {
+            let tiles = (size.x / TILE_SIZE).max(1.0) as i32;
+
+            platform.with_children(|parent| {
+                for i in 0..tiles {
+                    parent.spawn(SpriteBundle {
+                        sprite: Sprite {
+                            rect: Some(Rect::new(
+                                tile.x as f32,
+                                tile.y as f32,
+                                (tile.x + tile.w) as f32,
+                                (tile.y + tile.h) as f32
+                            )),
+                            custom_size: Some(Vec2::new(TILE_SIZE, size.y)),
+                            ..default()
+                        },
+                        texture: assets.tileset.clone(),
+                        transform: Transform::from_xyz(
+                            (i as f32 + 0.5) * TILE_SIZE - size.x / 2.0,
+                            0.0,
+                            1.0
+                        ),
+                        ..default()
+                    });
+                }
+            });
+        }
+    }
+}
+
+// Whether the player is standing on top of `platform`
+fn standing_on(
+    rapier: &RapierContext,
+    player: (Entity, &Transform, &Collider, &Player),
+    platform: Entity
+) -> bool {
+    let (_, tf, collider, player) = player;
+
+    if !player.grounded || player.vel.y > 0.0 {
+        return false;
+    }
+
+    let pos = tf.translation.truncate();
+
+    let cast = rapier.cast_shape(
+        Vect::new(pos.x, pos.y),
+        Rot::default(),
+        -Vect::Y,
+        collider,
+        STANDING_DISTANCE,
+        QueryFilter {
+            predicate: Some(&|x: Entity| x == platform),
+            ..default()
+        }
+    );
+
+    cast.is_some()
+}
+
+fn move_platforms(
+    time: Res<Time>,
+    mut platforms: Query<(Entity, &mut Transform, &mut MovingPlatform), Without<Player>>,
+    mut player: Query<(Entity, &mut Transform, &Collider, &Player), Without<Die>>,
+    rapier: Res<RapierContext>
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut tf, mut moving) in platforms.iter_mut() {
+        if moving.path.len() < 2 {
+            continue;
+        }
+
+        let pos = tf.translation.truncate();
+        let target = moving.path[moving.next];
+        let step = moving.speed * dt;
+
+        let new_pos = if pos.distance(target) <= step {
+            // ping-pong between the two ends of the path
+            let last = moving.path.len() - 1;
+
+            if moving.forward && moving.next == last {
+                moving.forward = false;
+            } else if !moving.forward && moving.next == 0 {
+                moving.forward = true;
+            }
+
+            moving.next = if moving.forward { moving.next + 1 } else { moving.next - 1 };
+            target
+        } else {
+            pos + (target - pos).normalize() * step
+        };
+
+        let delta = new_pos - pos;
+
+        if let Ok((p, mut player_tf, collider, player)) = player.get_single_mut() {
+            if standing_on(&rapier, (p, &*player_tf, collider, player), entity) {
+                player_tf.translation += delta.extend(0.0);
+            }
+        }
+
+        tf.translation.x = new_pos.x;
+        tf.translation.y = new_pos.y;
+    }
+}
+
+fn crumble_platforms(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut platforms: Query<(Entity, &Platform, &mut Transform, &mut Visibility, &mut CrumblingPlatform), Without<Player>>,
+    player: Query<(Entity, &Transform, &Collider, &Player), Without<Die>>,
+    rapier: Res<RapierContext>
+) {
+    let player = player.get_single().ok();
+
+    for (entity, platform, mut tf, mut visibility, mut crumbling) in platforms.iter_mut() {
+        match crumbling.state {
+            CrumbleState::Solid => {
+                if player.map_or(false, |player| standing_on(&rapier, player, entity)) {
+                    crumbling.state = CrumbleState::Crumbling;
+                    crumbling.crumble.reset();
+                }
+            }
+
+            CrumbleState::Crumbling => {
+                crumbling.crumble.tick(time.delta());
+
+                // shakes a little before it goes
+                let shake = (crumbling.crumble.percent() * 60.0).sin() * SCALE_FACTOR / 2.0;
+                tf.translation.x = platform.start.x + shake;
+
+                if crumbling.crumble.finished() {
+                    crumbling.state = CrumbleState::Fallen;
+                    crumbling.respawn.reset();
+                    tf.translation.x = platform.start.x;
+                    commands.entity(entity).remove::<Collider>();
+                }
+            }
+
+            CrumbleState::Fallen => {
+                crumbling.respawn.tick(time.delta());
+                tf.translation.y -= CRUMBLE_FALL_SPEED * time.delta_seconds();
+                visibility.is_visible = tf.translation.y > 0.0;
+
+                if crumbling.respawn.finished() {
+                    crumbling.state = CrumbleState::Solid;
+                    tf.translation.y = platform.start.y;
+                    visibility.is_visible = true;
+                    commands.entity(entity).insert(Collider::cuboid(
+                        platform.half_extents.x,
+                        platform.half_extents.y
+                    ));
+                }
+            }
+        }
+    }
+}
+
+fn bounce_pads(
+    pads: Query<(Entity, &BouncePad)>,
+    mut player: Query<(Entity, &Transform, &Collider, &mut Player), Without<Die>>,
+    rapier: Res<RapierContext>
+) {
+    let (p, tf, collider, mut player) = match player.get_single_mut() {
+        Ok(p) => p,
+        Err(_) => return
+    };
+
+    for (entity, pad) in pads.iter() {
+        if standing_on(&rapier, (p, tf, collider, &*player), entity) {
+            player.vel.y = pad.strength;
+            player.grounded = false;
+            return;
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::assets::{ShopAssets, UiAssets};
 
 use crate::level::{coord, LevelInfo};
 use crate::shop::ShopBundle;
-use crate::state::GameState;
+use crate::level::LevelSystems;
 
 #[derive(Component, Copy, Clone, Default)]
 pub struct ShopSpawnpointMarker;
@@ -21,7 +21,7 @@ pub fn register_shop_spawnpoints(app: &mut App) {
     app
         .register_ldtk_entity::<ShopSpawnpointBundle>("ShopSpawnpoint")
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Shops.on_update()
                 .with_system(spawn_shops)
         );
 }
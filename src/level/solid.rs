@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
-use crate::state::GameState;
+use crate::level::consts::{SOLID_TILE_VALUE, TILES_LAYER};
+use crate::level::merge::{cells_by_layer, merge_cells};
+use crate::level::LevelSystems;
 
 #[derive(Default, Component)]
 pub struct SolidTileSpawnMarker;
@@ -16,25 +18,37 @@ pub struct SolidTile;
 
 pub fn register_solid_tile(app: &mut App) {
     app
-        .register_ldtk_int_cell_for_layer::<SolidTileBundle>("Tiles", 1)
+        .register_ldtk_int_cell_for_layer::<SolidTileBundle>(TILES_LAYER, SOLID_TILE_VALUE)
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Solids.on_update()
                 .with_system(add_solid_tiles)
         );
 }
 
 pub fn add_solid_tiles(
     mut commands: Commands,
-    colliders: Query<Entity, Added<SolidTileSpawnMarker>>
+    colliders: Query<(&Parent, &GridCoords), Added<SolidTileSpawnMarker>>
 ) {
-    for e in colliders.iter() {
-        commands.entity(e).with_children(|parent| {
-            parent.spawn((
-                SolidTile,
-                Collider::cuboid(4., 4.),
-                RigidBody::Fixed,
-                TransformBundle::default()
-            ));
+    if colliders.is_empty() {
+        return;
+    }
+
+    for (layer, cells) in cells_by_layer(colliders.iter()) {
+        let rects = merge_cells(&cells, i32::MAX);
+        info!("Merged {} solid tiles into {} colliders", cells.len(), rects.len());
+
+        commands.entity(layer).with_children(|parent| {
+            for rect in rects {
+                let center = rect.center();
+                let half_extents = rect.half_extents();
+
+                parent.spawn((
+                    SolidTile,
+                    Collider::cuboid(half_extents.x, half_extents.y),
+                    RigidBody::Fixed,
+                    TransformBundle::from_transform(Transform::from_xyz(center.x, center.y, 0.0))
+                ));
+            }
         });
     }
-}
\ No newline at end of file
+}
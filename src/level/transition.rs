@@ -1,16 +1,26 @@
 use bevy::prelude::*;
 use bevy_debug_text_overlay::screen_print;
 use bevy_ecs_ldtk::prelude::*;
-use crate::camera::GameCamera;
+use serde::{Deserialize, Serialize};
+use crate::camera::{GameCamera, VIEW_SIZE};
 use crate::coin::coin::Coin;
 use crate::combat::{ExplosionAttack, ProjectileAttack};
 use crate::enemies::Enemy;
+use crate::fx::transition::{
+    FadeTransition, IrisTransition, MaskQuery, NoTransition, PixelateTransition, Transition,
+    TransitionAssets, TransitionView, WipeTransition
+};
 use crate::level::{FinishedTransitioning, exit::LevelExit, LevelInfo};
+use crate::level::checkpoint::Checkpoint;
 use crate::level::consts::TILE_SIZE;
+use crate::level::door::{DoorGroup, DoorTrigger};
+use crate::level::platform::Platform;
 use crate::level::tutorial::HelpText;
+use crate::pathfind::grid::PathfindingGrid;
 use crate::state::GameState;
 use crate::player::Player;
 use crate::shop::Shop;
+use crate::level::LevelSystems;
 
 
 #[derive(Resource, Default)]
@@ -27,37 +37,109 @@ pub struct TransitionSetupEvent {
 #[derive(Resource, Default)]
 pub struct LevelTransition {
     pub next: String,
-    pub transition_effect: TransitionEffect
+    // EntryPoint_ID of the entry point to place the player at in `next`
+    pub entry_point: i32,
+    pub transition_effect: TransitionEffect,
+    // reload `next` even if it's the level that's already loaded, used when
+    // respawning at a checkpoint
+    pub reload: bool
 }
 
-#[derive(Clone, Resource)]
+#[derive(Deserialize, Serialize, Copy, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
 pub enum TransitionEffect {
-    Fade(FadeTransition),
+    Fade,
+    Wipe,
+    Iris,
+    Pixelate,
+    // swaps the levels as soon as the next one is loaded, for fast iteration
+    None
 }
 
 impl Default for TransitionEffect {
     fn default() -> Self {
-       Self::Fade(FadeTransition::default())
+        Self::Fade
     }
 }
 
-#[derive(Resource, Clone)]
-pub struct FadeTransition {
-    pub mask: Option<Entity>,
-    pub fade_in: Timer,
-    pub fade_out: Timer,
-    pub fade_pause: Timer,
-    pub fade_color: Color
+impl TransitionEffect {
+    // The "Transition" enum on Exit entities, "Default" leaves it to
+    // config.toml
+    pub fn from_ldtk(name: &str) -> Result<Option<Self>, String> {
+        match name {
+            "Default" => Ok(None),
+            "Fade" => Ok(Some(TransitionEffect::Fade)),
+            "Wipe" => Ok(Some(TransitionEffect::Wipe)),
+            "Iris" => Ok(Some(TransitionEffect::Iris)),
+            "Pixelate" => Ok(Some(TransitionEffect::Pixelate)),
+            "None" => Ok(Some(TransitionEffect::None)),
+            _ => Err(format!("Unknown transition effect {:?}", name))
+        }
+    }
+
+    fn build(&self) -> Box<dyn Transition> {
+        match self {
+            TransitionEffect::Fade => Box::new(FadeTransition { color: Color::BLACK, ..default() }),
+            TransitionEffect::Wipe => Box::new(WipeTransition { color: Color::BLACK, ..default() }),
+            TransitionEffect::Iris => Box::new(IrisTransition::default()),
+            TransitionEffect::Pixelate => Box::new(PixelateTransition { color: Color::BLACK, ..default() }),
+            TransitionEffect::None => Box::new(NoTransition)
+        }
+    }
+}
+
+// Read from the `[transition]` section of config.toml, in seconds
+#[derive(Resource, Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct TransitionSettings {
+    // for exits that don't pick one, respawns and the main menu
+    pub effect: TransitionEffect,
+    pub cover: f32,
+    pub pause: f32,
+    pub reveal: f32
+}
+
+impl Default for TransitionSettings {
+    fn default() -> Self {
+        Self {
+            effect: TransitionEffect::Fade,
+            cover: 0.2,
+            pause: 0.5,
+            reveal: 0.2
+        }
+    }
 }
 
-impl Default for FadeTransition {
+// The transition being played, built from `LevelTransition` when it starts
+#[derive(Resource)]
+pub struct ActiveTransition {
+    pub effect: Box<dyn Transition>,
+    pub cover: Timer,
+    pub pause: Timer,
+    pub reveal: Timer,
+    // whether the player has been placed in the next level, see
+    // `level::move_player`. The pause lasts at least until then.
+    pub level_ready: bool
+}
+
+impl Default for ActiveTransition {
     fn default() -> Self {
         Self {
-            mask: None,
-            fade_in: Timer::from_seconds(0.2, TimerMode::Once),
-            fade_out: Timer::from_seconds(0.2, TimerMode::Once),
-            fade_pause: Timer::from_seconds(0.5, TimerMode::Once),
-            fade_color: Color::BLACK
+            effect: Box::new(NoTransition),
+            cover: Timer::default(),
+            pause: Timer::default(),
+            reveal: Timer::default(),
+            level_ready: false
+        }
+    }
+}
+
+impl ActiveTransition {
+    pub fn coverage(&self) -> f32 {
+        if !self.cover.finished() {
+            self.cover.percent()
+        } else {
+            self.reveal.percent_left()
         }
     }
 }
@@ -69,15 +151,17 @@ pub fn register_transition_systems(app: &mut App) {
         .add_event::<TransitionCleanupEvent>()
         .add_event::<TransitionSetupEvent>()
         .init_resource::<LevelTransition>()
+        .init_resource::<TransitionSettings>()
+        .init_resource::<ActiveTransition>()
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
-                .with_system(transition_update_effect)
+            LevelSystems::Transition.on_update()
+                .with_system(transition_update_effect.after(transition_on_update))
                 .with_system(transition_on_update)
-                .with_system(transition_cleanup_old)
-                .with_system(transition_setup_new)
+                .with_system(transition_cleanup_old.after(transition_on_update))
+                .with_system(transition_setup_new.after(transition_cleanup_old))
         )
         .add_system_set(
-            SystemSet::on_enter(GameState::LevelTransition)
+            LevelSystems::Transition.on_enter()
                 .with_system(transition_on_start)
         )
         .add_system_set(
@@ -93,20 +177,35 @@ pub fn transition_cleanup_old(
     enemies: Query<Entity, With<Enemy>>,
     shopkeepers: Query<Entity, With<Shop>>,
     projectiles: Query<Entity, With<ProjectileAttack>>,
-    misc: Query<Entity, Or<(With<ProjectileAttack>, With<ExplosionAttack>, With<Coin>, With<HelpText>)>>,
+    misc: Query<Entity, Or<(With<ProjectileAttack>, With<ExplosionAttack>, With<Coin>, With<HelpText>, With<Checkpoint>, With<Platform>, With<DoorGroup>, With<DoorTrigger>)>>,
+
+    levels: Query<Entity, With<Handle<LdtkLevel>>>,
 
     mut transition_cleanup_event: EventReader<TransitionCleanupEvent>,
     mut setup: EventWriter<TransitionSetupEvent>,
 
     sel: Res<LevelSelection>,
-    trans: Res<LevelTransition>
+    trans: Res<LevelTransition>,
+    mut active: ResMut<ActiveTransition>,
+    mut grid: ResMut<PathfindingGrid>
 ) {
     if transition_cleanup_event.is_empty() {
         return;
     }
 
     for ev in transition_cleanup_event.iter() {
-        if LevelSelection::Identifier(trans.next.clone()) != sel.clone() {
+        if trans.reload {
+            for level in levels.iter() {
+                commands.entity(level).insert(Respawn);
+            }
+        }
+
+        let swapping = trans.reload || LevelSelection::Identifier(trans.next.clone()) != sel.clone();
+
+        // staying in the same level, nothing to wait for
+        active.level_ready = !swapping;
+
+        if swapping {
             for exit in exits.iter() {
                 commands.entity(exit).despawn();
             }
@@ -131,6 +230,10 @@ pub fn transition_cleanup_old(
             for proj in projectiles.iter() {
                 commands.entity(proj).despawn_recursive();
             }
+
+            // refilled as the new level's tiles spawn
+            grid.solids.clear();
+            grid.hazards.clear();
         }
 
         setup.send(TransitionSetupEvent { new_level: ev.new_level.clone() });
@@ -160,110 +263,104 @@ pub fn transition_setup_new(
         .unwrap();
 
     lvl_info.grid_size = IVec2::new(lvl.px_wid, lvl.px_hei).as_vec2() / TILE_SIZE;
-    println!("Set new level");
+    debug!("Set new level {}, {} by {} tiles", lvl.identifier, lvl_info.grid_size.x, lvl_info.grid_size.y);
 }
 
 pub fn transition_on_start(
     mut commands: Commands,
-    mut trans: ResMut<LevelTransition>,
+    trans: Res<LevelTransition>,
+    settings: Res<TransitionSettings>,
+    assets: Option<Res<TransitionAssets>>,
+    mut active: ResMut<ActiveTransition>
 ) {
-    match &mut trans.transition_effect {
-        TransitionEffect::Fade(fade) => {
-            fade.fade_in.reset();
-            fade.fade_out.reset();
-            fade.fade_pause.reset();
-
-            fade.mask = Some(commands.spawn(
-                SpriteBundle {
-                    sprite: Sprite {
-                        color: fade.fade_color,
-                        custom_size: Some(Vec2::new(16000.0, 16000.0)),
-                        ..default()
-                    },
-                    transform: Transform::from_translation(Vec3::new(0.0, 0.0, 998.0)),
-                    ..default()
-                }
-            ).id());
-        }
-    }
+    let (cover, pause, reveal) = match trans.transition_effect {
+        TransitionEffect::None => (0.0, 0.0, 0.0),
+        _ => (settings.cover, settings.pause, settings.reveal)
+    };
+
+    // there's nothing to draw with in headless runs
+    let assets = assets.map(|a| a.clone()).unwrap_or_default();
+
+    let mut effect = trans.transition_effect.build();
+    effect.spawn(&mut commands, &assets);
+
+    *active = ActiveTransition {
+        effect,
+        cover: Timer::from_seconds(cover, TimerMode::Once),
+        pause: Timer::from_seconds(pause, TimerMode::Once),
+        reveal: Timer::from_seconds(reveal, TimerMode::Once),
+        level_ready: false
+    };
 }
 
-pub fn exit_transition(mut commands: Commands, transition: Res<LevelTransition>) {
-    match &transition.transition_effect {
-        TransitionEffect::Fade(trans) => {
-            commands.entity(trans.mask.unwrap()).despawn();
-        }
+pub fn exit_transition(
+    mut commands: Commands,
+    mut transition: ResMut<LevelTransition>,
+    settings: Res<TransitionSettings>,
+    active: Res<ActiveTransition>
+) {
+    transition.reload = false;
+
+    // only an exit picks something else
+    transition.transition_effect = settings.effect;
+
+    for mask in active.effect.masks() {
+        commands.entity(*mask).despawn();
     }
 }
 
 pub fn transition_on_update(
     time: Res<Time>,
     mut trans: ResMut<LevelTransition>,
-    mut sel: ResMut<LevelSelection>,
+    mut active: ResMut<ActiveTransition>,
     mut state: ResMut<State<GameState>>,
     mut events: EventWriter<TransitionCleanupEvent>
 ) {
     let dt = time.delta();
     let next_level = trans.next.clone();
 
-    match &mut trans.transition_effect {
-        TransitionEffect::Fade(fade) => {
-            if next_level == "None" {
-                trans.next = String::from("Init");
-                state.push(GameState::GameWonMenu).unwrap();
-                return;
-            }
+    if next_level == "None" {
+        trans.next = String::from("Init");
+        state.push(GameState::GameWonMenu).unwrap();
+        return;
+    }
 
-            fade.fade_in.tick(dt);
-            if fade.fade_in.just_finished() {
-                events.send(TransitionCleanupEvent { new_level: next_level.clone() });
-            }
+    active.cover.tick(dt);
+    if active.cover.just_finished() {
+        events.send(TransitionCleanupEvent { new_level: next_level });
+    }
 
-            if fade.fade_in.finished() && !fade.fade_in.just_finished() {
-                fade.fade_pause.tick(dt);
+    if active.cover.finished() && !active.cover.just_finished() {
+        active.pause.tick(dt);
 
-                if fade.fade_pause.finished() {
-                    fade.fade_out.tick(dt);
+        if active.pause.finished() && active.level_ready {
+            active.reveal.tick(dt);
 
-                    if fade.fade_out.just_finished() {
-                        state.set(GameState::Gameplay).unwrap();
-                    }
-                }
+            if active.reveal.just_finished() {
+                state.set(GameState::Gameplay).unwrap();
             }
         }
     }
 }
 
 pub fn transition_update_effect(
-    mut trans: ResMut<LevelTransition>,
-    camera: Query<&GlobalTransform, With<GameCamera>>,
-    mut pos: Query<(&mut Transform, &mut Sprite)>,
+    active: Res<ActiveTransition>,
+    camera: Query<(&GlobalTransform, &GameCamera)>,
+    player: Query<&GlobalTransform, With<Player>>,
+    mut masks: MaskQuery
 ) {
     if camera.is_empty() {
         return;
     }
 
-    let cam_pos = camera.single().translation();
+    let (cam_tf, cam) = camera.single();
+    let center = cam_tf.translation().truncate();
 
-    match &mut trans.transition_effect {
-        TransitionEffect::Fade(fade) => {
-            let (mut transform, mut spr) = pos.get_mut(fade.mask.unwrap()).unwrap();
-
-            transform.translation.x = cam_pos.x;
-            transform.translation.y = cam_pos.y;
-
-            let percent = if !fade.fade_in.finished() {
-                fade.fade_in.percent()
-            } else {
-                fade.fade_out.percent_left()
-            };
-
-            spr.color = Color::rgba(
-                fade.fade_color.r(),
-                fade.fade_color.g(),
-                fade.fade_color.b(),
-                percent
-            );
-        }
+    let view = TransitionView {
+        center,
+        size: VIEW_SIZE * cam.zoom,
+        focus: player.get_single().map_or(center, |tf| tf.translation().truncate())
     };
-}
\ No newline at end of file
+
+    active.effect.update(active.coverage(), &view, &mut masks);
+}
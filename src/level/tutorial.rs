@@ -1,10 +1,12 @@
 use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use crate::assets::UiAssets;
+use crate::input::bindings::describe_bindings;
 use crate::input::PlayerControls;
 use crate::interact::Interact;
-use crate::level::{coord, LevelInfo, util};
-use crate::state::GameState;
+use crate::level::{coord, LevelInfo};
+use crate::level::util::LdtkFields;
+use crate::level::LevelSystems;
 
 #[derive(Component, Copy, Clone, Default)]
 pub struct HelpTextSpawnpointMarker;
@@ -19,7 +21,7 @@ pub struct HelpTextSpawnpointBundle {
 pub fn register_tutorial_text(app: &mut App) {
     app.register_ldtk_entity::<HelpTextSpawnpointBundle>("HelpText")
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Tutorials.on_update()
                 .with_system(spawn_tutorial_text)
         );
 }
@@ -37,20 +39,28 @@ fn spawn_tutorial_text(
             lvl_info.grid_size.as_ivec2()
         );
 
-        let mut content = util::val_expect_string(&inst.field_instances[0].value).unwrap();
+        let mut content = match inst.field_string("Text") {
+            Ok(text) => text,
+            Err(e) => {
+                error!("Skipping help text: {}", e);
+                continue;
+            }
+        };
 
         let replacements = &[
-            ("MoveLeft", ctrl.move_left),
-            ("MoveRight", ctrl.move_right),
-            ("Jump", ctrl.jump),
-            ("Crouch", ctrl.crouch),
-            ("Slash", ctrl.slash),
-            ("Shoot", ctrl.shoot),
-            ("Dash", ctrl.dash),
+            ("MoveLeft", &ctrl.move_left),
+            ("MoveRight", &ctrl.move_right),
+            ("Jump", &ctrl.jump),
+            ("Crouch", &ctrl.crouch),
+            ("Slash", &ctrl.slash),
+            ("Shoot", &ctrl.shoot),
+            ("Dash", &ctrl.dash),
+            ("Interact", &ctrl.interact),
+            ("Pause", &ctrl.pause),
         ];
 
         for (pattern, new) in replacements {
-            content = content.replace(&format!("${{Controls.{}}}", pattern), &format!("{:?}", new));
+            content = content.replace(&format!("${{Controls.{}}}", pattern), &describe_bindings(new));
         }
 
         commands.spawn(HelpTextBundle::new(&ui_assets, pos_vec2, content));
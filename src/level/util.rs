@@ -1,5 +1,5 @@
 use bevy::prelude::*;
-use bevy_ecs_ldtk::{prelude::*, ldtk::FieldInstanceEntityReference};
+use bevy_ecs_ldtk::{prelude::*, ldtk::{FieldInstance, FieldInstanceEntityReference, Level}};
 
 pub fn val_expect_i32(fv: &FieldValue) -> Option<i32> {
     match fv {
@@ -15,6 +15,14 @@ pub fn val_expect_ent_ref(fv: &FieldValue) -> Option<FieldInstanceEntityReferenc
     }
 }
 
+// For refs that are allowed to be left empty
+pub fn val_expect_opt_ent_ref(fv: &FieldValue) -> Option<Option<FieldInstanceEntityReference>> {
+    match fv {
+        FieldValue::EntityRef(e) => Some(e.clone()),
+        _ => None
+    }
+}
+
 pub fn val_expect_string(fv: &FieldValue) -> Option<String> {
     match fv {
         FieldValue::String(Some(e)) => Some(e.clone()),
@@ -27,4 +35,108 @@ pub fn val_expect_point(fv: &FieldValue) -> Option<IVec2> {
         FieldValue::Point(Some(e)) => Some(e.clone()),
         _ => None
     }
-}
\ No newline at end of file
+}
+pub fn val_expect_enum(fv: &FieldValue) -> Option<String> {
+    match fv {
+        FieldValue::Enum(Some(e)) => Some(e.clone()),
+        _ => None
+    }
+}
+
+pub fn val_expect_f32(fv: &FieldValue) -> Option<f32> {
+    match fv {
+        FieldValue::Float(Some(f)) => Some(*f),
+        _ => None
+    }
+}
+
+// Every point has to be set, a path with holes in it isn't a path
+pub fn val_expect_points(fv: &FieldValue) -> Option<Vec<IVec2>> {
+    match fv {
+        FieldValue::Points(points) => points.iter().copied().collect(),
+        _ => None
+    }
+}
+
+// Fields are looked up by the identifier they have in the LDtk editor, so
+// reordering them there doesn't break anything. The errors name the field
+// and the entity or level it's missing from.
+pub trait LdtkFields {
+    fn field_instances(&self) -> &[FieldInstance];
+
+    fn describe(&self) -> String;
+
+    fn field(&self, id: &str) -> Result<&FieldValue, String> {
+        self.field_instances()
+            .iter()
+            .find(|f| f.identifier == id)
+            .map(|f| &f.value)
+            .ok_or_else(|| format!("{} has no field {:?}", self.describe(), id))
+    }
+
+    fn field_as<T>(
+        &self,
+        id: &str,
+        expected: &str,
+        f: impl Fn(&FieldValue) -> Option<T>
+    ) -> Result<T, String> {
+        let value = self.field(id)?;
+
+        f(value).ok_or_else(|| format!(
+            "{}: field {:?} should be {}, found {:?}",
+            self.describe(), id, expected, value
+        ))
+    }
+
+    fn field_i32(&self, id: &str) -> Result<i32, String> {
+        self.field_as(id, "an int", val_expect_i32)
+    }
+
+    fn field_string(&self, id: &str) -> Result<String, String> {
+        self.field_as(id, "a string", val_expect_string)
+    }
+
+    fn field_point(&self, id: &str) -> Result<IVec2, String> {
+        self.field_as(id, "a point", val_expect_point)
+    }
+
+    fn field_ent_ref(&self, id: &str) -> Result<FieldInstanceEntityReference, String> {
+        self.field_as(id, "an entity ref", val_expect_ent_ref)
+    }
+
+    fn field_opt_ent_ref(&self, id: &str) -> Result<Option<FieldInstanceEntityReference>, String> {
+        self.field_as(id, "an entity ref", val_expect_opt_ent_ref)
+    }
+
+    fn field_enum(&self, id: &str) -> Result<String, String> {
+        self.field_as(id, "an enum value", val_expect_enum)
+    }
+
+    fn field_f32(&self, id: &str) -> Result<f32, String> {
+        self.field_as(id, "a float", val_expect_f32)
+    }
+
+    fn field_points(&self, id: &str) -> Result<Vec<IVec2>, String> {
+        self.field_as(id, "a list of points", val_expect_points)
+    }
+}
+
+impl LdtkFields for EntityInstance {
+    fn field_instances(&self) -> &[FieldInstance] {
+        &self.field_instances
+    }
+
+    fn describe(&self) -> String {
+        format!("{} {}", self.identifier, self.iid)
+    }
+}
+
+impl LdtkFields for Level {
+    fn field_instances(&self) -> &[FieldInstance] {
+        &self.field_instances
+    }
+
+    fn describe(&self) -> String {
+        format!("level {} ({})", self.identifier, self.iid)
+    }
+}
@@ -0,0 +1,369 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_ecs_ldtk::ldtk::{LayerInstance, LdtkJson};
+use crate::enemies::registry::enemy_type;
+use crate::level::camera_zone::read_camera_zone_mode;
+use crate::level::consts::{
+    BRAMBLE_TILE_VALUE, BREAKABLE_TILE_VALUE, DEFAULT_ENTRY_POINT, DOOR_TILE_VALUE, ONE_WAY_TILE_VALUE,
+    PIT_TILE_VALUE, SOLID_TILE_VALUE, SPECIAL_TILES_LAYER, SPIKE_TILE_VALUE, TILES_LAYER
+};
+use crate::level::door::parse_door_condition;
+use crate::level::enemies::parse_difficulty;
+use crate::level::transition::TransitionEffect;
+use crate::level::util::LdtkFields;
+
+// The entities the `level` module registers and the fields it reads from
+// them, with their LDtk field types
+pub const ENTITY_SCHEMA: &[(&str, &[(&str, &str)])] = &[
+    ("EntryPoint", &[("EntryPoint_ID", "Int")]),
+    ("Exit", &[
+        ("NextLevel", "String"),
+        ("EntryPoint", "Int"),
+        ("Transition", "LocalEnum.TransitionEffect")
+    ]),
+    ("EnemySpawnpoint", &[
+        ("EnemyType", "LocalEnum.EnemyType"),
+        ("PatrolRegion", "EntityRef"),
+        ("Difficulty", "LocalEnum.Difficulty")
+    ]),
+    ("PatrolRegion", &[]),
+    ("BossSpawnpoint", &[
+        ("ChargeLeft", "Point"),
+        ("ChargeRight", "Point"),
+        ("HoverBase", "Point"),
+        ("SlamBase", "Point"),
+        ("BoomRegion", "EntityRef"),
+        ("SummonRegion", "EntityRef")
+    ]),
+    ("BoomRegion", &[]),
+    ("SummonRegion", &[]),
+    ("ShopSpawnpoint", &[]),
+    ("Checkpoint", &[]),
+    ("MovingPlatform", &[("Path", "Array<Point>"), ("Speed", "Float")]),
+    ("CrumblingPlatform", &[("CrumbleDelay", "Float"), ("RespawnDelay", "Float")]),
+    ("BouncePad", &[("Strength", "Float")]),
+    ("DoorGroup", &[("Condition", "LocalEnum.DoorCondition"), ("Trigger", "EntityRef")]),
+    ("Key", &[]),
+    ("PressureSwitch", &[]),
+    ("Lever", &[]),
+    ("CameraZone", &[("Mode", "LocalEnum.CameraZoneMode"), ("Bias", "Float")]),
+    ("HelpText", &[("Text", "String")])
+];
+
+// IntGrid layers and the values the tile spawners register for
+pub const INT_GRID_SCHEMA: &[(&str, i32, &str)] = &[
+    (TILES_LAYER, SOLID_TILE_VALUE, "solid"),
+    (TILES_LAYER, ONE_WAY_TILE_VALUE, "one-way"),
+    (TILES_LAYER, BREAKABLE_TILE_VALUE, "breakable"),
+    (SPECIAL_TILES_LAYER, DOOR_TILE_VALUE, "door"),
+    (SPECIAL_TILES_LAYER, SPIKE_TILE_VALUE, "spike"),
+    (SPECIAL_TILES_LAYER, PIT_TILE_VALUE, "pit"),
+    (SPECIAL_TILES_LAYER, BRAMBLE_TILE_VALUE, "bramble")
+];
+
+pub fn register_level_validation(app: &mut App) {
+    app.add_system(validate_levels);
+}
+
+// Reads every field the spawners are going to read, so a broken level shows
+// up as soon as `levels.ldtk` is loaded (or hot reloaded) instead of when
+// someone finally walks into it
+fn validate_levels(
+    mut events: EventReader<AssetEvent<LdtkAsset>>,
+    assets: Res<Assets<LdtkAsset>>
+) {
+    for ev in events.iter() {
+        let handle = match ev {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => handle,
+            AssetEvent::Removed { .. } => continue
+        };
+
+        let ldtk = match assets.get(handle) {
+            Some(ldtk) => ldtk,
+            None => continue
+        };
+
+        let problems = validate_project(&ldtk.project);
+
+        for problem in &problems {
+            error!("{}", problem);
+        }
+
+        if !problems.is_empty() {
+            warn!("Found {} problems in the LDtk project", problems.len());
+        }
+    }
+}
+
+pub fn validate_project(project: &LdtkJson) -> Vec<String> {
+    let mut problems = validate_schema(project);
+
+    // exits can lead into any entry point of any level
+    let entry_points: HashMap<&str, Vec<i32>> = project.levels
+        .iter()
+        .map(|lvl| {
+            let ids = lvl.layer_instances
+                .iter()
+                .flatten()
+                .flat_map(|layer| &layer.entity_instances)
+                .filter(|inst| inst.identifier == "EntryPoint")
+                .filter_map(|inst| inst.field_i32("EntryPoint_ID").ok())
+                .collect();
+
+            (lvl.identifier.as_str(), ids)
+        })
+        .collect();
+
+    for lvl in &project.levels {
+        let layers = lvl.layer_instances.as_deref().unwrap_or(&[]);
+
+        // entity refs have to point into the same level, the spawners only
+        // see the entities of the level being loaded
+        let entities: HashMap<&str, &EntityInstance> = layers
+            .iter()
+            .flat_map(|layer| &layer.entity_instances)
+            .map(|inst| (inst.iid.as_str(), inst))
+            .collect();
+
+        let ids = &entry_points[lvl.identifier.as_str()];
+
+        // "Init" is the empty level behind the main menu, everything else can
+        // be started from a save
+        if lvl.identifier != "Init" && !ids.contains(&DEFAULT_ENTRY_POINT) {
+            problems.push(format!("{}: has no EntryPoint {}", lvl.describe(), DEFAULT_ENTRY_POINT));
+        }
+
+        let mut seen = HashSet::new();
+        for id in ids {
+            if !seen.insert(id) {
+                problems.push(format!("{}: has more than one EntryPoint {}", lvl.describe(), id));
+            }
+        }
+
+        let ctx = ValidationContext {
+            entities: &entities,
+            entry_points: &entry_points
+        };
+
+        for layer in layers {
+            for inst in &layer.entity_instances {
+                for problem in ctx.validate_entity(layer, inst) {
+                    problems.push(format!("{}: {}", lvl.describe(), problem));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+// Checks the definitions themselves: a renamed field or a deleted layer
+// breaks every level at once
+fn validate_schema(project: &LdtkJson) -> Vec<String> {
+    let mut problems = vec![];
+
+    for (entity, fields) in ENTITY_SCHEMA {
+        let def = match project.defs.entities.iter().find(|def| def.identifier == *entity) {
+            Some(def) => def,
+            None => {
+                problems.push(format!("Entity {} isn't defined", entity));
+                continue;
+            }
+        };
+
+        for (field, ty) in fields.iter() {
+            match def.field_defs.iter().find(|f| f.identifier == *field) {
+                Some(f) if f.field_definition_type == *ty => {}
+                Some(f) => problems.push(format!(
+                    "Entity {}: field {} should be {}, found {}",
+                    entity, field, ty, f.field_definition_type
+                )),
+                None => problems.push(format!("Entity {} has no field {}", entity, field))
+            }
+        }
+    }
+
+    for (layer, value, name) in INT_GRID_SCHEMA {
+        let def = project.defs.layers
+            .iter()
+            .find(|def| def.identifier == *layer && def.layer_definition_type == "IntGrid");
+
+        match def {
+            Some(def) if def.int_grid_values.iter().any(|v| v.value == *value) => {}
+            Some(_) => problems.push(format!(
+                "IntGrid layer {} has no value {} for {} tiles", layer, value, name
+            )),
+            None => problems.push(format!(
+                "IntGrid layer {} for {} tiles isn't defined", layer, name
+            ))
+        }
+    }
+
+    problems
+}
+
+struct ValidationContext<'a> {
+    entities: &'a HashMap<&'a str, &'a EntityInstance>,
+    entry_points: &'a HashMap<&'a str, Vec<i32>>
+}
+
+impl<'a> ValidationContext<'a> {
+    fn validate_entity(&self, layer: &LayerInstance, inst: &EntityInstance) -> Vec<String> {
+        let checks = match inst.identifier.as_str() {
+            "EntryPoint" => vec![
+                inst.field_i32("EntryPoint_ID").map(|_| ())
+            ],
+
+            "Exit" => vec![
+                self.level_link(inst, "NextLevel", "EntryPoint"),
+                inst.field_enum("Transition").and_then(|t| TransitionEffect::from_ldtk(&t)
+                    .map(|_| ())
+                    .map_err(|e| format!("{}: {}", inst.describe(), e)))
+            ],
+
+            "EnemySpawnpoint" => vec![
+                self.entity_ref(inst, "PatrolRegion", "PatrolRegion")
+                    .and_then(|region| inside_region(layer, inst, region)),
+                inst.field_enum("EnemyType").and_then(|ty| enemy_type(&ty)
+                    .map(|_| ())
+                    .map_err(|e| format!("{}: {}", inst.describe(), e))),
+                inst.field_enum("Difficulty").and_then(|d| parse_difficulty(&d)
+                    .map(|_| ())
+                    .map_err(|e| format!("{}: {}", inst.describe(), e)))
+            ],
+
+            "BossSpawnpoint" => vec![
+                point_in_layer(layer, inst, "ChargeLeft"),
+                point_in_layer(layer, inst, "ChargeRight"),
+                point_in_layer(layer, inst, "HoverBase"),
+                point_in_layer(layer, inst, "SlamBase"),
+                self.entity_ref(inst, "BoomRegion", "BoomRegion").map(|_| ()),
+                self.entity_ref(inst, "SummonRegion", "SummonRegion").map(|_| ())
+            ],
+
+            "MovingPlatform" => vec![
+                inst.field_points("Path").and_then(|path| path
+                    .iter()
+                    .try_for_each(|p| inside_layer(layer, inst, "Path", *p))),
+                positive(inst, "Speed")
+            ],
+
+            "CrumblingPlatform" => vec![
+                positive(inst, "CrumbleDelay"),
+                positive(inst, "RespawnDelay")
+            ],
+
+            "BouncePad" => vec![
+                positive(inst, "Strength")
+            ],
+
+            "DoorGroup" => vec![
+                self.door_trigger(inst)
+            ],
+
+            "CameraZone" => vec![
+                read_camera_zone_mode(inst).map(|_| ())
+            ],
+
+            "HelpText" => vec![
+                inst.field_string("Text").map(|_| ())
+            ],
+
+            _ => vec![]
+        };
+
+        checks.into_iter().filter_map(Result::err).collect()
+    }
+
+    fn entity_ref(&self, inst: &EntityInstance, id: &str, expected: &str) -> Result<&'a EntityInstance, String> {
+        let e_ref = inst.field_ent_ref(id)?;
+
+        match self.entities.get(e_ref.entity_iid.as_str()) {
+            Some(found) if found.identifier == expected => Ok(*found),
+            Some(found) => Err(format!(
+                "{}: {} points at a {} ({}), expected a {}",
+                inst.describe(), id, found.identifier, e_ref.entity_iid, expected
+            )),
+            None => Err(format!(
+                "{}: {} points at {}, which isn't in this level",
+                inst.describe(), id, e_ref.entity_iid
+            ))
+        }
+    }
+
+    // Keys, switches and levers are required, the region enemies have to be
+    // cleared from isn't
+    fn door_trigger(&self, inst: &EntityInstance) -> Result<(), String> {
+        let kind = parse_door_condition(&inst.field_enum("Condition")?)
+            .map_err(|e| format!("{}: {}", inst.describe(), e))?;
+
+        match kind {
+            Some(kind) => self.entity_ref(inst, "Trigger", kind.identifier()).map(|_| ()),
+            None if inst.field_opt_ent_ref("Trigger")?.is_some() => {
+                self.entity_ref(inst, "Trigger", "PatrolRegion").map(|_| ())
+            }
+            None => Ok(())
+        }
+    }
+
+    // "None" is the exit out of the last level
+    fn level_link(&self, inst: &EntityInstance, level_id: &str, entry_id: &str) -> Result<(), String> {
+        let next = inst.field_string(level_id)?;
+        let entry = inst.field_i32(entry_id)?;
+
+        if next == "None" {
+            return Ok(());
+        }
+
+        match self.entry_points.get(next.as_str()) {
+            Some(ids) if ids.contains(&entry) => Ok(()),
+            Some(_) => Err(format!(
+                "{}: {} is EntryPoint {}, which {} doesn't have",
+                inst.describe(), entry_id, entry, next
+            )),
+            None => Err(format!("{}: {} links to unknown level {:?}", inst.describe(), level_id, next))
+        }
+    }
+}
+
+fn point_in_layer(layer: &LayerInstance, inst: &EntityInstance, id: &str) -> Result<(), String> {
+    inside_layer(layer, inst, id, inst.field_point(id)?)
+}
+
+fn inside_layer(layer: &LayerInstance, inst: &EntityInstance, id: &str, p: IVec2) -> Result<(), String> {
+    if p.x < 0 || p.y < 0 || p.x >= layer.c_wid || p.y >= layer.c_hei {
+        return Err(format!(
+            "{}: {} {:?} is outside the {}x{} level",
+            inst.describe(), id, p, layer.c_wid, layer.c_hei
+        ));
+    }
+
+    Ok(())
+}
+
+fn positive(inst: &EntityInstance, id: &str) -> Result<(), String> {
+    let value = inst.field_f32(id)?;
+
+    if value <= 0.0 {
+        return Err(format!("{}: {} should be above 0, found {}", inst.describe(), id, value));
+    }
+
+    Ok(())
+}
+
+// Regions are anchored at their top left corner, same as
+// `coord::grid_coords_to_region`
+fn inside_region(layer: &LayerInstance, inst: &EntityInstance, region: &EntityInstance) -> Result<(), String> {
+    let tl = region.grid;
+    let br = tl + IVec2::new(region.width, region.height) / layer.grid_size;
+
+    if inst.grid.x < tl.x || inst.grid.y < tl.y || inst.grid.x >= br.x || inst.grid.y >= br.y {
+        return Err(format!(
+            "{}: spawnpoint {:?} is outside its patrol region {} ({:?} to {:?})",
+            inst.describe(), inst.grid, region.iid, tl, br
+        ));
+    }
+
+    Ok(())
+}
@@ -11,7 +11,9 @@ pub mod enemies;
 pub mod state;
 pub mod assets;
 pub mod entry;
+pub mod headless;
 pub mod input;
+pub mod config;
 pub mod pathfind;
 pub mod util;
 pub mod entity_states;
@@ -22,3 +24,9 @@ pub mod ui;
 pub mod shop;
 pub mod interact;
 pub mod anim;
+pub mod save;
+pub mod rng;
+pub mod replay;
+pub mod run_stats;
+pub mod leaderboard;
+pub mod audio;
@@ -19,7 +19,10 @@ use crate::{
 use crate::combat::HurtAbility;
 use crate::common::PHYSICS_STEPS_PER_SEC;
 use crate::pathfind::Patrol;
+use crate::pathfind::PathfinderSystems;
 use crate::util::{Facing, FacingX};
+use crate::rng::{GameRng, RngStream};
+use crate::common::UpdateStage;
 
 #[derive(Component, Debug)]
 pub struct FlyPathfinder {
@@ -39,15 +42,18 @@ impl Default for FlyPathfinder {
 pub fn register_fly_pathfinders(app: &mut App) {
     app
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
-                .with_system(fly_pathfinder_chase)
-                .with_system(fly_pathfinder_patrol)
-                .with_system(fly_pathfinder_lose_notice)
-                .with_system(fly_pathfinder_got_hurt)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .label(PathfinderSystems::Fly)
+                .after(PathfinderSystems::Ranged)
                 .with_system(fly_pathfinder_just_died)
+                .with_system(fly_pathfinder_got_hurt.after(fly_pathfinder_just_died))
+                .with_system(fly_pathfinder_lose_notice.after(fly_pathfinder_got_hurt))
+                .with_system(fly_pathfinder_chase.after(fly_pathfinder_lose_notice))
+                .with_system(fly_pathfinder_patrol.after(fly_pathfinder_chase))
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Gameplay)
+            UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                .after(PathfinderSystems::Fly)
                 .with_run_criteria(FixedTimestep::steps_per_second(PHYSICS_STEPS_PER_SEC))
                 .with_system(fly_pathfinder_remove_kb)
         );
@@ -244,14 +250,14 @@ pub fn fly_pathfinder_lose_notice(
     }
 }
 
-pub fn fly_pathfinder_pick_patrol_point(
+pub fn fly_pathfinder_pick_patrol_point<R: Rng + ?Sized>(
+    rng: &mut R,
     grid: &PathfindingGrid,
     lvl_info: &LevelInfo,
     grid_region: GridRegion,
     self_grid_pos: IVec2,
     obj_size: Vec2
 ) -> Vec2 {
-    let mut rng = thread_rng();
     let mut tries = 0;
     let mut sel = IVec2::ZERO;
 
@@ -280,6 +286,7 @@ pub fn fly_pathfinder_pick_patrol_point(
 pub fn fly_pathfinder_patrol(
     lvl_info: Res<LevelInfo>,
     grid: Res<PathfindingGrid>,
+    mut rng: ResMut<GameRng>,
     mut fly: Query<(
         &GlobalTransform,
         &mut Enemy,
@@ -317,6 +324,7 @@ pub fn fly_pathfinder_patrol(
                 util::timer_tick_to_finish(&mut p.patrol_pause_timer);
 
                 p.target = fly_pathfinder_pick_patrol_point(
+                    rng.stream(RngStream::Pathfinding),
                     &grid,
                     &lvl_info,
                     grid_region,
@@ -374,7 +382,8 @@ pub fn fly_pathfinder_got_hurt(
     mut fly: Query<(
         &mut Enemy,
         &mut HurtAbility
-    ), (With<FlyPathfinder>, Added<Hurt>, Without<Die>)>
+    ), (With<FlyPathfinder>, Added<Hurt>, Without<Die>)>,
+    mut rng: ResMut<GameRng>
 ) {
     for (mut enemy, mut hurt) in fly.iter_mut() {
         if hurt.hit_event.is_none() {
@@ -382,7 +391,10 @@ pub fn fly_pathfinder_got_hurt(
         }
 
         let hit_ev = hurt.hit_event.take().unwrap();
-        enemy.vel = kb::randomize_knockback(kb::fly_pathfinder_knockback(hit_ev.kb));
+        enemy.vel = kb::randomize_knockback(
+            rng.stream(RngStream::Pathfinding),
+            kb::fly_pathfinder_knockback(hit_ev.kb)
+        );
     }
 }
 
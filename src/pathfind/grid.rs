@@ -4,13 +4,13 @@ use bevy_ecs_ldtk::prelude::*;
 use std::collections::HashSet;
 
 use crate::{
-    level::{LevelInfo, solid::SolidTileSpawnMarker},
-    state::GameState,
+    level::{LevelInfo, breakable::BreakableTileSpawnMarker, hazard::HazardTileSpawnMarker, solid::SolidTileSpawnMarker},
     pathfind::util::GridRegion
 };
 
 use pathfinding::prelude::bfs;
 use crate::level::consts::RENDERED_TILE_SIZE;
+use crate::level::LevelSystems;
 
 #[derive(Default, Debug)]
 pub struct PathfindingResult {
@@ -22,6 +22,7 @@ pub struct PathfindingResult {
 #[derive(Default, Resource)]
 pub struct PathfindingGrid {
     pub solids: HashSet<IVec2>,
+    pub hazards: HashSet<IVec2>,
     pub lvl_info: LevelInfo
 }
 
@@ -29,12 +30,19 @@ impl PathfindingGrid {
     pub fn fill(
         mut graph: ResMut<PathfindingGrid>,
         solids: Query<&GridCoords, Added<SolidTileSpawnMarker>>,
+        hazards: Query<&GridCoords, Added<HazardTileSpawnMarker>>,
+        breakables: Query<&GridCoords, Added<BreakableTileSpawnMarker>>,
         lvl_info: Res<LevelInfo>
     ) {
-        for coord in solids.iter() {
+        // breakable tiles are solid until they're blown open
+        for coord in solids.iter().chain(breakables.iter()) {
             graph.solids.insert(IVec2::new(coord.x, lvl_info.grid_size.y as i32 - coord.y - 1));
         }
 
+        for coord in hazards.iter() {
+            graph.hazards.insert(IVec2::new(coord.x, lvl_info.grid_size.y as i32 - coord.y - 1));
+        }
+
         graph.lvl_info = *lvl_info;
     }
 
@@ -133,7 +141,7 @@ pub fn register_pathfinding_grid(app: &mut App) {
     app
         .init_resource::<PathfindingGrid>()
         .add_system_set(
-            SystemSet::on_update(GameState::LevelTransition)
+            LevelSystems::Grid.on_update()
                 .with_system(PathfindingGrid::fill)
         );
 }
\ No newline at end of file
@@ -20,7 +20,6 @@ pub fn walk_pathfinder_knockback(kb: Vec2) -> Vec2 {
     Vec2::new(kb.x * 4.0, y_vel)
 }
 
-pub fn randomize_knockback(kb: Vec2) -> Vec2 {
-    let mut rng = thread_rng();
+pub fn randomize_knockback<R: Rng + ?Sized>(rng: &mut R, kb: Vec2) -> Vec2 {
     kb * Vec2::new(rng.gen_range(0.8..1.2), rng.gen_range(0.8..1.2))
 }
\ No newline at end of file
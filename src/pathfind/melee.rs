@@ -11,21 +11,26 @@ use crate::{
     }
 };
 
-use std::collections::HashSet;
 use crate::pathfind::{
     Patrol,
+    PathfinderSystems,
+    grid::PathfindingGrid,
     walk_pathfinder_get_suitable_target,
+    walk_pathfinder_hazard_ahead,
     walk_pathfinder_jump_if_needed,
     walk_pathfinder_stop_if_colliding_enemy_stopped
 };
 use crate::util::{Facing, FacingX};
+use crate::common::UpdateStage;
 
 #[derive(Component, Default, Debug, Copy, Clone)]
 pub struct MeleePathfinder;
 
 pub fn register_melee_pathfinders(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PathfinderSystems::Melee)
+            .after(PathfinderSystems::Walk)
             .with_system(melee_pathfinder_move)
     );
 }
@@ -42,8 +47,11 @@ fn melee_pathfinder_move(
         &Patrol
     ), (Without<Hurt>, Without<Die>, With<MeleePathfinder>)>,
     rapier: Res<RapierContext>,
+    grid: Res<PathfindingGrid>
 ) {
-    let mut colliding_enemies: HashSet<(Entity, Entity)> = HashSet::new();
+    // kept in the order they're found, stopping one enemy can stop the next
+    // so going through a HashSet would stop different enemies in every run
+    let mut colliding_enemies: Vec<(Entity, Entity)> = vec![];
 
     for (ent, collider, mut enemy, mut pathfinder, mut walk, mut facing, patrol) in pathfinders.iter_mut() {
         if !pathfinder.active {
@@ -78,6 +86,11 @@ fn melee_pathfinder_move(
                 facing.x = FacingX::Right;
             }
 
+            if walk_pathfinder_hazard_ahead(self_pos, dir, &pathfinder, &grid) {
+                enemy.vel.x = 0.0;
+                continue;
+            }
+
             walk_pathfinder_jump_if_needed(
                 Vec2::new(self_pos.x, self_pos.y),
                 dir.into(),
@@ -100,8 +113,8 @@ fn melee_pathfinder_move(
                     ..default()
                 },
                 |collision| {
-                    if transforms.contains(collision) {
-                        colliding_enemies.insert((ent, collision));
+                    if transforms.contains(collision) && !colliding_enemies.contains(&(ent, collision)) {
+                        colliding_enemies.push((ent, collision));
                     }
 
                     true
@@ -22,9 +22,22 @@ use crate::pathfind::grid::register_pathfinding_grid;
 use crate::state::GameState;
 use crate::player::Player;
 use crate::util::timer_tick_to_finish;
+use crate::common::UpdateStage;
 
 pub struct PathfindingPlugin;
 
+// The pathfinders run one kind after another in `UpdateStage::GameLogic`, in
+// this order, since they steer the same enemies and draw from the same random
+// numbers
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathfinderSystems {
+    Track,
+    Walk,
+    Melee,
+    Ranged,
+    Fly
+}
+
 #[derive(Component, Debug, Clone)]
 pub struct Pathfinder {
     pub active: bool,
@@ -76,7 +89,8 @@ impl Plugin for PathfindingPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_system_set(
-                SystemSet::on_update(GameState::Gameplay)
+                UpdateStage::GameLogic.on_update(GameState::Gameplay)
+                    .label(PathfinderSystems::Track)
                     .with_system(pathfind_track_player)
             )
             .add_event::<PathfinderStartChaseEvent>()
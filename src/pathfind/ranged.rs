@@ -5,13 +5,15 @@ use seldom_state::prelude::Done;
 
 use crate::combat::{CombatLayerMask, ProjectileAttackBundle};
 use crate::state::GameState;
-use crate::pathfind::{Pathfinder, WalkPathfinder, walk_pathfinder_jump_if_needed, Patrol, walk_pathfinder_get_suitable_target};
+use crate::pathfind::{Pathfinder, PathfinderSystems, WalkPathfinder, walk_pathfinder_jump_if_needed, Patrol, walk_pathfinder_get_suitable_target, walk_pathfinder_hazard_ahead};
+use crate::pathfind::grid::PathfindingGrid;
 use crate::enemies::Enemy;
 use crate::entity_states::*;
 use crate::level::door::DoorTile;
 use crate::level::one_way::OneWayTile;
 use crate::level::solid::SolidTile;
 use crate::util::{Facing, FacingX, quat_rot2d_rad};
+use crate::common::UpdateStage;
 
 #[derive(Component, Clone)]
 pub struct RangedPathfinder {
@@ -47,11 +49,13 @@ impl Default for RangedPathfinder {
 
 pub fn register_ranged_pathfinders(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PathfinderSystems::Ranged)
+            .after(PathfinderSystems::Melee)
             .with_system(ranged_pathfinder_move)
-            .with_system(ranged_pathfinder_tick_shoot_cooldown)
-            .with_system(ranged_pathfinder_add_shoot)
-            .with_system(ranged_pathfinder_shoot)
+            .with_system(ranged_pathfinder_tick_shoot_cooldown.after(ranged_pathfinder_move))
+            .with_system(ranged_pathfinder_add_shoot.after(ranged_pathfinder_tick_shoot_cooldown))
+            .with_system(ranged_pathfinder_shoot.after(ranged_pathfinder_add_shoot))
     );
 }
 
@@ -122,7 +126,8 @@ pub fn ranged_pathfinder_move(
         &mut Patrol
     ), (Without<Hurt>, Without<Shoot>, Without<Die>, With<RangedPathfinder>)>,
     one_ways: Query<Entity, With<OneWayTile>>,
-    rapier: Res<RapierContext>
+    rapier: Res<RapierContext>,
+    grid: Res<PathfindingGrid>
 ) {
     let _ = rapier;
 
@@ -175,6 +180,11 @@ pub fn ranged_pathfinder_move(
                     facing.x = FacingX::Right;
                 }
 
+                if walk_pathfinder_hazard_ahead(self_pos, dir, &pathfinder, &grid) {
+                    enemy.vel.x = 0.0;
+                    continue;
+                }
+
                 walk_pathfinder_jump_if_needed(
                     Vec2::new(self_pos.x, self_pos.y),
                     dir.into(),
@@ -6,7 +6,7 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     enemies::Enemy,
     state::GameState,
-    level::consts::SCALE_FACTOR,
+    level::{coord, consts::{RENDERED_TILE_SIZE, SCALE_FACTOR}},
     pathfind::{
         Pathfinder,
         PathfinderStopChaseEvent,
@@ -18,8 +18,11 @@ use crate::{
 };
 
 use crate::combat::HurtAbility;
-use crate::pathfind::Patrol;
+use crate::pathfind::{Patrol, PathfinderSystems};
+use crate::pathfind::grid::PathfindingGrid;
 use crate::util::{Facing, FacingX};
+use crate::rng::{GameRng, RngStream};
+use crate::common::UpdateStage;
 
 #[derive(Component, Default)]
 pub struct WalkPathfinder {
@@ -30,17 +33,19 @@ pub struct WalkPathfinder {
 
 pub fn register_walk_pathfinders(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(walk_pathfinder_patrol)
-            .with_system(walk_pathfinder_fall)
-            .with_system(walk_pathfinder_enter_fall)
-            .with_system(walk_pathfinder_jump)
-            .with_system(walk_pathfinder_hurt)
-            .with_system(walk_pathfinder_got_hurt)
-            .with_system(walk_pathfinder_hit_ground)
-            .with_system(walk_pathfinder_lose_notice)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PathfinderSystems::Walk)
+            .after(PathfinderSystems::Track)
             .with_system(walk_pathfinder_set_grounded)
-            .with_system(walk_pathfinder_just_died)
+            .with_system(walk_pathfinder_just_died.after(walk_pathfinder_set_grounded))
+            .with_system(walk_pathfinder_got_hurt.after(walk_pathfinder_just_died))
+            .with_system(walk_pathfinder_hurt.after(walk_pathfinder_got_hurt))
+            .with_system(walk_pathfinder_hit_ground.after(walk_pathfinder_hurt))
+            .with_system(walk_pathfinder_enter_fall.after(walk_pathfinder_hit_ground))
+            .with_system(walk_pathfinder_jump.after(walk_pathfinder_enter_fall))
+            .with_system(walk_pathfinder_fall.after(walk_pathfinder_jump))
+            .with_system(walk_pathfinder_lose_notice.after(walk_pathfinder_fall))
+            .with_system(walk_pathfinder_patrol.after(walk_pathfinder_lose_notice))
     );
 }
 
@@ -121,6 +126,40 @@ pub fn walk_pathfinder_needs_jump(
     ix.is_some()
 }
 
+// Whether walking on in `dir` steps into a hazard, or off a ledge with one
+// somewhere below it
+pub fn walk_pathfinder_hazard_ahead(
+    pos: Vec2,
+    dir: Vec2,
+    pathfinder: &Pathfinder,
+    grid: &PathfindingGrid
+) -> bool {
+    if dir.x == 0.0 {
+        return false;
+    }
+
+    let ahead = Vec2::new(
+        pos.x + dir.x.signum() * (pathfinder.bb.half_extents.x + RENDERED_TILE_SIZE / 2.0),
+        pos.y - pathfinder.bb.half_extents.y + RENDERED_TILE_SIZE / 2.0
+    );
+
+    let mut cell = coord::world_to_grid(ahead, grid.lvl_info.grid_size);
+
+    while cell.y < grid.lvl_info.grid_size.y as i32 {
+        if grid.hazards.contains(&cell) {
+            return true;
+        }
+
+        if grid.solids.contains(&cell) {
+            return false;
+        }
+
+        cell.y += 1;
+    }
+
+    false
+}
+
 pub fn walk_pathfinder_jump_if_needed(
     pos: Vec2,
     dir: Vec2,
@@ -146,7 +185,8 @@ fn walk_pathfinder_got_hurt(
     mut pathfinders: Query<(
         &mut Enemy,
         &mut HurtAbility
-    ), (With<WalkPathfinder>, Added<Hurt>, Without<Die>)>
+    ), (With<WalkPathfinder>, Added<Hurt>, Without<Die>)>,
+    mut rng: ResMut<GameRng>
 ) {
     for (mut enemy, mut hurt) in pathfinders.iter_mut() {
         if hurt.hit_event.is_none() {
@@ -154,7 +194,10 @@ fn walk_pathfinder_got_hurt(
         }
 
         let hit_ev = hurt.hit_event.take().unwrap();
-        let kb = kb::randomize_knockback(kb::walk_pathfinder_knockback(hit_ev.kb));
+        let kb = kb::randomize_knockback(
+            rng.stream(RngStream::Pathfinding),
+            kb::walk_pathfinder_knockback(hit_ev.kb)
+        );
 
         enemy.vel = kb;
     }
@@ -247,6 +290,8 @@ fn walk_pathfinder_patrol(
         &mut Facing,
     ), (Without<Hurt>, Without<Die>)>,
     rapier: Res<RapierContext>,
+    grid: Res<PathfindingGrid>,
+    mut rng: ResMut<GameRng>,
     _ev_stop: EventWriter<PathfinderStopChaseEvent>
 ) {
     let mut all_should_start_patrolling = false;
@@ -287,8 +332,7 @@ fn walk_pathfinder_patrol(
                     }
                 };
 
-                let mut rng = thread_rng();
-                p.target = Vec2::new(rng.gen_range(range), 0.0);
+                p.target = Vec2::new(rng.stream(RngStream::Pathfinding).gen_range(range), 0.0);
                 p.patrol_timer.reset();
             },
 
@@ -301,6 +345,13 @@ fn walk_pathfinder_patrol(
                 }
 
                 let dir = Vec2::new(p.target.x - self_pos.x, 0.0).normalize();
+
+                if walk_pathfinder_hazard_ahead(self_pos.truncate(), dir, &pathfinder, &grid) {
+                    p.patrol_pause_timer.reset();
+                    enemy.vel.x = 0.0;
+                    return;
+                }
+
                 enemy.vel.x = dir.x * pathfinder.patrol_speed;
 
                 if dir.x < 0.0 {
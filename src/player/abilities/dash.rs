@@ -14,8 +14,10 @@ use crate::combat::{AttackStrength, ColliderAttack, CombatLayerMask, HurtAbility
 use crate::entity_states::Die;
 use crate::player::abilities::autotarget;
 use crate::player::abilities::autotarget::{AttackDirection, change_facing_for_direction, direction_for_facing, direction_to_vec, Untargetable};
+use crate::player::PlayerSystems;
 use crate::player::consts::DASH_LEVELS;
 use crate::util::Facing;
+use crate::common::UpdateStage;
 
 // Ability
 
@@ -41,11 +43,13 @@ impl Default for DashAbility {
 
 pub fn register_dash_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Dash)
+            .after(PlayerSystems::Logic)
             .with_system(dash_ability_trigger)
-            .with_system(dash_ability_update)
-            .with_system(dash_ability_cooldown_update)
-            .with_system(dash_ability_remove_collider)
+            .with_system(dash_ability_update.after(dash_ability_trigger))
+            .with_system(dash_ability_cooldown_update.after(dash_ability_update))
+            .with_system(dash_ability_remove_collider.after(dash_ability_update))
     );
 }
 
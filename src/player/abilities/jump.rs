@@ -7,6 +7,7 @@ use crate::{
     state::GameState,
     player::{
         Player,
+        PlayerSystems,
         consts::{
             PLAYER_JUMP_SPEED,
             PLAYER_COYOTE_TIME,
@@ -17,6 +18,7 @@ use crate::{
     util
 };
 use crate::entity_states::Die;
+use crate::common::UpdateStage;
 
 #[derive(Component)]
 pub struct JumpAbility {
@@ -40,12 +42,14 @@ impl Default for JumpAbility {
 
 pub fn register_jump_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(jump_ability_trigger)
-            .with_system(jump_ability_request)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Jump)
+            .after(PlayerSystems::Dash)
             .with_system(jump_ability_tick_buffer)
+            .with_system(jump_ability_request.after(jump_ability_tick_buffer))
             .with_system(jump_ability_tick_coyote_time)
-            .with_system(jump_ability_reset_coyote_time)
+            .with_system(jump_ability_reset_coyote_time.after(jump_ability_tick_coyote_time))
+            .with_system(jump_ability_trigger.after(jump_ability_reset_coyote_time))
     );
 }
 
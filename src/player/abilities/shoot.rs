@@ -8,10 +8,11 @@ use crate::entity_states::Shoot;
 use crate::player::abilities::autotarget;
 use crate::player::abilities::autotarget::{attack_direction_between, AttackDirection, change_facing_for_direction, direction_for_facing, direction_to_vec, Untargetable};
 use crate::player::consts::{PLAYER_SHOOT_EXPIRATION_TIME, SHOOT_LEVELS};
-use crate::player::Player;
+use crate::player::{Player, PlayerSystems};
 use crate::state::GameState;
 use crate::anim::Animator;
 use crate::util::{Facing, quat_rot2d_rad};
+use crate::common::UpdateStage;
 
 
 #[derive(Component, Default, Debug)]
@@ -41,10 +42,12 @@ impl Default for ShootAbility {
 
 pub fn register_shoot_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Shoot)
+            .after(PlayerSystems::Slash)
             .with_system(shoot_cooldown_tick)
-            .with_system(shoot_ability_trigger)
-            .with_system(shoot_ability_update)
+            .with_system(shoot_ability_trigger.after(shoot_cooldown_tick))
+            .with_system(shoot_ability_update.after(shoot_ability_trigger))
     );
 }
 
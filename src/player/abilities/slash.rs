@@ -11,10 +11,12 @@ use crate::{
 use crate::combat::{AttackStrength, CombatLayerMask, ProjectileAttack};
 use crate::entity_states::Die;
 use crate::player::abilities::autotarget::{AttackDirection, change_facing_for_direction, direction_for_facing, get_closest_target, Untargetable};
+use crate::player::PlayerSystems;
 use crate::player::consts::SLASH_LEVELS;
 use crate::player::state_machine::Slash;
 use crate::util::{Facing, quat_rot2d_deg};
 use crate::anim::Animator;
+use crate::common::UpdateStage;
 
 // MAIN
 
@@ -40,10 +42,12 @@ impl Default for SlashAbility {
 
 pub fn register_slash_ability(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Slash)
+            .after(PlayerSystems::Jump)
             .with_system(slash_ability_trigger)
-            .with_system(slash_ability_update)
-            .with_system(slash_ability_cooldown_update)
+            .with_system(slash_ability_update.after(slash_ability_trigger))
+            .with_system(slash_ability_cooldown_update.after(slash_ability_update))
     );
 }
 
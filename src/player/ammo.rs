@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 use crate::entity_states::*;
 use crate::player::consts::AMMO_LEVELS;
-use crate::player::Player;
+use crate::player::{Player, PlayerSystems};
 use crate::state::GameState;
+use crate::common::UpdateStage;
 
 #[derive(Component, Copy, Clone, Debug)]
 pub struct Ammo {
@@ -21,7 +22,8 @@ impl Default for Ammo {
 
 pub fn register_ammo(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .after(PlayerSystems::Shoot)
             .with_system(lower_ammo_on_shoot)
     );
 }
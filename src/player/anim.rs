@@ -6,6 +6,7 @@ use crate::{
     assets::PlayerAssets,
     player::{
         Player,
+        PlayerSystems,
         state_machine::{Dash, Slash, Crouch}
     },
     entity_states::*
@@ -16,18 +17,21 @@ use crate::anim::AnimationChangeEvent;
 use crate::player::abilities::autotarget::AttackDirection;
 use crate::player::abilities::shoot::{shoot_ability_trigger, ShootAbility};
 use crate::util::Facing;
+use crate::common::UpdateStage;
 
 pub fn player_setup_anim(app: &mut App) {
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Animation)
+            .after(PlayerSystems::Shoot)
             .with_system(anim_run)
-            .with_system(anim_idle)
-            .with_system(anim_crouch)
-            .with_system(anim_slash)
-            .with_system(anim_dash)
-            .with_system(anim_fall)
-            .with_system(anim_jump)
-            .with_system(anim_shoot.after(shoot_ability_trigger))
+            .with_system(anim_idle.after(anim_run))
+            .with_system(anim_crouch.after(anim_idle))
+            .with_system(anim_slash.after(anim_crouch))
+            .with_system(anim_dash.after(anim_slash))
+            .with_system(anim_fall.after(anim_dash))
+            .with_system(anim_jump.after(anim_fall))
+            .with_system(anim_shoot.after(anim_jump).after(shoot_ability_trigger))
             // .with_system(flip_sprite_on_direction)
     );
 }
@@ -69,4 +69,7 @@ pub const SHOOT_LEVELS: [(f32, f32, i32); 6] = [
     (0.5, 13.0, 5),
     (0.4, 14.0, 7),
     (0.3, 16.0, 8),
-];
\ No newline at end of file
+];
+
+// Every skill goes from level 0 up to the last entry of its table
+pub const MAX_UPGRADE_LEVEL: u8 = (SLASH_LEVELS.len() - 1) as u8;
\ No newline at end of file
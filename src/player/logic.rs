@@ -7,6 +7,7 @@ use crate::{
     input::InputAction,
     player::{
         Player,
+        PlayerSystems,
         state_machine::*,
         consts::{
             PLAYER_FALL_GRAVITY,
@@ -23,8 +24,10 @@ use crate::anim::{AnimationChangeEvent, Animator};
 use crate::assets::PlayerAssets;
 use crate::coin::drops::CoinHolder;
 use crate::combat::{CombatLayerMask, ExplosionEvent, HurtAbility};
-use crate::common::PHYSICS_STEP_DELTA;
+use crate::common::{PHYSICS_STEP_DELTA, UpdateStage};
 use crate::fx::smoke::SmokeEvent;
+use crate::level::checkpoint::{CheckpointState, RespawnSettings};
+use crate::level::transition::LevelTransition;
 use crate::player::abilities::shoot;
 use crate::ui::menu::GotoMenuEvent;
 use crate::util::{Facing, FacingX};
@@ -41,16 +44,17 @@ pub fn player_setup_logic(app: &mut App) {
     use crate::player::abilities::{dash, slash, jump};
 
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
-            .with_system(idle)
-            .with_system(run)
-            .with_system(enter_fall)
-            .with_system(hit_ground)
+        UpdateStage::GameLogic.on_update(GameState::Gameplay)
+            .label(PlayerSystems::Logic)
             .with_system(got_hurt)
-            .with_system(start_crouch)
-            .with_system(crouch)
-            .with_system(player_died)
-            .with_system(player_despawn)
+            .with_system(hit_ground.after(got_hurt))
+            .with_system(enter_fall.after(hit_ground))
+            .with_system(start_crouch.after(enter_fall))
+            .with_system(crouch.after(start_crouch))
+            .with_system(idle.after(crouch))
+            .with_system(run.after(idle))
+            .with_system(player_died.after(run))
+            .with_system(player_despawn.after(player_died))
             .with_system(player_sync_score)
     );
 
@@ -62,11 +66,11 @@ pub fn player_setup_logic(app: &mut App) {
     app.init_resource::<PlayerScore>();
 
     app.add_system_set(
-        SystemSet::on_update(GameState::Gameplay)
+        UpdateStage::Physics.on_update(GameState::Gameplay)
             .with_run_criteria(FixedTimestep::steps_per_second(PHYSICS_STEPS_PER_SEC))
             .with_system(fall.before(physics_update))
             .with_system(physics_update)
-            .with_system(update_grounded)
+            .with_system(update_grounded.after(physics_update))
     );
 }
 
@@ -117,9 +121,13 @@ pub fn player_died(
 }
 
 pub fn player_despawn(
+    mut commands: Commands,
     mut q: Query<(Entity, &mut Die, &Animator), With<Player>>,
     mut state: ResMut<State<GameState>>,
-    trans: Res<GotoMenuEvent>
+    trans: Res<GotoMenuEvent>,
+    mut checkpoints: ResMut<CheckpointState>,
+    respawn: Res<RespawnSettings>,
+    mut level_trans: ResMut<LevelTransition>
 ) {
     if q.is_empty() {
         return;
@@ -128,9 +136,24 @@ pub fn player_despawn(
     let (e, mut die, animator) = q.single_mut();
 
     if animator.anim.name == "DEATH" && animator.total_looped == 1 {
-        if !trans.attempt {
-            die.should_despawn = true;
-            state.push(GameState::GameLostMenu).unwrap();
+        if trans.attempt {
+            return;
+        }
+
+        match checkpoints.begin_respawn(&respawn) {
+            // a fresh player gets spawned when the transition starts
+            Some(level) => {
+                commands.entity(e).despawn_recursive();
+
+                level_trans.next = level;
+                level_trans.reload = true;
+                state.set(GameState::LevelTransition).unwrap();
+            }
+
+            None => {
+                die.should_despawn = true;
+                state.push(GameState::GameLostMenu).unwrap();
+            }
         }
     }
 }
@@ -6,7 +6,6 @@ use seldom_state::prelude::StateMachine;
 use crate::anim::Animator;
 
 use crate::{
-    state::GameState,
     input::InputAction,
     assets::PlayerAssets,
     combat::ColliderAttackBundle
@@ -34,6 +33,19 @@ use crate::player::ammo::Ammo;
 use crate::player::consts::HEALTH_LEVELS;
 use crate::player::skill::{PlayerSkillLevels, upgrade_player_from_skills};
 use crate::util::Facing;
+use crate::level::LevelSystems;
+
+// The player's systems in `UpdateStage::GameLogic`, in this order: its
+// states, each ability in turn, then the animations that follow from them
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PlayerSystems {
+    Logic,
+    Dash,
+    Jump,
+    Slash,
+    Shoot,
+    Animation
+}
 
 #[derive(Bundle)]
 pub struct PlayerBundle {
@@ -83,7 +95,7 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_system_set(
-            SystemSet::on_enter(GameState::LevelTransition)
+            LevelSystems::Player.on_enter()
                 .with_system(setup_player)
         );
 
@@ -1,14 +1,15 @@
 use std::time::Duration;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::combat::Health;
 use crate::player::abilities::dash::DashAbility;
 use crate::player::abilities::shoot::ShootAbility;
 use crate::player::abilities::slash::SlashAbility;
 use crate::player::ammo::Ammo;
-use crate::player::consts::{AMMO_LEVELS, DASH_LEVELS, HEALTH_LEVELS, SHOOT_LEVELS, SLASH_LEVELS};
+use crate::player::consts::{AMMO_LEVELS, DASH_LEVELS, HEALTH_LEVELS, MAX_UPGRADE_LEVEL, SHOOT_LEVELS, SLASH_LEVELS};
 use crate::player::Player;
 
-#[derive(Copy, Clone, Default, Debug, Component, PartialEq)]
+#[derive(Copy, Clone, Default, Debug, Component, PartialEq, Serialize, Deserialize)]
 pub struct PlayerSkillLevels {
     pub dash_lvl: u8,
     pub slash_lvl: u8,
@@ -17,6 +18,20 @@ pub struct PlayerSkillLevels {
     pub health_lvl: u8,
 }
 
+impl PlayerSkillLevels {
+    // `upgrade_player_from_skills` indexes the level tables with these, so
+    // anything read back from disk goes through here first
+    pub fn clamped(&self) -> Self {
+        Self {
+            dash_lvl: self.dash_lvl.min(MAX_UPGRADE_LEVEL),
+            slash_lvl: self.slash_lvl.min(MAX_UPGRADE_LEVEL),
+            shoot_lvl: self.shoot_lvl.min(MAX_UPGRADE_LEVEL),
+            ammo_lvl: self.ammo_lvl.min(MAX_UPGRADE_LEVEL),
+            health_lvl: self.health_lvl.min(MAX_UPGRADE_LEVEL),
+        }
+    }
+}
+
 pub fn upgrade_player_from_skills(
     q: Query<&PlayerSkillLevels, Changed<PlayerSkillLevels>>,
     mut stats: Query<(
@@ -0,0 +1,624 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::time::TimeSystem;
+use leafwing_input_manager::prelude::*;
+use crate::input::{apply_scripted_input, InputAction, ScriptedInput};
+use crate::level::consts::DEFAULT_ENTRY_POINT;
+use crate::level::transition::LevelTransition;
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::save::save_dir;
+use crate::shop::ShopPurchaseEvent;
+use crate::shop::purchase::shop_apply_purchases;
+use crate::shop::stock::{ShopItem, SHOP_CATALOG_ALL};
+use crate::state::GameState;
+use crate::ui::menu::GotoMenuEvent;
+
+// A replay is the seed and starting level of a run, plus which actions were
+// held on every frame the game was simulating (gameplay and level
+// transitions, menus don't count) and how long that frame took. Shop menu
+// purchases are stored separately, tagged with the frame they happened
+// before. Frames are run-length encoded on disk, so holding a direction for
+// a few seconds only takes up a single entry.
+//
+// File layout, all little endian:
+//   "SBRP", version: u16, seed: u64, level length: u16, level: utf8
+//   run count: u32, then per run: pressed: u16, dt in microseconds: u32, length: u32
+//   purchase count: u32, then per purchase: frame: u32, item: u8, cost: i32
+
+pub const REPLAY_MAGIC: &[u8; 4] = b"SBRP";
+
+// Bump this whenever the layout above changes
+pub const REPLAY_VERSION: u16 = 1;
+
+// How long the main menu sits untouched before it starts playing the last
+// run as a demo
+pub const DEMO_IDLE_SECS: f32 = 20.0;
+
+// Every run is recorded here unless `--record` picks another file
+pub fn last_run_path() -> PathBuf {
+    save_dir().join("last_run.replay")
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ReplayFrame {
+    // bit i is set when the i-th variant of `InputAction` was held
+    pub pressed: u16,
+    pub dt_micros: u32
+}
+
+impl ReplayFrame {
+    pub fn dt(&self) -> Duration {
+        Duration::from_micros(self.dt_micros as u64)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ReplayPurchase {
+    pub frame: u32,
+    pub order: ShopItem,
+    pub cost: i32
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub level: String,
+    pub frames: Vec<ReplayFrame>,
+    pub purchases: Vec<ReplayPurchase>
+}
+
+pub fn pressed_mask(actions: &ActionState<InputAction>) -> u16 {
+    let mut mask = 0;
+
+    for (i, action) in InputAction::variants().enumerate() {
+        if actions.pressed(action) {
+            mask |= 1 << i;
+        }
+    }
+
+    mask
+}
+
+pub fn actions_from_mask(mask: u16) -> impl Iterator<Item = InputAction> {
+    InputAction::variants()
+        .enumerate()
+        .filter(move |(i, _)| mask & (1 << i) != 0)
+        .map(|(_, action)| action)
+}
+
+fn item_index(item: ShopItem) -> u8 {
+    SHOP_CATALOG_ALL.iter().position(|i| *i == item).unwrap() as u8
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.pos + n > self.bytes.len() {
+            return Err(format!("unexpected end of file at byte {}", self.pos));
+        }
+
+        let slice = &self.bytes[self.pos..(self.pos + n)];
+        self.pos += n;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
+
+impl Replay {
+    pub fn new(seed: u64, level: String) -> Self {
+        Self {
+            seed,
+            level,
+            frames: vec![],
+            purchases: vec![]
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut runs: Vec<(ReplayFrame, u32)> = vec![];
+        for frame in self.frames.iter() {
+            match runs.last_mut() {
+                Some((last, len)) if last == frame => *len += 1,
+                _ => runs.push((*frame, 1))
+            }
+        }
+
+        let mut out = vec![];
+        out.extend_from_slice(REPLAY_MAGIC);
+        out.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.seed.to_le_bytes());
+
+        out.extend_from_slice(&(self.level.len() as u16).to_le_bytes());
+        out.extend_from_slice(self.level.as_bytes());
+
+        out.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for (frame, len) in runs {
+            out.extend_from_slice(&frame.pressed.to_le_bytes());
+            out.extend_from_slice(&frame.dt_micros.to_le_bytes());
+            out.extend_from_slice(&len.to_le_bytes());
+        }
+
+        out.extend_from_slice(&(self.purchases.len() as u32).to_le_bytes());
+        for purchase in self.purchases.iter() {
+            out.extend_from_slice(&purchase.frame.to_le_bytes());
+            out.push(item_index(purchase.order));
+            out.extend_from_slice(&purchase.cost.to_le_bytes());
+        }
+
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let mut r = Reader { bytes, pos: 0 };
+
+        if r.take(4)? != REPLAY_MAGIC {
+            return Err("not a replay file".to_string());
+        }
+
+        let version = r.u16()?;
+        if version != REPLAY_VERSION {
+            return Err(format!("replay version {} (expected {})", version, REPLAY_VERSION));
+        }
+
+        let seed = r.u64()?;
+
+        let level_len = r.u16()? as usize;
+        let level = String::from_utf8(r.take(level_len)?.to_vec())
+            .map_err(|e| format!("bad level name: {}", e))?;
+
+        let mut replay = Replay::new(seed, level);
+
+        let run_count = r.u32()?;
+        for _ in 0..run_count {
+            let frame = ReplayFrame {
+                pressed: r.u16()?,
+                dt_micros: r.u32()?
+            };
+
+            let len = r.u32()?;
+            replay.frames.extend(std::iter::repeat(frame).take(len as usize));
+        }
+
+        let purchase_count = r.u32()?;
+        for _ in 0..purchase_count {
+            let frame = r.u32()?;
+
+            let item = r.u8()? as usize;
+            let order = match SHOP_CATALOG_ALL.get(item) {
+                Some(order) => *order,
+                None => return Err(format!("unknown shop item {}", item))
+            };
+
+            let cost = r.i32()?;
+
+            replay.purchases.push(ReplayPurchase { frame, order, cost });
+        }
+
+        Ok(replay)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        Self::decode(&bytes)
+    }
+
+    pub fn write(&self, path: &Path) {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+
+        match fs::write(path, self.encode()) {
+            Ok(_) => info!("Saved replay of {} frames to {:?}", self.frames.len(), path),
+            Err(e) => error!("Failed to write replay {:?}: {}", path, e)
+        }
+    }
+}
+
+// Records a replay of every run while `enabled`, written to `path` (if
+// there is one) once the run is over
+#[derive(Resource, Default, Debug)]
+pub struct ReplayRecorder {
+    pub enabled: bool,
+    pub path: Option<PathBuf>,
+    pub recording: Option<Replay>
+}
+
+// Drives the player with `replay` instead of the keyboard. The run is
+// started from the main menu, or directly by the headless app.
+#[derive(Resource, Default, Debug)]
+pub struct ReplayPlayback {
+    pub replay: Option<Replay>,
+    pub frame: usize,
+    pub started: bool,
+    // played from the main menu, any key goes back to it
+    pub demo: bool
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay: Some(replay),
+            ..default()
+        }
+    }
+
+    pub fn next_frame(&self) -> Option<ReplayFrame> {
+        self.replay.as_ref().and_then(|r| r.frames.get(self.frame).copied())
+    }
+
+    pub fn finished(&self) -> bool {
+        match &self.replay {
+            Some(replay) => self.frame >= replay.frames.len(),
+            None => true
+        }
+    }
+
+    // Whether the replay is what's driving the game right now
+    pub fn playing(&self) -> bool {
+        self.started && !self.finished()
+    }
+}
+
+// Playback sets the scripted input for a frame before it's applied, the
+// recording reads the actions back after, so both see the same frame
+#[derive(SystemLabel, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReplaySystems {
+    Drive,
+    Record
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<ReplayPlayback>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                drive_replay
+                    .label(ReplaySystems::Drive)
+                    .before(apply_scripted_input)
+            )
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                record_frame
+                    .label(ReplaySystems::Record)
+                    .after(apply_scripted_input)
+            )
+            .add_system(record_purchases.after(shop_apply_purchases))
+            .add_system_set(
+                SystemSet::on_enter(GameState::LevelTransition)
+                    .with_system(start_recording)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MainMenu)
+                    .with_system(start_replay)
+            );
+
+        for state in [GameState::MainMenu, GameState::GameWonMenu, GameState::GameLostMenu] {
+            app.add_system_set(SystemSet::on_enter(state).with_system(finish_recording));
+        }
+    }
+}
+
+// What playback needs in the windowed game, where time normally comes from
+// the wall clock: the recorded frame times, and demos on the main menu. The
+// headless app steps with the recorded times itself.
+pub struct ReplayWindowPlugin;
+
+impl Plugin for ReplayWindowPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DemoTimer>()
+            .add_system_to_stage(CoreStage::First, replay_clock.after(TimeSystem))
+            .add_system(leave_demo)
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainMenu)
+                    .with_system(end_demo)
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MainMenu)
+                    .with_system(start_demo.before(start_replay))
+            );
+    }
+}
+
+#[derive(Resource)]
+struct DemoTimer(Timer);
+
+impl Default for DemoTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(DEMO_IDLE_SECS, TimerMode::Once))
+    }
+}
+
+fn is_simulating(state: &GameState) -> bool {
+    matches!(state, GameState::Gameplay | GameState::LevelTransition)
+}
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    playback: Res<ReplayPlayback>,
+    rng: Res<GameRng>,
+    trans: Res<LevelTransition>
+) {
+    // a replay of a replay is the same file again
+    if !recorder.enabled || recorder.recording.is_some() || playback.started {
+        return;
+    }
+
+    info!("Recording a replay starting at {} with seed {}", trans.next, rng.seed());
+    recorder.recording = Some(Replay::new(rng.seed(), trans.next.clone()));
+}
+
+fn record_frame(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    mut recorder: ResMut<ReplayRecorder>,
+    player: Query<&ActionState<InputAction>, With<Player>>
+) {
+    if !is_simulating(state.current()) {
+        return;
+    }
+
+    let recording = match recorder.recording.as_mut() {
+        Some(r) => r,
+        None => return
+    };
+
+    // the player is only spawned a frame into the first transition
+    let pressed = player.iter().next().map(pressed_mask).unwrap_or(0);
+
+    recording.frames.push(ReplayFrame {
+        pressed,
+        dt_micros: time.delta().as_micros() as u32
+    });
+}
+
+fn record_purchases(
+    mut purchases: EventReader<ShopPurchaseEvent>,
+    mut recorder: ResMut<ReplayRecorder>
+) {
+    let recording = match recorder.recording.as_mut() {
+        Some(r) => r,
+        None => {
+            purchases.clear();
+            return;
+        }
+    };
+
+    for purchase in purchases.iter() {
+        recording.purchases.push(ReplayPurchase {
+            frame: recording.frames.len() as u32,
+            order: purchase.order,
+            cost: purchase.cost
+        });
+    }
+}
+
+fn finish_recording(mut recorder: ResMut<ReplayRecorder>) {
+    let recording = match recorder.recording.take() {
+        Some(r) => r,
+        None => return
+    };
+
+    if let Some(path) = recorder.path.as_ref() {
+        recording.write(path);
+    }
+}
+
+fn start_replay(
+    mut playback: ResMut<ReplayPlayback>,
+    mut rng: ResMut<GameRng>,
+    mut trans: ResMut<LevelTransition>,
+    mut state: ResMut<State<GameState>>
+) {
+    if playback.started {
+        return;
+    }
+
+    let replay = match playback.replay.as_ref() {
+        Some(r) => r,
+        None => return
+    };
+
+    info!("Playing back a replay of {} frames from {}", replay.frames.len(), replay.level);
+
+    rng.reseed(replay.seed);
+    trans.next = replay.level.clone();
+    trans.entry_point = DEFAULT_ENTRY_POINT;
+    playback.started = true;
+
+    state.overwrite_set(GameState::LevelTransition).unwrap();
+}
+
+fn drive_replay(
+    mut playback: ResMut<ReplayPlayback>,
+    mut script: ResMut<ScriptedInput>,
+    mut state: ResMut<State<GameState>>,
+    mut purchases: EventWriter<ShopPurchaseEvent>
+) {
+    // once the replay runs out the keyboard takes over, shop included
+    if !playback.started || playback.replay.is_none() || !script.enabled && playback.finished() {
+        return;
+    }
+
+    let frame = playback.frame;
+
+    // the shop only opens on the frame after the player interacts with it,
+    // replay everything bought there and close it straight away
+    if *state.current() == GameState::ShopMenu {
+        let replay = playback.replay.as_ref().unwrap();
+
+        for purchase in replay.purchases.iter().filter(|p| p.frame as usize == frame) {
+            purchases.send(ShopPurchaseEvent {
+                cost: purchase.cost,
+                order: purchase.order
+            });
+        }
+
+        state.pop().unwrap();
+        return;
+    }
+
+    if !is_simulating(state.current()) {
+        return;
+    }
+
+    match playback.next_frame() {
+        Some(next) => {
+            script.enabled = true;
+            script.pressed = actions_from_mask(next.pressed).collect();
+            playback.frame += 1;
+        }
+
+        None => {
+            info!("Replay finished");
+            script.enabled = false;
+            script.pressed.clear();
+        }
+    }
+}
+
+// Steps time by the recorded frame times instead of the real ones. Runs after
+// Bevy's own time update, which this overrides: `update_with_instant` takes
+// the delta from the last update, so moving that on by `dt` makes `dt` the
+// delta everything else sees this frame.
+fn replay_clock(
+    playback: Res<ReplayPlayback>,
+    state: Res<State<GameState>>,
+    mut time: ResMut<Time>
+) {
+    if !playback.playing() || !is_simulating(state.current()) {
+        return;
+    }
+
+    let (next, last) = match (playback.next_frame(), time.last_update()) {
+        (Some(next), Some(last)) => (next, last),
+        _ => return
+    };
+
+    time.update_with_instant(last + next.dt());
+}
+
+fn any_input(
+    keys: &Input<KeyCode>,
+    mouse: &Input<MouseButton>,
+    gamepad: &Input<GamepadButton>
+) -> bool {
+    keys.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || gamepad.get_just_pressed().next().is_some()
+}
+
+fn start_demo(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad: Res<Input<GamepadButton>>,
+    mut timer: ResMut<DemoTimer>,
+    mut playback: ResMut<ReplayPlayback>
+) {
+    if playback.replay.is_some() || any_input(&keys, &mouse, &gamepad) {
+        timer.0.reset();
+        return;
+    }
+
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    let path = last_run_path();
+    if !path.exists() {
+        return;
+    }
+
+    match Replay::load(&path) {
+        Ok(replay) => {
+            // `start_replay` takes it from here
+            *playback = ReplayPlayback {
+                demo: true,
+                ..ReplayPlayback::new(replay)
+            };
+        }
+
+        Err(e) => warn!("Not playing a demo, failed to load {:?}: {}", path, e)
+    }
+}
+
+// Back to the menu once the demo runs out, or on any key
+fn leave_demo(
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad: Res<Input<GamepadButton>>,
+    playback: Res<ReplayPlayback>,
+    state: Res<State<GameState>>,
+    mut goto: ResMut<GotoMenuEvent>
+) {
+    if !playback.demo || !playback.started || *state.current() == GameState::MainMenu {
+        return;
+    }
+
+    let over = matches!(state.current(), GameState::GameWonMenu | GameState::GameLostMenu);
+
+    if over || playback.finished() || any_input(&keys, &mouse, &gamepad) {
+        goto.attempt = true;
+    }
+}
+
+fn end_demo(
+    mut playback: ResMut<ReplayPlayback>,
+    mut script: ResMut<ScriptedInput>,
+    mut timer: ResMut<DemoTimer>
+) {
+    timer.0.reset();
+
+    if !playback.demo {
+        return;
+    }
+
+    *playback = ReplayPlayback::default();
+    script.enabled = false;
+    script.pressed.clear();
+}
+
+// `--record path/to/run.replay` and `--replay path/to/run.replay`
+pub fn replay_args() -> (Option<PathBuf>, Option<PathBuf>) {
+    let args: Vec<String> = std::env::args().collect();
+
+    let find = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(PathBuf::from)
+    };
+
+    (find("--record"), find("--replay"))
+}
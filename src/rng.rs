@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+// All gameplay randomness comes from here, so a run can be reproduced from
+// its seed. Each subsystem draws from its own stream, which means that e.g.
+// spore particles pulling a few extra numbers doesn't change where coins land.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    Enemies,
+    Coins,
+    Pathfinding,
+    Boss,
+    Effects,
+    // screen shake and the like, which only run with a window. Kept away from
+    // everything else so a headless replay draws the same numbers.
+    Presentation,
+}
+
+#[derive(Resource, Debug)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, StdRng>
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(random_seed())
+    }
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: HashMap::new()
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.restart();
+    }
+
+    // Rewinds every stream back to the start of the seed, done whenever a
+    // new run begins
+    pub fn restart(&mut self) {
+        self.streams.clear();
+    }
+
+    // Streams are created the first time they're used, so the order the
+    // subsystems first ask for them in doesn't matter
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = stream_seed(self.seed, stream);
+        self.streams
+            .entry(stream)
+            .or_insert_with(|| StdRng::seed_from_u64(seed))
+    }
+}
+
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    seed ^ (stream as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+}
+
+// Kept small so it's easy to read off the menu and type back in
+pub fn random_seed() -> u64 {
+    thread_rng().gen::<u32>() as u64
+}
+
+// `--seed 1234` or `--seed=1234`
+pub fn seed_from_args() -> Option<u64> {
+    let args: Vec<String> = std::env::args().collect();
+
+    for (i, arg) in args.iter().enumerate() {
+        let value = if arg == "--seed" {
+            args.get(i + 1).cloned()
+        } else if let Some(value) = arg.strip_prefix("--seed=") {
+            Some(value.to_string())
+        } else {
+            continue;
+        };
+
+        match value.as_deref().map(str::parse::<u64>) {
+            Some(Ok(seed)) => return Some(seed),
+            _ => {
+                warn!("Invalid --seed argument {:?}, using a random seed", value);
+                return None;
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+use crate::bossfight::Boss;
+use crate::bossfight::stage::BossStage;
+use crate::coin::drops::CoinHolder;
+use crate::coin::pickup::CoinPickupEvent;
+use crate::combat::{CombatEvent, handle_hits, HurtAbility, Immunity};
+use crate::enemies::spawner::EnemyType;
+use crate::entity_states::{Die, Hurt};
+use crate::level::transition::{LevelTransition, TransitionSetupEvent};
+use crate::player::Player;
+use crate::shop::purchase::shop_apply_purchases;
+use crate::shop::ShopPurchaseEvent;
+use crate::state::GameState;
+use crate::common::UpdateStage;
+use crate::level::LevelSystems;
+
+// Everything that happened during the current run, cleared whenever the
+// main menu opens. Read by the win and lose screens and the leaderboard.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct RunStats {
+    // seconds spent in gameplay on each level, in the order they were played
+    pub level_times: Vec<(String, f32)>,
+    pub deaths: u32,
+    pub kills: HashMap<EnemyType, u32>,
+
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+
+    pub coins_earned: i32,
+    pub coins_spent: i32,
+
+    // counts from the boss's first stage change until it dies
+    pub boss_fight_time: f32,
+    pub boss_defeated: bool
+}
+
+impl RunStats {
+    pub fn total_time(&self) -> f32 {
+        self.level_times.iter().map(|(_, t)| t).sum()
+    }
+
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
+
+    pub fn last_level(&self) -> Option<&str> {
+        self.level_times.last().map(|(level, _)| level.as_str())
+    }
+}
+
+// 83.4 -> "1:23"
+pub fn format_time(secs: f32) -> String {
+    let secs = secs as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+pub struct RunStatsPlugin;
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<RunStats>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainMenu)
+                    .with_system(reset_run_stats)
+            )
+            .add_system_set(
+                LevelSystems::Stats.on_update()
+                    .with_system(track_level_start)
+            )
+            .add_system_set(
+                UpdateStage::Combat.on_update(GameState::Gameplay)
+                    .with_system(track_level_time)
+                    .with_system(track_deaths)
+                    .with_system(track_damage.before(handle_hits))
+                    .with_system(track_coins_earned)
+                    .with_system(track_boss_fight)
+            )
+            .add_system(track_coins_spent.before(shop_apply_purchases));
+    }
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+fn track_level_start(
+    mut setup: EventReader<TransitionSetupEvent>,
+    trans: Res<LevelTransition>,
+    mut stats: ResMut<RunStats>
+) {
+    for ev in setup.iter() {
+        // a respawn keeps counting towards the level it happened in
+        if trans.reload || ev.new_level == "Init" || ev.new_level == "None" {
+            continue;
+        }
+
+        stats.level_times.push((ev.new_level.clone(), 0.0));
+    }
+}
+
+fn track_level_time(time: Res<Time>, mut stats: ResMut<RunStats>) {
+    if let Some((_, t)) = stats.level_times.last_mut() {
+        *t += time.delta_seconds();
+    }
+}
+
+fn track_deaths(
+    mut stats: ResMut<RunStats>,
+    players: Query<Entity, (With<Player>, Added<Die>)>,
+    enemies: Query<&EnemyType, Added<Die>>
+) {
+    stats.deaths += players.iter().count() as u32;
+
+    for ty in enemies.iter() {
+        *stats.kills.entry(*ty).or_insert(0) += 1;
+    }
+}
+
+// Only counts the hits `handle_hits` is going to apply: the first one on a
+// target each frame, and none on targets that are immune, hurt or dead
+fn track_damage(
+    mut stats: ResMut<RunStats>,
+    mut hits: EventReader<CombatEvent>,
+    targets: Query<(&HurtAbility, Option<&Immunity>), (Without<Hurt>, Without<Die>)>,
+    players: Query<Entity, With<Player>>
+) {
+    let mut hit_this_frame = HashSet::new();
+
+    for hit in hits.iter() {
+        let (hurt, immunity) = match targets.get(hit.target) {
+            Ok(t) => t,
+            Err(_) => continue
+        };
+
+        if hurt.hit_event.is_some() || immunity.map(|i| i.is_immune).unwrap_or(false) {
+            continue;
+        }
+
+        if !hit_this_frame.insert(hit.target) {
+            continue;
+        }
+
+        if players.contains(hit.target) {
+            stats.damage_taken += hit.damage.abs();
+        } else {
+            stats.damage_dealt += hit.damage.abs();
+        }
+    }
+}
+
+fn track_coins_earned(
+    mut stats: ResMut<RunStats>,
+    mut pickups: EventReader<CoinPickupEvent>,
+    players: Query<Entity, With<Player>>
+) {
+    for pickup in pickups.iter() {
+        if players.contains(pickup.collector) {
+            stats.coins_earned += pickup.value;
+        }
+    }
+}
+
+// Same affordability check as `shop_apply_purchases`, which skips anything
+// the player can't pay for
+fn track_coins_spent(
+    mut stats: ResMut<RunStats>,
+    mut purchases: EventReader<ShopPurchaseEvent>,
+    coins: Query<&CoinHolder, With<Player>>
+) {
+    if coins.is_empty() {
+        purchases.clear();
+        return;
+    }
+
+    let mut allowance = coins.single().total_value;
+
+    for buy in purchases.iter() {
+        if buy.cost > allowance {
+            continue;
+        }
+
+        allowance -= buy.cost;
+        stats.coins_spent += buy.cost;
+    }
+}
+
+fn track_boss_fight(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    bosses: Query<(&BossStage, Option<&Die>), With<Boss>>
+) {
+    for (stage, die) in bosses.iter() {
+        if die.is_some() {
+            stats.boss_defeated = true;
+            continue;
+        }
+
+        if *stage != BossStage::Waiting {
+            stats.boss_fight_time += time.delta_seconds();
+        }
+    }
+}
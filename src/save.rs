@@ -0,0 +1,252 @@
+use std::fs;
+use std::path::PathBuf;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::coin::drops::CoinHolder;
+use crate::combat::Health;
+use crate::level::transition::{LevelTransition, TransitionSetupEvent};
+use crate::player::ammo::Ammo;
+use crate::player::Player;
+use crate::player::skill::PlayerSkillLevels;
+use crate::replay::ReplayPlayback;
+use crate::state::GameState;
+
+// Bump this whenever the layout of `SaveData` changes, old saves get ignored
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SaveData {
+    pub version: u32,
+    pub level: String,
+
+    pub skills: PlayerSkillLevels,
+    pub coins: i32,
+
+    pub hp: i32,
+    pub max_hp: i32,
+
+    pub rounds_left: u32,
+    pub max_rounds: u32,
+}
+
+// A save that was picked from the main menu, applied to the player once
+// the level it points to starts loading
+#[derive(Resource, Default, Debug)]
+pub struct PendingSave(pub Option<SaveData>);
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PendingSave>()
+            .add_system_set(
+                SystemSet::on_update(GameState::LevelTransition)
+                    .with_system(apply_pending_save)
+                    .with_system(save_on_transition.after(apply_pending_save))
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::GameWonMenu)
+                    .with_system(delete_save_on_win)
+            );
+    }
+}
+
+pub fn save_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("shroom_boom")
+}
+
+pub fn save_file_path() -> PathBuf {
+    save_dir().join("save.ron")
+}
+
+impl SaveData {
+    // Also what checkpoints keep, see level/checkpoint.rs
+    pub fn capture(
+        level: &str,
+        skills: &PlayerSkillLevels,
+        coins: &CoinHolder,
+        health: &Health,
+        ammo: &Ammo
+    ) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            level: level.to_string(),
+
+            skills: *skills,
+            coins: coins.total_value,
+
+            hp: health.hp,
+            max_hp: health.max_hp,
+
+            rounds_left: ammo.rounds_left,
+            max_rounds: ammo.max_rounds,
+        }
+    }
+
+    pub fn apply(
+        &self,
+        skills: &mut PlayerSkillLevels,
+        coins: &mut CoinHolder,
+        health: &mut Health,
+        ammo: &mut Ammo
+    ) {
+        // a hand-edited or old save can have levels past the last upgrade
+        *skills = self.skills.clamped();
+        coins.total_value = self.coins;
+
+        health.max_hp = self.max_hp;
+        health.hp = self.hp;
+
+        ammo.max_rounds = self.max_rounds;
+        ammo.rounds_left = self.rounds_left;
+    }
+
+    pub fn exists() -> bool {
+        Self::load().is_some()
+    }
+
+    pub fn load() -> Option<Self> {
+        let path = save_file_path();
+        let text = fs::read_to_string(&path).ok()?;
+
+        let data: SaveData = match ron::from_str(&text) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to parse save file {:?}: {}", path, e);
+                return None;
+            }
+        };
+
+        if data.version != SAVE_VERSION {
+            warn!(
+                "Ignoring save file {:?} with version {} (expected {})",
+                path, data.version, SAVE_VERSION
+            );
+            return None;
+        }
+
+        Some(data)
+    }
+
+    pub fn write(&self) {
+        let path = save_file_path();
+
+        if let Err(e) = fs::create_dir_all(save_dir()) {
+            error!("Failed to create save directory {:?}: {}", save_dir(), e);
+            return;
+        }
+
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).unwrap();
+
+        if let Err(e) = fs::write(&path, text) {
+            error!("Failed to write save file {:?}: {}", path, e);
+        }
+    }
+
+    pub fn delete() {
+        let _ = fs::remove_file(save_file_path());
+    }
+}
+
+fn is_saveable_level(level: &str) -> bool {
+    level != "Init" && level != "None"
+}
+
+pub fn apply_pending_save(
+    setup: EventReader<TransitionSetupEvent>,
+    mut pending: ResMut<PendingSave>,
+    mut player: Query<(
+        &mut PlayerSkillLevels,
+        &mut CoinHolder,
+        &mut Health,
+        &mut Ammo
+    ), With<Player>>
+) {
+    if setup.is_empty() || player.is_empty() {
+        return;
+    }
+
+    setup.clear();
+
+    let save = match pending.0.take() {
+        Some(save) => save,
+        None => return
+    };
+
+    let (mut skills, mut coins, mut health, mut ammo) = player.single_mut();
+    save.apply(&mut skills, &mut coins, &mut health, &mut ammo);
+
+    info!("Loaded save for level {}", save.level);
+}
+
+pub fn save_on_transition(
+    mut setup: EventReader<TransitionSetupEvent>,
+    player: Query<(
+        &PlayerSkillLevels,
+        &CoinHolder,
+        &Health,
+        &Ammo
+    ), With<Player>>,
+    trans: Res<LevelTransition>,
+    playback: Res<ReplayPlayback>
+) {
+    if setup.is_empty() || player.is_empty() {
+        return;
+    }
+
+    // replays and demos don't touch the player's own save
+    if playback.started {
+        setup.clear();
+        return;
+    }
+
+    // respawning at a checkpoint, the save from entering the level stays
+    if trans.reload {
+        setup.clear();
+        return;
+    }
+
+    let (skills, coins, health, ammo) = player.single();
+
+    for ev in setup.iter() {
+        if !is_saveable_level(&ev.new_level) {
+            continue;
+        }
+
+        SaveData::capture(&ev.new_level, skills, coins, health, ammo).write();
+    }
+}
+
+fn delete_save_on_win(playback: Res<ReplayPlayback>) {
+    if !playback.started {
+        SaveData::delete();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::consts::{HEALTH_LEVELS, MAX_UPGRADE_LEVEL};
+
+    #[test]
+    fn apply_clamps_skill_levels() {
+        let mut data = SaveData::capture(
+            "Level_0",
+            &PlayerSkillLevels::default(),
+            &CoinHolder::default(),
+            &Health::new(HEALTH_LEVELS[0]),
+            &Ammo::default()
+        );
+        data.skills.slash_lvl = 200;
+        data.skills.dash_lvl = 2;
+
+        let mut skills = PlayerSkillLevels::default();
+        data.apply(&mut skills, &mut CoinHolder::default(), &mut Health::new(1), &mut Ammo::default());
+
+        assert_eq!(skills.slash_lvl, MAX_UPGRADE_LEVEL);
+        assert_eq!(skills.dash_lvl, 2);
+    }
+}
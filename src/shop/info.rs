@@ -2,8 +2,8 @@ use bevy::prelude::*;
 use crate::assets::ShopAssets;
 use crate::shop::stock::ShopItem;
 
-
-// Without killing optional enemies, players can get 1365 coins
+// Run `cargo run --bin economy_report` to see how these prices compare to
+// what the levels pay out
 
 #[derive(Clone, Component)]
 pub struct ShopItemInfo {
@@ -18,37 +18,37 @@ impl ShopItemInfo {
             // ITEMS
 
             ShopItem::WaterCupItem => Self {
-                cost: 10,
+                cost: item_cost(order, lvl),
                 name: "Water Cup",
                 icon: assets.waters[0].clone(),
             },
 
             ShopItem::WaterBucketItem => Self {
-                cost: 15,
+                cost: item_cost(order, lvl),
                 name: "Water Bucket",
                 icon: assets.waters[1].clone(),
             },
 
             ShopItem::WaterTankItem => Self {
-                cost: 20,
+                cost: item_cost(order, lvl),
                 name: "Water Tank",
                 icon: assets.waters[2].clone(),
             },
 
             ShopItem::OddTonicItem => Self {
-                cost: 10,
+                cost: item_cost(order, lvl),
                 name: "Odd Tonic",
                 icon: assets.tonics[0].clone(),
             },
 
             ShopItem::StrangeTonicItem => Self {
-                cost: 15,
+                cost: item_cost(order, lvl),
                 name: "Strange Tonic",
                 icon: assets.tonics[1].clone(),
             },
 
             ShopItem::BizarreTonicItem => Self {
-                cost: 20,
+                cost: item_cost(order, lvl),
                 name: "Bizarre Tonic",
                 icon: assets.tonics[2].clone(),
             },
@@ -56,31 +56,31 @@ impl ShopItemInfo {
             // UPGRADES
 
             ShopItem::HealthUpgrade => Self {
-                cost: cost_for_upgrading(lvl.unwrap()),
+                cost: item_cost(order, lvl),
                 name: "Health",
                 icon: assets.health_up.clone(),
             },
 
             ShopItem::AmmoUpgrade => Self {
-                cost: cost_for_upgrading(lvl.unwrap()),
+                cost: item_cost(order, lvl),
                 name: "Ammo",
                 icon: assets.ammo_up.clone(),
             },
 
             ShopItem::SlashUpgrade => Self {
-                cost: cost_for_upgrading(lvl.unwrap()),
+                cost: item_cost(order, lvl),
                 name: "Slash",
                 icon: assets.slash_up.clone(),
             },
 
             ShopItem::DashUpgrade => Self {
-                cost: cost_for_upgrading(lvl.unwrap()),
+                cost: item_cost(order, lvl),
                 name: "Dash",
                 icon: assets.dash_up.clone(),
             },
 
             ShopItem::ShootUpgrade => Self {
-                cost: cost_for_upgrading(lvl.unwrap()),
+                cost: item_cost(order, lvl),
                 name: "Shoot",
                 icon: assets.shoot_up.clone(),
             },
@@ -88,7 +88,25 @@ impl ShopItemInfo {
     }
 }
 
-fn cost_for_upgrading(lvl: u8) -> i32 {
+pub fn item_cost(order: ShopItem, lvl: Option<u8>) -> i32 {
+    match order {
+        ShopItem::WaterCupItem => 10,
+        ShopItem::WaterBucketItem => 15,
+        ShopItem::WaterTankItem => 20,
+
+        ShopItem::OddTonicItem => 10,
+        ShopItem::StrangeTonicItem => 15,
+        ShopItem::BizarreTonicItem => 20,
+
+        ShopItem::HealthUpgrade
+        | ShopItem::AmmoUpgrade
+        | ShopItem::SlashUpgrade
+        | ShopItem::DashUpgrade
+        | ShopItem::ShootUpgrade => cost_for_upgrading(lvl.unwrap())
+    }
+}
+
+pub fn cost_for_upgrading(lvl: u8) -> i32 {
     match lvl {
         0 => 10,
         1 => 20,
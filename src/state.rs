@@ -8,5 +8,7 @@ pub enum GameState {
     Gameplay,
     LevelTransition,
     GameWonMenu,
-    GameLostMenu
+    GameLostMenu,
+    SettingsMenu,
+    LeaderboardMenu
 }
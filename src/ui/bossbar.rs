@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use crate::assets::BossAssets;
 use crate::bossfight::Boss;
+use crate::camera::VIEW_SIZE;
 use crate::bossfight::consts::{BOSS_EASY_HEALTH_THRESHOLD, BOSS_HARD_HEALTH_THRESHOLD, BOSS_HEALTH, BOSS_MEDIUM_HEALTH_THRESHOLD};
 use crate::bossfight::stage::BossStage;
 use crate::combat::Health;
@@ -35,7 +36,7 @@ fn spawn_bossbar(
 
     let id = commands.spawn(NodeBundle {
         style: Style {
-            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            size: Size::new(Val::Px(VIEW_SIZE.x), Val::Px(VIEW_SIZE.y)),
             position_type: PositionType::Absolute,
             justify_content: JustifyContent::FlexEnd,
             flex_direction: FlexDirection::Column,
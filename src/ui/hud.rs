@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
 use crate::assets::UiAssets;
+use crate::camera::{GameViewport, VIEW_SIZE};
 use crate::coin::drops::CoinHolder;
 use crate::combat::Health;
 use crate::player::abilities::dash::DashAbility;
@@ -54,6 +55,7 @@ pub fn register_hud_ui_systems(app: &mut App) {
         )
         .add_system_set(
             SystemSet::new()
+                .with_system(scale_hud_to_viewport)
                 .with_system(sync_hud)
                 .with_system(sync_hud_cooldowns)
         );
@@ -73,7 +75,8 @@ fn setup_hud(
     let entity = commands
         .spawn(NodeBundle {
             style: Style {
-                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                size: Size::new(Val::Px(VIEW_SIZE.x), Val::Px(VIEW_SIZE.y)),
+                position_type: PositionType::Absolute,
                 justify_content: JustifyContent::SpaceBetween,
                 ..default()
             },
@@ -207,6 +210,31 @@ fn setup_hud(
     hud.entity = entity;
 }
 
+// The HUD is laid out for a `VIEW_SIZE` window, and scaled with the game so it
+// stays inside the letterboxing and its pixels line up with the level's
+fn scale_hud_to_viewport(
+    viewport: Res<GameViewport>,
+    hud: Res<Hud>,
+    mut ui_scale: ResMut<UiScale>,
+    mut style: Query<&mut Style>
+) {
+    if !(viewport.is_changed() || hud.is_changed()) || viewport.scale == 0 {
+        return;
+    }
+
+    let scale = viewport.size.x / VIEW_SIZE.x;
+    ui_scale.scale = scale as f64;
+
+    // `UiScale` scales positions too, so the offset is in HUD pixels
+    if let Ok(mut style) = style.get_mut(hud.entity) {
+        style.position = UiRect {
+            left: Val::Px(viewport.pos.x / scale),
+            top: Val::Px(viewport.pos.y / scale),
+            ..default()
+        };
+    }
+}
+
 
 
 
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use kayak_ui::prelude::*;
+use kayak_ui::widgets::*;
+use crate::assets::UiAssets;
+use crate::leaderboard::Leaderboard;
+use crate::run_stats::{format_time, RunStats};
+use crate::state::GameState;
+use crate::ui::EventInput;
+use crate::ui::nav::{focused_button_style, MenuFocus, MenuFocusState, update_menu_focus};
+use crate::ui::style::button_style;
+
+#[derive(Component, Clone, PartialEq, Default)]
+pub struct LeaderboardMenuProps {
+}
+
+impl Widget for LeaderboardMenuProps {
+}
+
+#[derive(Bundle)]
+pub struct LeaderboardMenuBundle {
+    pub props: LeaderboardMenuProps,
+    pub styles: KStyle,
+    pub computed_styles: ComputedStyles,
+    pub children: KChildren,
+    pub on_event: OnEvent,
+    pub widget_name: WidgetName,
+}
+
+impl Default for LeaderboardMenuBundle {
+    fn default() -> Self {
+        Self {
+            props: LeaderboardMenuProps::default(),
+            styles: KStyle::default(),
+            computed_styles: ComputedStyles::default(),
+            children: KChildren::default(),
+            on_event: OnEvent::default(),
+            widget_name: LeaderboardMenuProps::default().get_name(),
+        }
+    }
+}
+
+pub fn register_leaderboard_menu_systems(app: &mut App) {
+    app.add_system_set(
+        SystemSet::on_update(GameState::LeaderboardMenu)
+            .with_system(leaderboard_menu_navigate.after(update_menu_focus))
+    );
+}
+
+// The only button is Back
+fn leaderboard_menu_navigate(
+    mut focus: ResMut<MenuFocus>,
+    mut state: ResMut<State<GameState>>
+) {
+    focus.count = 1;
+
+    if focus.back || focus.confirmed {
+        state.pop().unwrap();
+    }
+}
+
+pub fn register_leaderboard_menu_ui(widget_context: &mut KayakRootContext) {
+    widget_context.add_widget_data::<LeaderboardMenuProps, MenuFocusState>();
+
+    widget_context.add_widget_system(
+        LeaderboardMenuProps::default().get_name(),
+        widget_update::<LeaderboardMenuProps, MenuFocusState>,
+        leaderboard_menu_render,
+    );
+}
+
+pub fn leaderboard_menu_render(
+    In((widget_context, entity)): In<(KayakWidgetContext, Entity)>,
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    board: Res<Leaderboard>,
+    state_query: Query<&MenuFocusState>
+) -> bool {
+    let state_entity = widget_context.use_state(
+        &mut commands,
+        entity,
+        MenuFocusState::default()
+    );
+
+    let focused = match state_query.get(state_entity) {
+        Ok(s) => s.focused,
+        _ => return false
+    };
+
+    let button_styles = button_style();
+
+    let title_styles = KStyle {
+        top: StyleProp::Value(Units::Pixels(0.0)),
+        bottom: StyleProp::Value(Units::Pixels(12.0)),
+        font_size: StyleProp::Value(40.0),
+        ..default()
+    };
+
+    let row_styles = KStyle {
+        height: StyleProp::Value(Units::Pixels(24.0)),
+        font_size: StyleProp::Value(20.0),
+        ..default()
+    };
+
+    let image_styles = KStyle {
+        width: StyleProp::Value(Units::Pixels(480.0)),
+        height: StyleProp::Value(Units::Pixels(560.0)),
+        position_type: StyleProp::Value(KPositionType::SelfDirected),
+        ..default()
+    };
+
+    let background_styles = KStyle {
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        top: StyleProp::Value(Units::Stretch(1.0)),
+        bottom: StyleProp::Value(Units::Stretch(1.0)),
+
+        padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
+
+        width: StyleProp::Value(Units::Pixels(480.0)),
+        height: StyleProp::Value(Units::Pixels(560.0)),
+
+        layout_type: StyleProp::Value(LayoutType::Column),
+        row_between: StyleProp::Value(Units::Pixels(6.0)),
+
+        ..default()
+    };
+
+    let mut rows: Vec<String> = board.entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| e.describe(i))
+        .collect();
+
+    if rows.is_empty() {
+        rows.push("No runs yet".to_string());
+    }
+
+    let click_back = OnEvent::new(move |
+        In((event_dispatcher_context, _, event, _entity)): EventInput,
+        mut state: ResMut<State<GameState>>
+    | {
+        match event.event_type {
+            EventType::Click(_) => {
+                state.pop().unwrap();
+            }
+            _ => {}
+        }
+
+        (event_dispatcher_context, event)
+    });
+
+    let parent_id = Some(entity);
+
+    rsx! {
+        <BackgroundBundle styles={background_styles.clone()}>
+            <KImageBundle
+                styles={image_styles}
+                image={KImage(assets.pause_bg.clone())}
+            />
+
+            <BackgroundBundle styles={background_styles.clone()}>
+                <TextWidgetBundle
+                    text={TextProps {
+                        content: "Leaderboard".to_string(),
+                        ..default()
+                    }}
+                    styles={title_styles}
+                />
+
+                {rows.into_iter().for_each(|row| {
+                    constructor! {
+                        <TextWidgetBundle
+                            text={TextProps {
+                                content: row,
+                                ..default()
+                            }}
+                            styles={row_styles.clone()}
+                        />
+                    }
+                })}
+
+                <KButtonBundle
+                    styles={focused_button_style(&button_styles, focused == 0)}
+                    button={KButton {
+                        text: "Back".into()
+                    }}
+                    on_event={click_back}
+                />
+            </BackgroundBundle>
+        </BackgroundBundle>
+    };
+
+    true
+}
+
+// Shown under the score on the win and lose screens
+pub fn run_summary_lines(stats: &RunStats, board: &Leaderboard) -> Vec<String> {
+    let mut lines = vec![
+        format!("Time {}  Kills {}", format_time(stats.total_time()), stats.total_kills()),
+        format!("Damage {} dealt, {} taken", stats.damage_dealt, stats.damage_taken),
+        format!("Coins {} earned, {} spent", stats.coins_earned, stats.coins_spent),
+    ];
+
+    if stats.boss_fight_time > 0.0 {
+        lines.push(format!("Boss fight {}", format_time(stats.boss_fight_time)));
+    }
+
+    lines.push(match board.last_rank {
+        Some(rank) => format!("Leaderboard #{}", rank + 1),
+        None => "Didn't make the leaderboard".to_string()
+    });
+
+    lines
+}
@@ -6,7 +6,10 @@ use crate::coin::drops::CoinHolder;
 use crate::player::logic::PlayerScore;
 use crate::player::Player;
 use crate::state::GameState;
+use crate::leaderboard::Leaderboard;
+use crate::run_stats::RunStats;
 use crate::ui::EventInput;
+use crate::ui::leaderboard::run_summary_lines;
 use crate::ui::menu::GotoMenuEvent;
 use crate::ui::style::{background_style, button_style};
 
@@ -55,7 +58,9 @@ fn lose_menu_render(
     In((widget_context, entity)): In<(KayakWidgetContext, Entity)>,
     mut commands: Commands,
     assets: Res<UiAssets>,
-    score: Res<PlayerScore>
+    score: Res<PlayerScore>,
+    stats: Res<RunStats>,
+    board: Res<Leaderboard>
 ) -> bool {
     let button_styles = button_style();
 
@@ -67,6 +72,14 @@ fn lose_menu_render(
         ..default()
     };
 
+    let summary_styles = KStyle {
+        height: StyleProp::Value(Units::Pixels(20.0)),
+        font_size: StyleProp::Value(16.0),
+        ..default()
+    };
+
+    let summary = run_summary_lines(&stats, &board);
+
     let title_styles = KStyle {
         top: StyleProp::Value(Units::Pixels(0.0)),
         bottom: StyleProp::Value(Units::Percentage(16.0)),
@@ -83,10 +96,10 @@ fn lose_menu_render(
         padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
 
         width: StyleProp::Value(Units::Pixels(384.0)),
-        height: StyleProp::Value(Units::Pixels(400.0)),
+        height: StyleProp::Value(Units::Pixels(540.0)),
 
         layout_type: StyleProp::Value(LayoutType::Column),
-        row_between: StyleProp::Value(Units::Pixels(20.0)),
+        row_between: StyleProp::Value(Units::Pixels(12.0)),
 
         ..default()
     };
@@ -100,7 +113,7 @@ fn lose_menu_render(
         padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
 
         width: StyleProp::Value(Units::Pixels(320.0)),
-        height: StyleProp::Value(Units::Pixels(384.0)),
+        height: StyleProp::Value(Units::Pixels(524.0)),
 
         layout_type: StyleProp::Value(LayoutType::Column),
         row_between: StyleProp::Value(Units::Pixels(20.0)),
@@ -181,6 +194,16 @@ fn lose_menu_render(
                     alignment: Alignment::Middle,
                     ..default()
                 }} styles={score_styles}/>
+
+                {summary.into_iter().for_each(|line| {
+                    constructor! {
+                        <TextWidgetBundle text={TextProps {
+                            content: line,
+                            alignment: Alignment::Middle,
+                            ..default()
+                        }} styles={summary_styles.clone()}/>
+                    }
+                })}
             </BackgroundBundle>
 
         </BackgroundBundle>
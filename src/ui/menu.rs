@@ -11,18 +11,21 @@ use crate::combat::{ExplosionAttack, ProjectileAttack};
 use crate::enemies::Enemy;
 use crate::fx::indicator::Indicator;
 use crate::fx::smoke::Smoke;
+use crate::level::consts::{DEFAULT_ENTRY_POINT, FIRST_LEVEL};
 use crate::level::transition::LevelTransition;
 use crate::level::tutorial::HelpText;
 use crate::player::logic::PlayerScore;
 use crate::player::Player;
+use crate::rng::{GameRng, random_seed};
+use crate::save::{PendingSave, SaveData};
 use crate::shop::Shop;
 
 use crate::state::GameState;
 use crate::ui::bossbar::BossBar;
-use crate::ui::event_handlers::{goto_state_event, StateTransition};
 use crate::ui::EventInput;
 use crate::ui::hud::Hud;
-use crate::ui::style::{background_style, button_style};
+use crate::ui::nav::{focused_button_style, MenuFocus, update_menu_focus};
+use crate::ui::style::button_style;
 
 #[derive(Component, Clone, PartialEq, Default)]
 pub struct MainMenuProps {
@@ -59,11 +62,122 @@ pub fn register_menu_ui_systems(app: &mut App) {
     app.add_system_set(
         SystemSet::on_enter(GameState::MainMenu)
             .with_system(menu_state_setup)
+    ).add_system_set(
+        SystemSet::on_update(GameState::MainMenu)
+            .with_system(main_menu_navigate.after(update_menu_focus))
+            .with_system(update_main_menu_state.after(main_menu_navigate))
     ).add_system_set(
         SystemSet::new().with_system(goto_menu)
     ).add_system_set(
         SystemSet::on_exit(GameState::MainMenu).with_system(stop_attempting)
-    ).init_resource::<GotoMenuEvent>();
+    ).init_resource::<GotoMenuEvent>()
+        .init_resource::<MainMenuEntries>();
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MainMenuEntry {
+    Continue,
+    NewGame,
+    Seed,
+    Leaderboard,
+    Settings,
+    Quit
+}
+
+impl MainMenuEntry {
+    pub fn label(&self, rng: &GameRng) -> String {
+        match self {
+            MainMenuEntry::Continue => "Continue".to_string(),
+            MainMenuEntry::NewGame => "New Game".to_string(),
+            MainMenuEntry::Seed => format!("Seed {}", rng.seed()),
+            MainMenuEntry::Leaderboard => "Leaderboard".to_string(),
+            MainMenuEntry::Settings => "Settings".to_string(),
+            MainMenuEntry::Quit => "Quit".to_string()
+        }
+    }
+}
+
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct MainMenuState {
+    pub labels: Vec<String>,
+    pub focused: usize
+}
+
+// Filled in when the main menu opens, since Continue only shows up with a save
+#[derive(Resource, Clone, Debug, Default)]
+pub struct MainMenuEntries(pub Vec<MainMenuEntry>);
+
+fn run_main_menu_entry(
+    entry: MainMenuEntry,
+    state: &mut State<GameState>,
+    trans: &mut LevelTransition,
+    pending: &mut PendingSave,
+    rng: &mut GameRng
+) {
+    match entry {
+        MainMenuEntry::Continue => {
+            if let Some(save) = SaveData::load() {
+                trans.next = save.level.clone();
+                pending.0 = Some(save);
+                rng.restart();
+                state.overwrite_set(GameState::LevelTransition).unwrap();
+            }
+        }
+
+        MainMenuEntry::NewGame => {
+            rng.restart();
+            state.overwrite_set(GameState::LevelTransition).unwrap();
+        }
+
+        MainMenuEntry::Seed => {
+            rng.reseed(random_seed());
+        }
+
+        MainMenuEntry::Leaderboard => {
+            state.push(GameState::LeaderboardMenu).unwrap();
+        }
+
+        MainMenuEntry::Settings => {
+            state.push(GameState::SettingsMenu).unwrap();
+        }
+
+        MainMenuEntry::Quit => {
+            std::process::exit(0);
+        }
+    }
+}
+
+fn main_menu_navigate(
+    mut focus: ResMut<MenuFocus>,
+    entries: Res<MainMenuEntries>,
+    mut state: ResMut<State<GameState>>,
+    mut trans: ResMut<LevelTransition>,
+    mut pending: ResMut<PendingSave>,
+    mut rng: ResMut<GameRng>
+) {
+    focus.count = entries.0.len();
+
+    if let Some(entry) = entries.0.get(focus.index) {
+        if focus.confirmed(focus.index) {
+            run_main_menu_entry(*entry, &mut state, &mut trans, &mut pending, &mut rng);
+        }
+    }
+}
+
+fn update_main_menu_state(
+    entries: Res<MainMenuEntries>,
+    rng: Res<GameRng>,
+    focus: Res<MenuFocus>,
+    mut q: Query<&mut MainMenuState>
+) {
+    let labels: Vec<String> = entries.0.iter().map(|e| e.label(&rng)).collect();
+
+    for mut state in q.iter_mut() {
+        if state.labels != labels || state.focused != focus.index {
+            state.labels = labels.clone();
+            state.focused = focus.index;
+        }
+    }
 }
 
 pub fn menu_state_setup(
@@ -89,9 +203,21 @@ pub fn menu_state_setup(
     mut hud: ResMut<Hud>,
     mut trans: ResMut<LevelTransition>,
     mut sel: ResMut<LevelSelection>,
+    mut pending: ResMut<PendingSave>,
+    mut entries: ResMut<MainMenuEntries>,
 ) {
     score.score = 0;
 
+    entries.0.clear();
+    if SaveData::exists() {
+        entries.0.push(MainMenuEntry::Continue);
+    }
+    entries.0.push(MainMenuEntry::NewGame);
+    entries.0.push(MainMenuEntry::Seed);
+    entries.0.push(MainMenuEntry::Leaderboard);
+    entries.0.push(MainMenuEntry::Settings);
+    entries.0.push(MainMenuEntry::Quit);
+
     for e in q.iter() {
         if let Some(cmd) = commands.get_entity(e) {
             cmd.despawn_recursive();
@@ -105,7 +231,9 @@ pub fn menu_state_setup(
         }
     }
 
-    trans.next = String::from("Level_0");
+    trans.next = String::from(FIRST_LEVEL);
+    trans.entry_point = DEFAULT_ENTRY_POINT;
+    pending.0 = None;
     *sel = LevelSelection::Identifier(String::from("Init"));
 }
 
@@ -130,11 +258,11 @@ pub fn goto_menu(mut state: ResMut<State<GameState>>, goto: Res<GotoMenuEvent>)
 
 
 pub fn register_menu_ui(widget_context: &mut KayakRootContext) {
-    widget_context.add_widget_data::<MainMenuProps, EmptyState>();
+    widget_context.add_widget_data::<MainMenuProps, MainMenuState>();
 
     widget_context.add_widget_system(
         MainMenuProps::default().get_name(),
-        widget_update::<MainMenuProps, EmptyState>,
+        widget_update::<MainMenuProps, MainMenuState>,
         main_menu_render,
     );
 }
@@ -142,13 +270,22 @@ pub fn register_menu_ui(widget_context: &mut KayakRootContext) {
 pub fn main_menu_render(
     In((widget_context, entity)): In<(KayakWidgetContext, Entity)>,
     mut commands: Commands,
-    assets: Res<UiAssets>
+    assets: Res<UiAssets>,
+    entries: Res<MainMenuEntries>,
+    state_query: Query<&MainMenuState>
 ) -> bool {
     let button_styles = button_style();
 
+    // seeds and "Leaderboard" are too long for the big menu font
+    let small_button_styles = KStyle {
+        font_size: StyleProp::Value(28.0),
+        line_height: StyleProp::Value(28.0),
+        ..button_style()
+    };
+
     let title_styles = KStyle {
         top: StyleProp::Value(Units::Pixels(0.0)),
-        bottom: StyleProp::Value(Units::Percentage(16.0)),
+        bottom: StyleProp::Value(Units::Percentage(8.0)),
         font_size: StyleProp::Value(40.0),
         ..default()
     };
@@ -162,7 +299,7 @@ pub fn main_menu_render(
         padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
 
         width: StyleProp::Value(Units::Pixels(320.0)),
-        height: StyleProp::Value(Units::Pixels(384.0)),
+        height: StyleProp::Value(Units::Pixels(444.0)),
 
         layout_type: StyleProp::Value(LayoutType::Column),
         row_between: StyleProp::Value(Units::Pixels(20.0)),
@@ -188,28 +325,56 @@ pub fn main_menu_render(
         padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
 
         width: StyleProp::Value(Units::Pixels(384.0)),
-        height: StyleProp::Value(Units::Pixels(400.0)),
+        height: StyleProp::Value(Units::Pixels(460.0)),
 
         layout_type: StyleProp::Value(LayoutType::Column),
-        row_between: StyleProp::Value(Units::Pixels(20.0)),
+        row_between: StyleProp::Value(Units::Pixels(10.0)),
 
         ..default()
     };
 
-    let click_quit = OnEvent::new(
-        move |In((event_dispatcher_context, _, event, _entity)): EventInput| {
+    let state_entity = widget_context.use_state(
+        &mut commands,
+        entity,
+        MainMenuState::default()
+    );
+
+    let menu_state = match state_query.get(state_entity) {
+        Ok(s) => s,
+        _ => return false
+    };
+
+    let focused = menu_state.focused;
+    let labels = menu_state.labels.clone();
+
+    let buttons = entries.0.clone().into_iter().zip(labels).enumerate().map(|(i, (entry, label))| {
+        let on_event = OnEvent::new(move |
+            In((event_dispatcher_context, _, event, _entity)): EventInput,
+            mut state: ResMut<State<GameState>>,
+            mut trans: ResMut<LevelTransition>,
+            mut pending: ResMut<PendingSave>,
+            mut rng: ResMut<GameRng>,
+            mut focus: ResMut<MenuFocus>
+        | {
             match event.event_type {
                 EventType::Click(_) => {
-                    std::process::exit(0);
+                    focus.index = i;
+                    run_main_menu_entry(entry, &mut state, &mut trans, &mut pending, &mut rng);
                 }
                 _ => {}
             }
 
             (event_dispatcher_context, event)
-        }
-    );
+        });
+
+        let styles = if entry == MainMenuEntry::Seed || entry == MainMenuEntry::Leaderboard {
+            &small_button_styles
+        } else {
+            &button_styles
+        };
 
-    let click_new_game = goto_state_event(StateTransition::Set(GameState::LevelTransition));
+        (label, focused_button_style(styles, i == focused), on_event)
+    });
 
     let parent_id = Some(entity);
 
@@ -232,21 +397,17 @@ pub fn main_menu_render(
                     ..default()
                 }} styles={title_styles.clone()}/>
 
-                <KButtonBundle
-                    styles={button_styles.clone()}
-                    button={KButton {
-                        text: "New Game".into()
-                    }}
-                    on_event={click_new_game}
-                />
-
-                <KButtonBundle
-                    styles={button_styles.clone()}
-                    button={KButton {
-                        text: "Quit".into()
-                    }}
-                    on_event={click_quit}
-                />
+                {buttons.for_each(|(text, styles, on_event)| {
+                    constructor! {
+                        <KButtonBundle
+                            styles={styles}
+                            button={KButton {
+                                text: text.into()
+                            }}
+                            on_event={on_event}
+                        />
+                    }
+                })}
             </BackgroundBundle>
         </BackgroundBundle>
         </ElementBundle>
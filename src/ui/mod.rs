@@ -9,6 +9,9 @@ pub mod shop_button;
 pub mod bossbar;
 pub mod win;
 pub mod lose;
+pub mod nav;
+pub mod settings;
+pub mod leaderboard;
 
 use kayak_ui::prelude::*;
 use kayak_ui::widgets::*;
@@ -29,6 +32,9 @@ impl Plugin for GameUiPlugin {
         shop::register_shop_menu_ui_systems(app);
         shop_button::register_shop_button_systems(app);
         bossbar::register_boss_bar(app);
+        nav::register_menu_nav(app);
+        settings::register_settings_menu_systems(app);
+        leaderboard::register_leaderboard_menu_systems(app);
     }
 }
 
@@ -48,6 +54,8 @@ fn startup_ui(
     pause::register_pause_ui(&mut widget_context);
     shop::register_shop_menu_ui(&mut widget_context);
     shop_button::register_shop_button_ui(&mut widget_context);
+    settings::register_settings_menu_ui(&mut widget_context);
+    leaderboard::register_leaderboard_menu_ui(&mut widget_context);
 
     let parent_id = None;
 
@@ -57,5 +65,6 @@ fn startup_ui(
         </KayakAppBundle>
     };
 
-    commands.spawn(UICameraBundle::new(widget_context));
+    // the HUD is drawn by the game camera, inside its viewport
+    commands.spawn((UICameraBundle::new(widget_context), UiCameraConfig { show_ui: false }));
 }
\ No newline at end of file
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use kayak_ui::prelude::*;
+use leafwing_input_manager::prelude::*;
+use leafwing_input_manager::user_input::InputKind;
+use crate::input::InputAction;
+use crate::input::bindings::GamepadBinding;
+use crate::player::Player;
+use crate::state::GameState;
+
+// Lets the kayak menus be driven by the keyboard or a controller. Each menu
+// keeps a list of its buttons, `MenuFocus` tracks which one is selected, and
+// the menu's own systems act on `MenuFocus::confirmed` the same way the
+// button's click handler would.
+
+#[derive(Actionlike, PartialEq, Eq, Copy, Clone, Debug, Hash)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Confirm,
+    Back,
+}
+
+impl MenuAction {
+    pub fn input_map() -> InputMap<Self> {
+        use MenuAction::*;
+        use InputKind::*;
+
+        let mut map = InputMap::new([
+            (Keyboard(KeyCode::Up), Up),
+            (Keyboard(KeyCode::W), Up),
+            (Keyboard(KeyCode::Down), Down),
+            (Keyboard(KeyCode::S), Down),
+            (Keyboard(KeyCode::Return), Confirm),
+            (Keyboard(KeyCode::Space), Confirm),
+            (Keyboard(KeyCode::Back), Back),
+
+            (GamepadButton(GamepadButtonType::DPadUp), Up),
+            (GamepadButton(GamepadButtonType::DPadDown), Down),
+            (GamepadButton(GamepadButtonType::South), Confirm),
+            (GamepadButton(GamepadButtonType::East), Back),
+        ]);
+
+        map.insert(GamepadBinding::Axis(GamepadAxisType::LeftStickY, true).user_input(), Up);
+        map.insert(GamepadBinding::Axis(GamepadAxisType::LeftStickY, false).user_input(), Down);
+
+        map
+    }
+}
+
+const MENU_STATES: [GameState; 5] = [
+    GameState::MainMenu,
+    GameState::PauseMenu,
+    GameState::ShopMenu,
+    GameState::SettingsMenu,
+    GameState::LeaderboardMenu
+];
+
+#[derive(Resource, Debug, Default)]
+pub struct MenuFocus {
+    pub index: usize,
+    pub count: usize,
+    pub confirmed: bool,
+    pub back: bool,
+    // set while a menu is waiting on raw input, e.g. a key to rebind
+    pub locked: bool,
+}
+
+impl MenuFocus {
+    pub fn confirmed(&self, index: usize) -> bool {
+        self.confirmed && self.index == index
+    }
+}
+
+// Copied into each menu widget's state so the widget re-renders when the
+// selected button changes
+#[derive(Component, Debug, Default, Clone, PartialEq)]
+pub struct MenuFocusState {
+    pub focused: usize
+}
+
+pub fn register_menu_nav(app: &mut App) {
+    app
+        .add_plugin(InputManagerPlugin::<MenuAction>::default())
+        .init_resource::<ActionState<MenuAction>>()
+        .insert_resource(MenuAction::input_map())
+        .init_resource::<MenuFocus>()
+        .add_system(assign_menu_gamepad)
+        .add_system(update_menu_focus)
+        .add_system(sync_menu_focus_state.after(update_menu_focus))
+        .add_system(consume_gameplay_input_on_confirm.after(update_menu_focus));
+
+    for state in MENU_STATES {
+        app
+            .add_system_set(SystemSet::on_enter(state.clone()).with_system(reset_menu_focus))
+            .add_system_set(SystemSet::on_resume(state).with_system(reset_menu_focus));
+    }
+}
+
+fn reset_menu_focus(mut focus: ResMut<MenuFocus>) {
+    focus.index = 0;
+    focus.confirmed = false;
+    focus.back = false;
+    focus.locked = false;
+}
+
+pub fn update_menu_focus(
+    actions: Res<ActionState<MenuAction>>,
+    mut focus: ResMut<MenuFocus>
+) {
+    if focus.locked {
+        focus.confirmed = false;
+        focus.back = false;
+        return;
+    }
+
+    focus.confirmed = actions.just_pressed(MenuAction::Confirm);
+    focus.back = actions.just_pressed(MenuAction::Back);
+
+    if focus.count == 0 {
+        return;
+    }
+
+    if actions.just_pressed(MenuAction::Up) {
+        focus.index = (focus.index + focus.count - 1) % focus.count;
+    }
+
+    if actions.just_pressed(MenuAction::Down) {
+        focus.index = (focus.index + 1) % focus.count;
+    }
+
+    if focus.index >= focus.count {
+        focus.index = focus.count - 1;
+    }
+}
+
+// Space and the South button are Jump as well as Confirm, so the press that
+// closes a menu would carry on into gameplay. Consumed actions stay released
+// until their button is let go
+fn consume_gameplay_input_on_confirm(
+    focus: Res<MenuFocus>,
+    state: Res<State<GameState>>,
+    mut players: Query<&mut ActionState<InputAction>, With<Player>>
+) {
+    if !focus.confirmed || !MENU_STATES.contains(state.current()) {
+        return;
+    }
+
+    for mut actions in players.iter_mut() {
+        for action in InputAction::variants() {
+            actions.consume(action);
+        }
+    }
+}
+
+fn assign_menu_gamepad(
+    gamepads: Res<Gamepads>,
+    mut map: ResMut<InputMap<MenuAction>>
+) {
+    let connected = map.gamepad().map(|g| gamepads.contains(g)).unwrap_or(false);
+    if connected {
+        return;
+    }
+
+    if let Some(gamepad) = gamepads.iter().next() {
+        map.set_gamepad(gamepad);
+    }
+}
+
+fn sync_menu_focus_state(
+    focus: Res<MenuFocus>,
+    mut q: Query<&mut MenuFocusState>
+) {
+    for mut state in q.iter_mut() {
+        if state.focused != focus.index {
+            state.focused = focus.index;
+        }
+    }
+}
+
+pub fn focused_button_style(base: &KStyle, focused: bool) -> KStyle {
+    if !focused {
+        return base.clone();
+    }
+
+    KStyle {
+        background_color: StyleProp::Value(Color::rgb(0.2, 0.14, 0.1)),
+        border_color: StyleProp::Value(Color::rgb_u8(0xFE, 0xE7, 0x61)),
+        border: StyleProp::Value(Edge::all(2.0)),
+        ..base.clone()
+    }
+}
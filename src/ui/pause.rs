@@ -2,11 +2,15 @@ use bevy::prelude::*;
 use bevy_rapier2d::parry::query::SplitResult::Positive;
 use kayak_ui::prelude::*;
 use kayak_ui::widgets::*;
+use leafwing_input_manager::prelude::ActionState;
 use crate::assets::UiAssets;
+use crate::input::InputAction;
+use crate::player::Player;
 use crate::state::GameState;
 use crate::ui::event_handlers::{goto_state_event, StateTransition};
 use crate::ui::EventInput;
 use crate::ui::menu::GotoMenuEvent;
+use crate::ui::nav::{focused_button_style, MenuFocus, MenuFocusState, update_menu_focus};
 use crate::ui::style::{background_style, button_style};
 
 pub fn register_pause_systems(app: &mut App) {
@@ -14,14 +18,81 @@ pub fn register_pause_systems(app: &mut App) {
         .add_system_set(
             SystemSet::on_update(GameState::Gameplay)
                 .with_system(pause_if_needed)
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::PauseMenu)
+                .with_system(pause_menu_navigate.after(update_menu_focus))
         );
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PauseMenuEntry {
+    Resume,
+    Settings,
+    Quit
+}
+
+impl PauseMenuEntry {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PauseMenuEntry::Resume => "Resume",
+            PauseMenuEntry::Settings => "Settings",
+            PauseMenuEntry::Quit => "Quit"
+        }
+    }
+}
+
+pub const PAUSE_MENU_ENTRIES: &[PauseMenuEntry] = &[
+    PauseMenuEntry::Resume,
+    PauseMenuEntry::Settings,
+    PauseMenuEntry::Quit
+];
+
+fn run_pause_menu_entry(
+    entry: PauseMenuEntry,
+    state: &mut State<GameState>,
+    goto: &mut GotoMenuEvent
+) {
+    match entry {
+        PauseMenuEntry::Resume => {
+            state.pop().unwrap();
+        }
+
+        PauseMenuEntry::Settings => {
+            state.push(GameState::SettingsMenu).unwrap();
+        }
+
+        PauseMenuEntry::Quit => {
+            state.pop().unwrap();
+            goto.attempt = true;
+        }
+    }
+}
+
+fn pause_menu_navigate(
+    mut focus: ResMut<MenuFocus>,
+    mut state: ResMut<State<GameState>>,
+    mut goto: ResMut<GotoMenuEvent>
+) {
+    focus.count = PAUSE_MENU_ENTRIES.len();
+
+    if focus.back {
+        run_pause_menu_entry(PauseMenuEntry::Resume, &mut state, &mut goto);
+    } else if focus.confirmed {
+        run_pause_menu_entry(PAUSE_MENU_ENTRIES[focus.index], &mut state, &mut goto);
+    }
+}
+
 fn pause_if_needed(
     mut state: ResMut<State<GameState>>,
-    key: Res<Input<KeyCode>>,
+    player: Query<&ActionState<InputAction>, With<Player>>,
 ) {
-    if !key.just_pressed(KeyCode::Escape) || state.current() != &GameState::Gameplay {
+    if player.is_empty() {
+        return;
+    }
+
+    let input = player.single();
+    if !input.just_pressed(InputAction::Pause) || state.current() != &GameState::Gameplay {
         return;
     }
 
@@ -61,11 +132,11 @@ impl Default for PauseMenuBundle {
 
 
 pub fn register_pause_ui(widget_context: &mut KayakRootContext) {
-    widget_context.add_widget_data::<PauseMenuProps, EmptyState>();
+    widget_context.add_widget_data::<PauseMenuProps, MenuFocusState>();
 
     widget_context.add_widget_system(
         PauseMenuProps::default().get_name(),
-        widget_update::<PauseMenuProps, EmptyState>,
+        widget_update::<PauseMenuProps, MenuFocusState>,
         pause_menu_render,
     );
 }
@@ -74,6 +145,7 @@ fn pause_menu_render(
     In((widget_context, entity)): In<(KayakWidgetContext, Entity)>,
     mut commands: Commands,
     assets: Res<UiAssets>,
+    focus_query: Query<&MenuFocusState>
 ) -> bool {
     let button_styles = button_style();
 
@@ -122,37 +194,37 @@ fn pause_menu_render(
 
     let parent_id = Some(entity);
 
-    let click_resume = OnEvent::new(move |
-        In((event_dispatcher_context, _, event, _entity)): EventInput,
-        mut state: ResMut<State<GameState>>,
-    | {
-        match event.event_type {
-            EventType::Click(_) => {
-                state.pop().unwrap();
-            }
-            _ => {}
-        }
+    let state_entity = widget_context.use_state(
+        &mut commands,
+        entity,
+        MenuFocusState::default()
+    );
 
-        (event_dispatcher_context, event)
-    });
+    let focused = match focus_query.get(state_entity) {
+        Ok(s) => s.focused,
+        _ => return false
+    };
 
-    let click_exit = OnEvent::new(move |
-        In((event_dispatcher_context, _, event, _entity)): EventInput,
-        mut state: ResMut<State<GameState>>,
-        mut goto: ResMut<GotoMenuEvent>
-    | {
-        match event.event_type {
-            EventType::Click(_) => {
-                state.pop().unwrap();
-                goto.attempt = true;
+    let buttons = PAUSE_MENU_ENTRIES.iter().enumerate().map(|(i, entry)| {
+        let entry = *entry;
+
+        let on_event = OnEvent::new(move |
+            In((event_dispatcher_context, _, event, _entity)): EventInput,
+            mut state: ResMut<State<GameState>>,
+            mut goto: ResMut<GotoMenuEvent>
+        | {
+            match event.event_type {
+                EventType::Click(_) => {
+                    run_pause_menu_entry(entry, &mut state, &mut goto);
+                }
+                _ => {}
             }
-            _ => {}
-        }
 
-        (event_dispatcher_context, event)
-    });
+            (event_dispatcher_context, event)
+        });
 
-    // let click_exit = goto_state_event(StateTransition::Set(GameState::MainMenu));
+        (entry.label(), focused_button_style(&button_styles, i == focused), on_event)
+    });
 
     rsx! {
         <BackgroundBundle styles={background_styles.clone()}>
@@ -171,21 +243,17 @@ fn pause_menu_render(
                     styles={title_styles}
                 />
 
-                <KButtonBundle
-                    styles={button_styles.clone()}
-                    button={KButton {
-                        text: "Resume".into()
-                    }}
-                    on_event={click_resume}
-                />
-
-                <KButtonBundle
-                    styles={button_styles.clone()}
-                    button={KButton {
-                        text: "Quit".into()
-                    }}
-                    on_event={click_exit}
-                />
+                {buttons.for_each(|(text, styles, on_event)| {
+                    constructor! {
+                        <KButtonBundle
+                            styles={styles}
+                            button={KButton {
+                                text: text.into()
+                            }}
+                            on_event={on_event}
+                        />
+                    }
+                })}
             </BackgroundBundle>
 
         </BackgroundBundle>
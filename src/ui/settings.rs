@@ -0,0 +1,490 @@
+use bevy::prelude::*;
+use kayak_ui::prelude::*;
+use kayak_ui::widgets::*;
+use leafwing_input_manager::user_input::Modifier;
+use crate::assets::UiAssets;
+use crate::audio::VolumeSettings;
+use crate::camera::CameraSettings;
+use crate::config::{GameSettings, RESOLUTIONS, write_config};
+use crate::input::bindings::{Binding, BindingButton, describe_bindings};
+use crate::input::PlayerControls;
+use crate::level::checkpoint::RespawnSettings;
+use crate::level::transition::TransitionSettings;
+use crate::state::GameState;
+use crate::ui::EventInput;
+use crate::ui::nav::{focused_button_style, MenuFocus, update_menu_focus};
+use crate::ui::style::button_style;
+
+const SCREEN_SHAKE_STEPS: &[f32] = &[0.0, 0.25, 0.5, 0.75, 1.0, 1.25, 1.5];
+const VOLUME_STEPS: &[f32] = &[0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SettingsEntry {
+    Rebind(&'static str),
+    Fullscreen,
+    Resolution,
+    ScreenShake,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Back
+}
+
+fn settings_entries() -> Vec<SettingsEntry> {
+    let mut entries: Vec<SettingsEntry> = PlayerControls::default()
+        .actions()
+        .iter()
+        .map(|(name, _, _)| SettingsEntry::Rebind(*name))
+        .collect();
+
+    entries.push(SettingsEntry::Fullscreen);
+    entries.push(SettingsEntry::Resolution);
+    entries.push(SettingsEntry::ScreenShake);
+    entries.push(SettingsEntry::MasterVolume);
+    entries.push(SettingsEntry::MusicVolume);
+    entries.push(SettingsEntry::SfxVolume);
+    entries.push(SettingsEntry::Back);
+
+    entries
+}
+
+// "move_left" -> "Move Left"
+fn action_label(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn next_step(steps: &[f32], current: f32) -> f32 {
+    // small epsilon so values typed into config.toml by hand still land on a step
+    let i = steps.iter().position(|s| *s >= current - 0.001);
+    let next = i.map(|i| (i + 1) % steps.len()).unwrap_or(0);
+    steps[next]
+}
+
+impl SettingsEntry {
+    pub fn label(
+        &self,
+        controls: &PlayerControls,
+        settings: &GameSettings,
+        volume: &VolumeSettings,
+        rebind: &RebindState
+    ) -> String {
+        match self {
+            SettingsEntry::Rebind(name) => {
+                if rebind.awaiting == Some(*name) {
+                    return format!("{}: press a key", action_label(name));
+                }
+
+                let bindings = controls.actions()
+                    .iter()
+                    .find(|(n, _, _)| n == name)
+                    .map(|(_, b, _)| describe_bindings(b))
+                    .unwrap_or_default();
+
+                format!("{}: {}", action_label(name), bindings)
+            },
+
+            SettingsEntry::Fullscreen => {
+                format!("Fullscreen: {}", if settings.fullscreen { "On" } else { "Off" })
+            },
+
+            SettingsEntry::Resolution => {
+                format!("Resolution: {}x{}", settings.resolution.x, settings.resolution.y)
+            },
+
+            SettingsEntry::ScreenShake => {
+                format!("Screen Shake: {}%", (settings.screen_shake * 100.0).round())
+            },
+
+            SettingsEntry::MasterVolume => {
+                format!("Master Volume: {}%", (volume.master * 100.0).round())
+            },
+
+            SettingsEntry::MusicVolume => {
+                format!("Music Volume: {}%", (volume.music * 100.0).round())
+            },
+
+            SettingsEntry::SfxVolume => {
+                format!("Sound Volume: {}%", (volume.sfx * 100.0).round())
+            },
+
+            SettingsEntry::Back => "Back".to_string()
+        }
+    }
+}
+
+// The action that's waiting on a key press to be rebound
+#[derive(Resource, Debug, Default)]
+pub struct RebindState {
+    pub awaiting: Option<&'static str>
+}
+
+#[derive(Debug, Component, PartialEq, Clone, Default)]
+pub struct SettingsMenuState {
+    pub labels: Vec<String>,
+    pub focused: usize,
+}
+
+#[derive(Component, Clone, PartialEq, Default)]
+pub struct SettingsMenuProps {
+}
+
+impl Widget for SettingsMenuProps {
+}
+
+#[derive(Bundle)]
+pub struct SettingsMenuBundle {
+    pub props: SettingsMenuProps,
+    pub styles: KStyle,
+    pub computed_styles: ComputedStyles,
+    pub children: KChildren,
+    pub on_event: OnEvent,
+    pub widget_name: WidgetName,
+}
+
+impl Default for SettingsMenuBundle {
+    fn default() -> Self {
+        Self {
+            props: SettingsMenuProps::default(),
+            styles: KStyle::default(),
+            computed_styles: ComputedStyles::default(),
+            children: KChildren::default(),
+            on_event: OnEvent::default(),
+            widget_name: SettingsMenuProps::default().get_name(),
+        }
+    }
+}
+
+pub fn register_settings_menu_systems(app: &mut App) {
+    app
+        .init_resource::<RebindState>()
+        .add_system_set(
+            SystemSet::on_update(GameState::SettingsMenu)
+                .with_system(capture_rebind.after(update_menu_focus))
+                .with_system(settings_menu_navigate.after(capture_rebind))
+                .with_system(update_settings_menu_state.after(settings_menu_navigate))
+        )
+        .add_system_set(
+            SystemSet::on_exit(GameState::SettingsMenu)
+                .with_system(cancel_rebind)
+        );
+}
+
+fn cancel_rebind(mut rebind: ResMut<RebindState>) {
+    rebind.awaiting = None;
+}
+
+// Returns whether the config file needs to be written
+fn run_settings_entry(
+    entry: SettingsEntry,
+    state: &mut State<GameState>,
+    settings: &mut GameSettings,
+    volume: &mut VolumeSettings,
+    rebind: &mut RebindState,
+    focus: &mut MenuFocus
+) -> bool {
+    match entry {
+        SettingsEntry::Rebind(name) => {
+            rebind.awaiting = Some(name);
+            focus.locked = true;
+            return false;
+        }
+
+        SettingsEntry::Fullscreen => {
+            settings.fullscreen = !settings.fullscreen;
+        }
+
+        SettingsEntry::Resolution => {
+            let current = RESOLUTIONS.iter().position(|r| *r == settings.resolution);
+            let next = current.map(|i| (i + 1) % RESOLUTIONS.len()).unwrap_or(0);
+            settings.resolution = RESOLUTIONS[next];
+        }
+
+        SettingsEntry::ScreenShake => {
+            settings.screen_shake = next_step(SCREEN_SHAKE_STEPS, settings.screen_shake);
+        }
+
+        SettingsEntry::MasterVolume => {
+            volume.master = next_step(VOLUME_STEPS, volume.master);
+        }
+
+        SettingsEntry::MusicVolume => {
+            volume.music = next_step(VOLUME_STEPS, volume.music);
+        }
+
+        SettingsEntry::SfxVolume => {
+            volume.sfx = next_step(VOLUME_STEPS, volume.sfx);
+        }
+
+        SettingsEntry::Back => {
+            state.pop().unwrap();
+            return false;
+        }
+    }
+
+    true
+}
+
+fn settings_menu_navigate(
+    mut focus: ResMut<MenuFocus>,
+    mut state: ResMut<State<GameState>>,
+    controls: Res<PlayerControls>,
+    mut settings: ResMut<GameSettings>,
+    mut volume: ResMut<VolumeSettings>,
+    respawn: Res<RespawnSettings>,
+    transition: Res<TransitionSettings>,
+    camera: Res<CameraSettings>,
+    mut rebind: ResMut<RebindState>
+) {
+    let entries = settings_entries();
+    focus.count = entries.len();
+
+    if focus.back {
+        state.pop().unwrap();
+        return;
+    }
+
+    if focus.confirmed {
+        let entry = entries[focus.index];
+
+        if run_settings_entry(entry, &mut state, &mut settings, &mut volume, &mut rebind, &mut focus) {
+            write_config(&controls, &settings, &volume, &respawn, &transition, &camera);
+        }
+    }
+}
+
+fn held_modifiers(keys: &Input<KeyCode>) -> Vec<Modifier> {
+    let mut modifiers = vec![];
+
+    if keys.any_pressed([KeyCode::LControl, KeyCode::RControl]) {
+        modifiers.push(Modifier::Control);
+    }
+
+    if keys.any_pressed([KeyCode::LShift, KeyCode::RShift]) {
+        modifiers.push(Modifier::Shift);
+    }
+
+    if keys.any_pressed([KeyCode::LAlt, KeyCode::RAlt]) {
+        modifiers.push(Modifier::Alt);
+    }
+
+    if keys.any_pressed([KeyCode::LWin, KeyCode::RWin]) {
+        modifiers.push(Modifier::Win);
+    }
+
+    modifiers
+}
+
+fn is_modifier_key(key: KeyCode) -> bool {
+    use KeyCode::*;
+    matches!(key, LControl | RControl | LShift | RShift | LAlt | RAlt | LWin | RWin)
+}
+
+fn capture_rebind(
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    mut controls: ResMut<PlayerControls>,
+    settings: Res<GameSettings>,
+    volume: Res<VolumeSettings>,
+    respawn: Res<RespawnSettings>,
+    transition: Res<TransitionSettings>,
+    camera: Res<CameraSettings>,
+    mut rebind: ResMut<RebindState>,
+    mut focus: ResMut<MenuFocus>
+) {
+    let name = match rebind.awaiting {
+        Some(name) => name,
+        None => return
+    };
+
+    let button = keys.get_just_pressed()
+        .find(|k| !is_modifier_key(**k))
+        .map(|k| BindingButton::Key(*k))
+        .or_else(|| mouse.get_just_pressed().next().map(|m| BindingButton::Mouse(*m)));
+
+    let button = match button {
+        Some(b) => b,
+        None => return
+    };
+
+    let binding = Binding {
+        modifiers: held_modifiers(&keys),
+        button
+    };
+
+    info!("Rebound {} to {}", name, binding);
+    *controls.bindings_mut(name).unwrap() = vec![binding];
+
+    rebind.awaiting = None;
+    focus.locked = false;
+
+    write_config(&controls, &settings, &volume, &respawn, &transition, &camera);
+}
+
+fn update_settings_menu_state(
+    controls: Res<PlayerControls>,
+    settings: Res<GameSettings>,
+    volume: Res<VolumeSettings>,
+    rebind: Res<RebindState>,
+    focus: Res<MenuFocus>,
+    mut q: Query<&mut SettingsMenuState>
+) {
+    let labels: Vec<String> = settings_entries()
+        .iter()
+        .map(|e| e.label(&controls, &settings, &volume, &rebind))
+        .collect();
+
+    for mut state in q.iter_mut() {
+        if state.labels != labels || state.focused != focus.index {
+            state.labels = labels.clone();
+            state.focused = focus.index;
+        }
+    }
+}
+
+
+pub fn register_settings_menu_ui(widget_context: &mut KayakRootContext) {
+    widget_context.add_widget_data::<SettingsMenuProps, SettingsMenuState>();
+
+    widget_context.add_widget_system(
+        SettingsMenuProps::default().get_name(),
+        widget_update::<SettingsMenuProps, SettingsMenuState>,
+        settings_menu_render,
+    );
+}
+
+pub fn settings_menu_render(
+    In((widget_context, entity)): In<(KayakWidgetContext, Entity)>,
+    mut commands: Commands,
+    assets: Res<UiAssets>,
+    state_query: Query<&SettingsMenuState>
+) -> bool {
+    let state_entity = widget_context.use_state(
+        &mut commands,
+        entity,
+        SettingsMenuState::default()
+    );
+
+    let state = match state_query.get(state_entity) {
+        Ok(s) => s,
+        _ => return false
+    };
+
+    let button_styles = KStyle {
+        height: StyleProp::Value(Units::Pixels(30.0)),
+        width: StyleProp::Value(Units::Pixels(440.0)),
+        font_size: StyleProp::Value(20.0),
+        line_height: StyleProp::Value(20.0),
+        ..button_style()
+    };
+
+    let title_styles = KStyle {
+        top: StyleProp::Value(Units::Pixels(0.0)),
+        bottom: StyleProp::Value(Units::Pixels(12.0)),
+        font_size: StyleProp::Value(40.0),
+        ..default()
+    };
+
+    let image_styles = KStyle {
+        width: StyleProp::Value(Units::Pixels(540.0)),
+        height: StyleProp::Value(Units::Pixels(680.0)),
+        position_type: StyleProp::Value(KPositionType::SelfDirected),
+        ..default()
+    };
+
+    let background_styles = KStyle {
+        left: StyleProp::Value(Units::Stretch(1.0)),
+        right: StyleProp::Value(Units::Stretch(1.0)),
+        top: StyleProp::Value(Units::Stretch(1.0)),
+        bottom: StyleProp::Value(Units::Stretch(1.0)),
+
+        padding: StyleProp::Value(Edge::all(Units::Stretch(1.0))),
+
+        width: StyleProp::Value(Units::Pixels(540.0)),
+        height: StyleProp::Value(Units::Pixels(680.0)),
+
+        layout_type: StyleProp::Value(LayoutType::Column),
+        row_between: StyleProp::Value(Units::Pixels(4.0)),
+
+        ..default()
+    };
+
+    let entries = settings_entries();
+
+    let buttons = state.labels.clone().into_iter().enumerate().map(|(i, text)| {
+        let entry = entries[i];
+
+        let on_event = OnEvent::new(move |
+            In((event_dispatcher_context, _, event, _entity)): EventInput,
+            mut state: ResMut<State<GameState>>,
+            controls: Res<PlayerControls>,
+            mut settings: ResMut<GameSettings>,
+            mut volume: ResMut<VolumeSettings>,
+            respawn: Res<RespawnSettings>,
+            transition: Res<TransitionSettings>,
+            camera: Res<CameraSettings>,
+            mut rebind: ResMut<RebindState>,
+            mut focus: ResMut<MenuFocus>
+        | {
+            match event.event_type {
+                EventType::Click(_) => {
+                    if rebind.awaiting.is_none() {
+                        focus.index = i;
+
+                        if run_settings_entry(entry, &mut state, &mut settings, &mut volume, &mut rebind, &mut focus) {
+                            write_config(&controls, &settings, &volume, &respawn, &transition, &camera);
+                        }
+                    }
+                }
+                _ => {}
+            }
+
+            (event_dispatcher_context, event)
+        });
+
+        (text, focused_button_style(&button_styles, i == state.focused), on_event)
+    });
+
+    let parent_id = Some(entity);
+
+    rsx! {
+        <BackgroundBundle styles={background_styles.clone()}>
+            <KImageBundle
+                styles={image_styles}
+                image={KImage(assets.pause_bg.clone())}
+            />
+
+            <BackgroundBundle styles={background_styles.clone()}>
+                <TextWidgetBundle
+                    text={TextProps {
+                        content: "Settings".to_string(),
+                        ..default()
+                    }}
+                    styles={title_styles}
+                />
+
+                {buttons.for_each(|(text, styles, on_event)| {
+                    constructor! {
+                        <KButtonBundle
+                            styles={styles}
+                            button={KButton {
+                                text: text.into()
+                            }}
+                            on_event={on_event}
+                        />
+                    }
+                })}
+            </BackgroundBundle>
+        </BackgroundBundle>
+    };
+
+    true
+}
@@ -14,6 +14,8 @@ use crate::ui::shop_button;
 
 
 use bevy_inspector_egui::Inspectable;
+use crate::state::GameState;
+use crate::ui::nav::{focused_button_style, MenuFocus, update_menu_focus};
 use crate::ui::style::button_style;
 
 #[derive(Resource, Inspectable, Debug)]
@@ -137,12 +139,14 @@ impl Default for UiShopStyleData {
 #[derive(Debug, Component, PartialEq, Clone)]
 pub struct ShopMenuState {
     pub skill_levels: PlayerSkillLevels,
+    pub focused: usize,
 }
 
 impl Default for ShopMenuState {
     fn default() -> Self {
         Self {
             skill_levels: PlayerSkillLevels::default(),
+            focused: 0,
         }
     }
 }
@@ -150,6 +154,7 @@ impl Default for ShopMenuState {
 
 fn update_shop_menu_state(
     p: Query<&PlayerSkillLevels>,
+    focus: Res<MenuFocus>,
     mut q: Query<&mut ShopMenuState>
 ) {
     if p.is_empty() || q.is_empty() {
@@ -159,6 +164,64 @@ fn update_shop_menu_state(
     let skill_levels = p.single();
     for mut state in q.iter_mut() {
         state.skill_levels = *skill_levels;
+        state.focused = focus.index;
+    }
+}
+
+fn upgrade_level(skill_levels: &PlayerSkillLevels, item: ShopItem) -> u8 {
+    match item {
+        ShopItem::HealthUpgrade => skill_levels.health_lvl,
+        ShopItem::AmmoUpgrade => skill_levels.ammo_lvl,
+        ShopItem::ShootUpgrade => skill_levels.shoot_lvl,
+        ShopItem::SlashUpgrade => skill_levels.slash_lvl,
+        ShopItem::DashUpgrade => skill_levels.dash_lvl,
+        _ => panic!("Unknown upgrade {:?}!", item)
+    }
+}
+
+// Rows are numbered upgrades first, then items, then the Back button
+fn shop_row_purchase(
+    assets: &ShopAssets,
+    skill_levels: &PlayerSkillLevels,
+    row: usize
+) -> Option<ShopPurchaseEvent> {
+    if let Some(item) = SHOP_CATALOG_UPGRADES.get(row) {
+        let lvl = upgrade_level(skill_levels, *item);
+        if lvl >= 5 {
+            return None;
+        }
+
+        let info = ShopItemInfo::for_item(assets, *item, Some(lvl));
+        return Some(ShopPurchaseEvent { cost: info.cost, order: *item });
+    }
+
+    SHOP_CATALOG_ITEMS.get(row - SHOP_CATALOG_UPGRADES.len()).map(|item| {
+        let info = ShopItemInfo::for_item(assets, *item, None);
+        ShopPurchaseEvent { cost: info.cost, order: *item }
+    })
+}
+
+fn shop_menu_navigate(
+    mut focus: ResMut<MenuFocus>,
+    mut state: ResMut<State<GameState>>,
+    mut purchases: EventWriter<ShopPurchaseEvent>,
+    assets: Res<ShopAssets>,
+    p: Query<&PlayerSkillLevels>
+) {
+    let rows = SHOP_CATALOG_UPGRADES.len() + SHOP_CATALOG_ITEMS.len();
+    focus.count = rows + 1;
+
+    if focus.back || focus.confirmed(rows) {
+        state.pop().unwrap();
+        return;
+    }
+
+    if !focus.confirmed || p.is_empty() {
+        return;
+    }
+
+    if let Some(purchase) = shop_row_purchase(&assets, p.single(), focus.index) {
+        purchases.send(purchase);
     }
 }
 
@@ -200,6 +263,10 @@ pub fn register_shop_menu_ui_systems(app: &mut App) {
         .add_system_set(
             SystemSet::new()
                 .with_system(update_shop_menu_state)
+        )
+        .add_system_set(
+            SystemSet::on_update(GameState::ShopMenu)
+                .with_system(shop_menu_navigate.after(update_menu_focus))
         );
 }
 
@@ -392,21 +459,18 @@ pub fn shop_menu_render(
 
     let click_return_to_gameplay = goto_state_event(StateTransition::Pop);
 
-    let items = SHOP_CATALOG_ITEMS.iter().map(|i| {
+    let focused = state.focused;
+    let n_upgrades = SHOP_CATALOG_UPGRADES.len();
+    let back_row = n_upgrades + SHOP_CATALOG_ITEMS.len();
+
+    let items = SHOP_CATALOG_ITEMS.iter().enumerate().map(|(row, i)| {
         let info = ShopItemInfo::for_item(&assets, *i, None);
         let purchase = ShopPurchaseEvent { cost: info.cost, order: *i };
-        (info.icon, format!("${:?}", info.cost), info.name, Some(purchase))
+        (info.icon, format!("${:?}", info.cost), info.name, Some(purchase), n_upgrades + row == focused)
     });
 
-    let upgrades = SHOP_CATALOG_UPGRADES.iter().filter_map(|i| {
-        let lvl = match i {
-            ShopItem::HealthUpgrade => state.skill_levels.health_lvl,
-            ShopItem::AmmoUpgrade => state.skill_levels.ammo_lvl,
-            ShopItem::ShootUpgrade => state.skill_levels.shoot_lvl,
-            ShopItem::SlashUpgrade => state.skill_levels.slash_lvl,
-            ShopItem::DashUpgrade => state.skill_levels.dash_lvl,
-            _ => panic!("Unknown upgrade {:?}!", i)
-        };
+    let upgrades = SHOP_CATALOG_UPGRADES.iter().enumerate().filter_map(|(row, i)| {
+        let lvl = upgrade_level(&state.skill_levels, *i);
 
         let info = ShopItemInfo::for_item(&assets, *i, Some(lvl));
         let purchase = ShopPurchaseEvent { cost: info.cost, order: *i };
@@ -416,14 +480,16 @@ pub fn shop_menu_render(
                 info.icon,
                 format!("${:?}", info.cost),
                 format!("{} lv. {}", info.name, lvl + 1),
-                Some(purchase)
+                Some(purchase),
+                row == focused
             ))
         } else {
             Some((
                 info.icon,
                 "".to_string(),
                 format!("{} MAX", info.name),
-                None
+                None,
+                row == focused
             ))
         }
     });
@@ -453,7 +519,7 @@ pub fn shop_menu_render(
                         }}/>
 
                         <BackgroundBundle styles={items_styles.clone()}>
-                        {upgrades.for_each(|(icon, cost, content, purchase)| {
+                        {upgrades.for_each(|(icon, cost, content, purchase, focused)| {
                             constructor! {
                             <BackgroundBundle styles={sale_styles.clone()}>
 
@@ -485,7 +551,8 @@ pub fn shop_menu_render(
                                 <BackgroundBundle styles={purchase_container_styles.clone()}>
                                     <shop_button::ShopButtonBundle
                                         props={shop_button::ShopButtonProps {
-                                            purchase
+                                            purchase,
+                                            focused
                                         }}
                                     />
                                 </BackgroundBundle>
@@ -510,7 +577,7 @@ pub fn shop_menu_render(
                         }}/>
 
                         <BackgroundBundle styles={items_styles.clone()}>
-                        {items.for_each(|(icon, cost, content, purchase)| {
+                        {items.for_each(|(icon, cost, content, purchase, focused)| {
                             constructor! {
                             <BackgroundBundle styles={sale_styles.clone()}>
 
@@ -542,7 +609,8 @@ pub fn shop_menu_render(
                                 <BackgroundBundle styles={purchase_container_styles.clone()}>
                                     <shop_button::ShopButtonBundle
                                         props={shop_button::ShopButtonProps {
-                                            purchase
+                                            purchase,
+                                            focused
                                         }}
                                     />
                                 </BackgroundBundle>
@@ -566,7 +634,7 @@ pub fn shop_menu_render(
                         text: "Back".into(),
                         ..default()
                     }}
-                    styles={button_styles}
+                    styles={focused_button_style(&button_styles, focused == back_row)}
                     on_event={click_return_to_gameplay}
                 />
             </BackgroundBundle>
@@ -8,7 +8,8 @@ use crate::ui::EventInput;
 
 #[derive(Component, Clone, PartialEq, Default)]
 pub struct ShopButtonProps {
-    pub purchase: Option<ShopPurchaseEvent>
+    pub purchase: Option<ShopPurchaseEvent>,
+    pub focused: bool
 }
 
 impl Widget for ShopButtonProps {
@@ -126,6 +127,7 @@ pub fn shop_button_render(
         assets.blank.clone()
     } else {
         match state {
+            ShopButtonState::Normal if props.focused => assets.buy_hover.clone(),
             ShopButtonState::Normal => assets.buy.clone(),
             ShopButtonState::Hover => assets.buy_hover.clone(),
             ShopButtonState::Press => assets.buy_pressed.clone()
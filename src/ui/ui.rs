@@ -4,7 +4,7 @@ use bevy_debug_text_overlay::screen_print;
 
 use kayak_ui::prelude::*;
 use kayak_ui::widgets::*;
-use crate::ui::{menu, pause, shop, win, lose};
+use crate::ui::{menu, pause, shop, win, lose, settings, leaderboard};
 
 
 #[derive(Debug, Component, PartialEq, Clone)]
@@ -128,6 +128,18 @@ fn ui_render(
                     <menu::MainMenuBundle/>
                 }
             }}
+
+            {if state.state == Some(GameState::SettingsMenu) {
+                constructor! {
+                    <settings::SettingsMenuBundle/>
+                }
+            }}
+
+            {if state.state == Some(GameState::LeaderboardMenu) {
+                constructor! {
+                    <leaderboard::LeaderboardMenuBundle/>
+                }
+            }}
         </ElementBundle>
     };
 
@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use shroom_boom::coin::drops::CoinHolder;
+use shroom_boom::combat::Health;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::input::InputAction;
+use shroom_boom::level::checkpoint::{Checkpoint, CheckpointState, RespawnPenalty, RespawnSettings};
+use shroom_boom::player::Player;
+use shroom_boom::state::GameState;
+
+fn player_pos(sim: &mut HeadlessApp) -> Vec2 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Transform>(player).unwrap().translation.truncate()
+}
+
+fn grounded(sim: &mut HeadlessApp) -> bool {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Player>(player).unwrap().grounded
+}
+
+fn coins(sim: &mut HeadlessApp) -> i32 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<CoinHolder>(player).unwrap().total_value
+}
+
+fn set_coins(sim: &mut HeadlessApp, coins: i32) {
+    let player = sim.player().expect("no player");
+    sim.world_mut().get_mut::<CoinHolder>(player).unwrap().total_value = coins;
+}
+
+// Holds `dir`, hopping whenever the player runs into a wall
+fn run_until_stopped(sim: &mut HeadlessApp, dir: InputAction, max_frames: u32, mut done: impl FnMut(&mut HeadlessApp) -> bool) -> bool {
+    let mut last_x = player_pos(sim).x;
+
+    sim.hold(dir);
+
+    let arrived = sim.run_until(max_frames, |sim| {
+        if done(sim) {
+            return true;
+        }
+
+        let x = player_pos(sim).x;
+        if (x - last_x).abs() < 0.5 && grounded(sim) {
+            sim.tap(InputAction::Jump);
+        }
+        last_x = x;
+
+        false
+    });
+
+    sim.release(dir);
+    arrived
+}
+
+fn checkpoint_iid(sim: &HeadlessApp) -> Option<String> {
+    sim.world()
+        .resource::<CheckpointState>()
+        .snapshot
+        .as_ref()
+        .and_then(|s| s.iid.clone())
+}
+
+#[test]
+fn dying_goes_back_to_the_last_checkpoint() {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level("Level_0"));
+    assert!(sim.run_until(120, grounded), "never landed");
+
+    // lives instead of coins, so the snapshot's coins come back as they were
+    sim.world_mut().insert_resource(RespawnSettings {
+        penalty: RespawnPenalty::Lives,
+        ..default()
+    });
+
+    set_coins(&mut sim, 30);
+
+    // Level_0's checkpoint is at the top of the shaft on the right
+    assert!(
+        run_until_stopped(&mut sim, InputAction::RunRight, 600, |sim| checkpoint_iid(sim).is_some()),
+        "never reached the checkpoint"
+    );
+
+    let iid = checkpoint_iid(&sim).unwrap();
+    let spawn_pos = {
+        let mut q = sim.world_mut().query::<&Checkpoint>();
+        q.iter(sim.world()).find(|c| c.iid == iid).unwrap().spawn_pos
+    };
+
+    // spent after the checkpoint, gone again on respawn
+    set_coins(&mut sim, 80);
+
+    let player = sim.player().unwrap();
+    sim.world_mut().get_mut::<Health>(player).unwrap().hp = 0;
+
+    let respawned = sim.run_until(600, |sim| {
+        sim.state() == GameState::Gameplay
+            && !sim.world().resource::<CheckpointState>().respawning
+            && sim.player().map_or(false, |p| p != player)
+    });
+    assert!(respawned, "never respawned");
+
+    assert_eq!(coins(&mut sim), 30);
+    assert_eq!(player_pos(&mut sim).x, spawn_pos.x);
+    assert_eq!(sim.world().resource::<CheckpointState>().lives_left, 2);
+    assert_eq!(checkpoint_iid(&sim), Some(iid));
+}
@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use rand::Rng;
+use shroom_boom::coin::coin::Coin;
+use shroom_boom::coin::drops::CoinHolder;
+use shroom_boom::combat::Health;
+use shroom_boom::enemies::Enemy;
+use shroom_boom::enemies::spawner::EnemyType;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::input::InputAction;
+use shroom_boom::player::Player;
+use shroom_boom::rng::{GameRng, RngStream};
+
+fn player_x(sim: &mut HeadlessApp) -> f32 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Transform>(player).unwrap().translation.x
+}
+
+fn grounded(sim: &mut HeadlessApp) -> bool {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Player>(player).unwrap().grounded
+}
+
+// Down the shaft on the right of Level_0 and into the tumbleweed and
+// dandelion, so enemies spawn with random stats, walk and fly around, and
+// drop coins when they die
+fn play(seed: u64) -> HeadlessApp {
+    let mut sim = HeadlessApp::with_seed(seed);
+    assert!(sim.start_level("Level_0"));
+    assert!(sim.run_until(120, grounded), "never landed");
+
+    sim.hold(InputAction::RunRight);
+    sim.run_until(600, |sim| player_x(sim) > 59.0 * 32.0);
+    sim.release_all();
+
+    sim.hold(InputAction::Crouch);
+    sim.step_frames(60);
+    sim.release_all();
+
+    for i in 0..900 {
+        if i % 300 < 60 {
+            sim.hold(InputAction::RunLeft);
+        } else {
+            sim.release(InputAction::RunLeft);
+        }
+
+        match i % 12 {
+            0 => sim.tap(InputAction::Shoot),
+            6 => sim.tap(InputAction::Slash),
+            _ => sim.step()
+        }
+    }
+
+    sim
+}
+
+// Everything a run could end up differing in, written out so a failure shows
+// where
+fn describe(sim: &mut HeadlessApp) -> Vec<String> {
+    let mut out = vec![format!("score {}", sim.score())];
+
+    let world = sim.world_mut();
+
+    for (tf, health, coins) in world.query_filtered::<(&Transform, &Health, &CoinHolder), With<Player>>().iter(world) {
+        out.push(format!("player at {} with {} hp and {} coins", tf.translation, health.hp, coins.total_value));
+    }
+
+    let mut enemies: Vec<String> = world
+        .query_filtered::<(&EnemyType, &Transform, &Health), With<Enemy>>()
+        .iter(world)
+        .map(|(ty, tf, health)| format!("{} at {} with {}/{} hp", ty.name(), tf.translation, health.hp, health.max_hp))
+        .collect();
+
+    let mut coins: Vec<String> = world
+        .query_filtered::<&Transform, With<Coin>>()
+        .iter(world)
+        .map(|tf| format!("coin at {}", tf.translation))
+        .collect();
+
+    enemies.sort();
+    coins.sort();
+    out.extend(enemies);
+    out.extend(coins);
+
+    let mut rng = world.resource_mut::<GameRng>();
+    for stream in [RngStream::Enemies, RngStream::Coins, RngStream::Pathfinding, RngStream::Boss, RngStream::Effects] {
+        out.push(format!("next {:?} draw {}", stream, rng.stream(stream).gen::<u64>()));
+    }
+
+    out
+}
+
+#[test]
+fn same_seed_and_inputs_make_the_same_run() {
+    let first = describe(&mut play(7));
+    let second = describe(&mut play(7));
+
+    assert_eq!(first, second);
+}
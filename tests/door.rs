@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::level::door::{DoorCondition, DoorGroup, DoorTile, DoorTrigger, DoorTriggerKind};
+
+// Loads `level` and checks its door group is closed, waits on a `kind`
+// trigger that was spawned, and covers `door_count` door tiles
+fn assert_door_group(level: &str, kind: DoorTriggerKind, door_count: usize) {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level(level));
+    sim.step_frames(5);
+
+    let world = sim.world_mut();
+
+    let (group, iid) = world
+        .query::<(Entity, &DoorGroup)>()
+        .iter(world)
+        .find_map(|(e, group)| match &group.condition {
+            DoorCondition::Trigger(k, iid) if *k == kind => {
+                assert!(!group.open, "{}'s doors start open", level);
+                Some((e, iid.clone()))
+            }
+            _ => None
+        })
+        .unwrap_or_else(|| panic!("{} has no {:?} door group", level, kind));
+
+    let trigger = world
+        .query::<&DoorTrigger>()
+        .iter(world)
+        .find(|trigger| trigger.iid == iid)
+        .unwrap_or_else(|| panic!("{}'s {:?} wasn't spawned", level, kind));
+
+    assert_eq!(trigger.kind, kind);
+    assert!(!trigger.active);
+
+    let doors = world
+        .query::<&DoorTile>()
+        .iter(world)
+        .filter(|door| door.group == group)
+        .count();
+
+    assert_eq!(doors, door_count, "{}'s {:?} door group covers the wrong doors", level, kind);
+}
+
+#[test]
+fn level_4_has_a_key_door() {
+    assert_door_group("Level_4", DoorTriggerKind::Key, 4);
+}
+
+#[test]
+fn level_5_has_a_switch_door() {
+    assert_door_group("Level_5", DoorTriggerKind::Switch, 4);
+}
+
+#[test]
+fn level_7_has_a_lever_door() {
+    assert_door_group("Level_7", DoorTriggerKind::Lever, 4);
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use shroom_boom::combat::Health;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::input::InputAction;
+use shroom_boom::level::hazard::HazardKind;
+use shroom_boom::level::{coord, LevelInfo};
+use shroom_boom::player::Player;
+
+fn player_hp(sim: &mut HeadlessApp) -> i32 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Health>(player).unwrap().hp
+}
+
+fn grounded(sim: &mut HeadlessApp) -> bool {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Player>(player).unwrap().grounded
+}
+
+fn player_cell(sim: &mut HeadlessApp) -> IVec2 {
+    let player = sim.player().expect("no player");
+    let pos = sim.world().get::<Transform>(player).unwrap().translation.truncate();
+    coord::world_to_grid(pos, sim.world().resource::<LevelInfo>().grid_size)
+}
+
+// Level_5 starts between spikes in the corner to the right and a pit to the
+// left
+fn start_level_5() -> HeadlessApp {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level("Level_5"));
+    assert!(sim.run_until(120, grounded), "never landed");
+
+    sim
+}
+
+#[test]
+fn walking_onto_spikes_hurts() {
+    let mut sim = start_level_5();
+    let hp = player_hp(&mut sim);
+
+    sim.hold(InputAction::RunRight);
+
+    assert!(sim.run_until(120, |sim| player_hp(sim) < hp), "never took damage from the spikes");
+    assert_eq!(player_hp(&mut sim), hp - HazardKind::Spikes.damage());
+}
+
+#[test]
+fn falling_into_a_pit_goes_back_to_safe_ground() {
+    let mut sim = start_level_5();
+    let hp = player_hp(&mut sim);
+
+    sim.hold(InputAction::RunLeft);
+
+    assert!(sim.run_until(120, |sim| player_hp(sim) < hp), "never fell into the pit");
+    sim.release_all();
+
+    assert_eq!(player_hp(&mut sim), hp - HazardKind::Pit.damage());
+
+    // the pit is rows 11 and 12, the floor around it is row 11
+    assert!(sim.run_until(60, grounded), "never landed after the pit");
+    assert!(player_cell(&mut sim).y < 11, "still in the pit: {:?}", player_cell(&mut sim));
+}
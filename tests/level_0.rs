@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use shroom_boom::enemies::Enemy;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::input::InputAction;
+use shroom_boom::level::coord;
+use shroom_boom::level::LevelInfo;
+use shroom_boom::player::Player;
+
+fn player_pos(sim: &mut HeadlessApp) -> Vec2 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Transform>(player).unwrap().translation.truncate()
+}
+
+fn grounded(sim: &mut HeadlessApp) -> bool {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Player>(player).unwrap().grounded
+}
+
+fn player_cell(sim: &mut HeadlessApp) -> IVec2 {
+    let pos = player_pos(sim);
+    coord::world_to_grid(pos, sim.world().resource::<LevelInfo>().grid_size)
+}
+
+fn enemies_left(sim: &mut HeadlessApp) -> usize {
+    let mut q = sim.world_mut().query_filtered::<(), With<Enemy>>();
+    q.iter(sim.world()).count()
+}
+
+// Holds `dir` until the player gets to `col`, hopping over anything it runs
+// into on the way
+fn run_to_column(sim: &mut HeadlessApp, dir: InputAction, col: i32) -> bool {
+    let mut last_x = player_pos(sim).x;
+    let mut stuck = 0;
+
+    sim.hold(dir);
+
+    let arrived = sim.run_until(600, |sim| {
+        let cell = player_cell(sim);
+        if (dir == InputAction::RunRight && cell.x >= col) || (dir == InputAction::RunLeft && cell.x <= col) {
+            return true;
+        }
+
+        let x = player_pos(sim).x;
+        stuck = if (x - last_x).abs() < 0.5 { stuck + 1 } else { 0 };
+        last_x = x;
+
+        if stuck > 4 && grounded(sim) {
+            sim.tap(InputAction::Jump);
+            stuck = 0;
+        }
+
+        false
+    });
+
+    sim.release(dir);
+    arrived
+}
+
+// Crouches through the one way platform under the player
+fn drop_down(sim: &mut HeadlessApp) -> bool {
+    let row = player_cell(sim).y;
+
+    sim.hold(InputAction::Crouch);
+    let landed = sim.run_until(240, |sim| player_cell(sim).y > row && grounded(sim));
+    sim.release(InputAction::Crouch);
+
+    landed
+}
+
+// Shoots and slashes at whatever is closest until only `left` enemies remain
+fn fight_until(sim: &mut HeadlessApp, left: usize) -> bool {
+    for i in 0..900 {
+        if enemies_left(sim) <= left {
+            return true;
+        }
+
+        match i % 12 {
+            0 => sim.tap(InputAction::Shoot),
+            6 => sim.tap(InputAction::Slash),
+            _ => sim.step()
+        }
+    }
+
+    false
+}
+
+#[test]
+fn starts_at_the_entry_point_on_the_ground() {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level("Level_0"));
+
+    assert_eq!(sim.current_level(), "Level_0");
+    assert!(sim.run_until(120, grounded), "never landed");
+}
+
+#[test]
+fn runs_and_jumps() {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level("Level_0"));
+    assert!(sim.run_until(120, grounded), "never landed");
+
+    let start = player_pos(&mut sim);
+
+    sim.hold(InputAction::RunRight);
+    sim.step_frames(20);
+    sim.release_all();
+
+    let after_run = player_pos(&mut sim);
+    assert!(after_run.x > start.x, "didn't move right: {:?} to {:?}", start, after_run);
+
+    sim.tap(InputAction::Jump);
+    sim.step_frames(5);
+
+    assert!(player_pos(&mut sim).y > after_run.y, "didn't leave the ground");
+    assert!(sim.run_until(240, grounded), "never came back down");
+}
+
+#[test]
+fn exit_leads_to_level_1() {
+    let mut sim = HeadlessApp::with_seed(1);
+    assert!(sim.start_level("Level_0"));
+    assert!(sim.run_until(120, grounded), "never landed");
+
+    // along the top and down the shaft on the right
+    assert!(run_to_column(&mut sim, InputAction::RunRight, 59), "never reached the shaft");
+    assert!(drop_down(&mut sim), "never dropped down the shaft");
+
+    // the tumbleweed waits on the ledge at the bottom
+    assert!(run_to_column(&mut sim, InputAction::RunLeft, 50), "never reached the tumbleweed");
+    assert!(fight_until(&mut sim, 2), "never killed the tumbleweed");
+
+    // and the dandelion comes down to the floor of the next room
+    assert!(run_to_column(&mut sim, InputAction::RunLeft, 22), "never reached the dandelion");
+    assert!(sim.run_until(120, grounded), "never landed by the dandelion");
+    assert!(fight_until(&mut sim, 1), "never killed the dandelion");
+
+    // down the shaft on the left into the bottom room
+    assert!(run_to_column(&mut sim, InputAction::RunLeft, 5), "never reached the left shaft");
+    assert!(drop_down(&mut sim), "never dropped down the left shaft");
+    assert!(run_to_column(&mut sim, InputAction::RunRight, 7), "never got over the bottom room");
+    assert!(drop_down(&mut sim), "never dropped into the bottom room");
+    assert!(fight_until(&mut sim, 0), "never killed the pumpkin");
+
+    // the vines only open once every enemy is dead
+    sim.hold(InputAction::RunRight);
+
+    assert!(sim.run_until(900, |sim| sim.current_level() == "Level_1"), "never reached Level_1");
+    assert!(sim.run_until(600, |sim| sim.player().is_some() && grounded(sim)), "never landed in Level_1");
+}
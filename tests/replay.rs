@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use shroom_boom::headless::HeadlessApp;
+use shroom_boom::input::InputAction;
+use shroom_boom::replay::{Replay, ReplayRecorder};
+
+fn player_pos(sim: &mut HeadlessApp) -> Vec3 {
+    let player = sim.player().expect("no player");
+    sim.world().get::<Transform>(player).unwrap().translation
+}
+
+// Runs into the first room of Level_0, jumping and attacking on the way
+fn record_run(seed: u64) -> (Replay, u32, Vec3) {
+    let mut sim = HeadlessApp::with_seed(seed);
+    sim.world_mut().resource_mut::<ReplayRecorder>().enabled = true;
+
+    assert!(sim.start_level("Level_0"));
+
+    sim.hold(InputAction::RunRight);
+    sim.step_frames(90);
+
+    sim.tap(InputAction::Jump);
+    sim.step_frames(30);
+
+    sim.tap(InputAction::Slash);
+    sim.step_frames(20);
+
+    sim.tap(InputAction::Shoot);
+    sim.release_all();
+    sim.step_frames(60);
+
+    let replay = sim.world()
+        .resource::<ReplayRecorder>()
+        .recording
+        .clone()
+        .expect("nothing was recorded");
+
+    (replay, sim.score(), player_pos(&mut sim))
+}
+
+#[test]
+fn replays_survive_encoding() {
+    let (replay, _, _) = record_run(42);
+
+    assert!(!replay.frames.is_empty());
+    assert_eq!(Replay::decode(&replay.encode()), Ok(replay));
+}
+
+#[test]
+fn playing_a_replay_back_ends_the_same() {
+    let (replay, score, pos) = record_run(42);
+
+    // a different seed to start with, the replay brings its own
+    let mut sim = HeadlessApp::with_seed(7);
+    assert!(sim.play_replay(&replay), "replay stopped early");
+
+    assert_eq!(sim.score(), score);
+    assert_eq!(player_pos(&mut sim), pos);
+}